@@ -1,6 +1,5 @@
 import 'dart:async';
 
-import 'package:acter/common/utils/utils.dart';
 import 'package:acter/features/events/providers/event_providers.dart';
 import 'package:acter/features/home/providers/client_providers.dart';
 import 'package:acter_flutter_sdk/acter_flutter_sdk_ffi.dart' show Client;
@@ -12,7 +11,11 @@ class AsyncParticipantsNotifier
 
   Future<List<String>> _getParticipants(Client client, String calEvtId) async {
     final calEvent = await ref.watch(calendarEventProvider(calEvtId).future);
-    return asDartStringList(await calEvent.participants());
+    final participants = await calEvent.participants();
+    return participants
+        .toList()
+        .map((rsvp) => rsvp.sender().toString())
+        .toList();
   }
 
   @override
@@ -20,6 +20,7 @@ lazy_static! {
 
 mod account;
 mod activities;
+mod agenda;
 mod attachments;
 mod auth;
 mod backup;
@@ -32,9 +33,12 @@ mod common;
 mod convo;
 mod deep_linking;
 mod device;
+mod documents;
+mod history;
 mod invitations;
 mod news;
 mod pins;
+mod polls;
 mod profile;
 mod push;
 mod reactions;
@@ -46,6 +50,7 @@ mod spaces;
 mod stories;
 mod super_invites;
 mod tasks;
+mod time_tracking;
 mod timeline;
 mod typing;
 mod utils;
@@ -56,9 +61,12 @@ pub mod read_receipts;
 pub use account::{Account, ExternalId, ThreePidEmailTokenResponse};
 pub use acter_core::{
     events::{
-        calendar::EventLocationInfo, news::NewsContent, stories::StoryContent, Category,
-        CategoryBuilder, Colorize, ColorizeBuilder, Display, DisplayBuilder, ObjRefBuilder,
-        UtcDateTime,
+        calendar::{EventLocationInfo, EventReminder},
+        news::NewsContent,
+        stories::StoryContent,
+        tasks::{ChecklistItem, TaskState},
+        Category, CategoryBuilder, Colorize, ColorizeBuilder, Display, DisplayBuilder,
+        ObjRefBuilder, UtcDateTime,
     },
     models::{
         status::{
@@ -69,10 +77,11 @@ pub use acter_core::{
             RoomServerAclContent, RoomTombstoneContent, RoomTopicContent, SpaceChildContent,
             SpaceParentContent,
         },
-        ActerModel, Tag, TextMessageContent,
+        ActerModel, CalendarEventOccurrence, DueReminder, Tag, TextMessageContent,
     },
 };
 pub use activities::{Activities, Activity, ActivityObject};
+pub use agenda::{AgendaDiff, AgendaItem};
 pub use attachments::{Attachment, AttachmentDraft, AttachmentsManager};
 pub use auth::{
     destroy_local_data, guest_client, login_new_client, login_with_token, register_with_token,
@@ -89,7 +98,7 @@ pub use bookmarks::Bookmarks;
 pub use calendar_events::{CalendarEvent, CalendarEventDraft, CalendarEventUpdateBuilder};
 pub use categories::{Categories, CategoriesBuilder};
 pub use client::{Client, ClientStateBuilder, HistoryLoadState, LocalUrlPreview, SyncState};
-pub use comments::{Comment, CommentDraft, CommentUpdateBuilder, CommentsManager};
+pub use comments::{Comment, CommentDraft, CommentThread, CommentUpdateBuilder, CommentsManager};
 pub use common::{
     duration_from_secs, new_colorize_builder, new_display_builder, new_obj_ref_builder,
     new_thumb_size, ComposeDraft, DeviceRecord, MediaSource, OptionBuffer, OptionComposeDraft,
@@ -101,9 +110,12 @@ pub use convo::{
 pub use core::time::Duration as EfkDuration;
 pub use deep_linking::{new_link_ref_details, ObjRef, RefDetails};
 pub use device::DeviceEvent;
+pub use documents::{Document, DocumentDraft, DocumentEditDraft, DocumentRevision};
+pub use history::{FieldChange, ObjectHistory, ObjectHistoryEntry};
 pub use invitations::{InvitationsManager, ObjectInvitationsManager, RoomInvitation};
 pub use news::{NewsEntry, NewsEntryDraft, NewsEntryUpdateBuilder, NewsSlide, NewsSlideDraft};
 pub use pins::{Pin as ActerPin, PinDraft, PinUpdateBuilder};
+pub use polls::{Poll, PollDraft, PollUpdateBuilder, PollVoteDraft, PollsManager};
 pub use profile::UserProfile;
 pub use push::{
     NotificationItem, NotificationRoom, NotificationSender, NotificationSettings, Pusher,
@@ -132,8 +144,10 @@ pub use super_invites::{
     SuperInviteInfo, SuperInviteToken, SuperInvites, SuperInvitesTokenUpdateBuilder,
 };
 pub use tasks::{
-    Task, TaskDraft, TaskList, TaskListDraft, TaskListUpdateBuilder, TaskUpdateBuilder,
+    Task, TaskDraft, TaskList, TaskListDraft, TaskListUpdateBuilder, TaskStateGroup,
+    TaskUpdateBuilder,
 };
+pub use time_tracking::{TimeLog, TimeLogDraft, TimeTrackingManager};
 pub use timeline::{
     EventSendState, MsgContent, MsgDraft, TimelineEventItem, TimelineItem, TimelineItemDiff,
     TimelineStream, TimelineVirtualItem,
@@ -37,7 +37,23 @@ impl Activity {
         self.inner.content()
     }
 
+    pub fn follow_ups(&self) -> Vec<Activity> {
+        self.inner
+            .follow_ups()
+            .iter()
+            .cloned()
+            .map(|inner| Activity {
+                inner,
+                client: self.client.clone(),
+            })
+            .collect()
+    }
+
+    /// empty if the sender must be hidden, e.g. for votes on anonymous polls
     pub fn sender_id_str(&self) -> String {
+        if self.inner.hides_sender() {
+            return String::new();
+        }
         self.inner.event_meta().sender.to_string()
     }
 
@@ -64,7 +80,8 @@ impl Activity {
             ActivityContent::DescriptionChange { content, .. } => {
                 content.as_ref().map(MsgContent::from)
             }
-            ActivityContent::Comment { content, .. } => Some(MsgContent::from(content)),
+            ActivityContent::Comment { content, .. }
+            | ActivityContent::CommentReply { content, .. } => Some(MsgContent::from(content)),
             ActivityContent::Boost {
                 first_slide: Some(first_slide),
                 ..
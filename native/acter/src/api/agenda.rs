@@ -0,0 +1,329 @@
+use acter_core::{
+    events::UtcDateTime,
+    models::{self, agenda_items, AgendaSource},
+    referencing::ExecuteReference,
+};
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use futures::stream::StreamExt;
+use matrix_sdk::room::Room;
+use matrix_sdk_base::{
+    ruma::{OwnedEventId, OwnedRoomId},
+    RoomState,
+};
+use matrix_sdk_ui::eyeball_im::VectorDiff;
+use std::collections::HashMap;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamMap};
+use tracing::warn;
+
+use super::{
+    calendar_events::CalendarEvent,
+    client::Client,
+    news::NewsEntry,
+    pins::Pin,
+    tasks::Task,
+    utils::{remap_for_diff, ApiVectorDiff},
+    RUNTIME,
+};
+
+#[derive(Clone, Debug)]
+pub struct AgendaItem {
+    client: Client,
+    room: Room,
+    inner: models::AgendaItem,
+}
+
+impl AgendaItem {
+    pub fn item_type(&self) -> String {
+        self.inner.item_type().to_owned()
+    }
+
+    pub fn utc_at(&self) -> UtcDateTime {
+        self.inner.utc_at()
+    }
+
+    pub fn at_rfc3339(&self) -> String {
+        self.inner.utc_at().to_rfc3339()
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.inner.is_all_day()
+    }
+
+    pub fn title(&self) -> String {
+        self.inner.title()
+    }
+
+    pub fn event_id(&self) -> OwnedEventId {
+        self.inner.event_id().to_owned()
+    }
+
+    pub fn event_id_str(&self) -> String {
+        self.inner.event_id().to_string()
+    }
+
+    pub fn room_id(&self) -> OwnedRoomId {
+        self.inner.room_id().to_owned()
+    }
+
+    pub fn room_id_str(&self) -> String {
+        self.inner.room_id().to_string()
+    }
+
+    pub fn key(&self) -> String {
+        self.inner.key()
+    }
+
+    pub fn rsvp_status(&self) -> Option<String> {
+        match &self.inner {
+            models::AgendaItem::CalendarEvent { rsvp, .. } => Some(rsvp.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn calendar_event(&self) -> Option<CalendarEvent> {
+        match &self.inner {
+            models::AgendaItem::CalendarEvent { event, .. } => Some(CalendarEvent::new(
+                self.client.clone(),
+                self.room.clone(),
+                event.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn occurrence(&self) -> Option<models::CalendarEventOccurrence> {
+        match &self.inner {
+            models::AgendaItem::CalendarEvent { occurrence, .. } => Some(occurrence.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn task(&self) -> Option<Task> {
+        match &self.inner {
+            models::AgendaItem::Task { task, .. } => Some(Task::new(
+                self.client.clone(),
+                self.room.clone(),
+                task.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn pin(&self) -> Option<Pin> {
+        match &self.inner {
+            models::AgendaItem::Pin(pin) => Some(Pin::new(
+                self.client.clone(),
+                self.room.clone(),
+                pin.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn news_entry(&self) -> Option<NewsEntry> {
+        match &self.inner {
+            models::AgendaItem::NewsEntry(news_entry) => Some(NewsEntry::new(
+                self.client.clone(),
+                self.room.clone(),
+                news_entry.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn has_changed_from(&self, other: &AgendaItem) -> bool {
+        serde_json::to_value(&self.inner).ok() != serde_json::to_value(&other.inner).ok()
+    }
+}
+
+pub type AgendaDiff = ApiVectorDiff<AgendaItem>;
+
+/// The diffs turning `items` into `new_items`, applied to `items` as we go.
+/// Items are matched by their key, so moved items are removed and inserted
+/// again while changed items are set in place.
+fn agenda_diffs(
+    items: &mut Vec<AgendaItem>,
+    new_items: Vec<AgendaItem>,
+) -> Vec<VectorDiff<AgendaItem>> {
+    let mut diffs = vec![];
+    for index in (0..items.len()).rev() {
+        let key = items[index].key();
+        if !new_items.iter().any(|item| item.key() == key) {
+            items.remove(index);
+            diffs.push(VectorDiff::Remove { index });
+        }
+    }
+    for (index, item) in new_items.into_iter().enumerate() {
+        let key = item.key();
+        if items.get(index).is_some_and(|current| current.key() == key) {
+            if item.has_changed_from(&items[index]) {
+                items[index] = item.clone();
+                diffs.push(VectorDiff::Set { index, value: item });
+            }
+            continue;
+        }
+        if let Some(pos) = items.iter().skip(index).position(|c| c.key() == key) {
+            items.remove(index + pos);
+            diffs.push(VectorDiff::Remove { index: index + pos });
+        }
+        items.insert(index, item.clone());
+        diffs.push(VectorDiff::Insert { index, value: item });
+    }
+    diffs
+}
+
+/// The changes the agenda stream listens to, by the source they refresh
+type AgendaChanges = StreamMap<(AgendaSource, ExecuteReference), BroadcastStream<()>>;
+
+/// The items of all sources, sorted chronologically
+fn merged_agenda(per_source: &HashMap<AgendaSource, Vec<AgendaItem>>) -> Vec<AgendaItem> {
+    let mut items: Vec<AgendaItem> = per_source.values().flatten().cloned().collect();
+    items.sort_by_key(|item| (item.utc_at(), item.key()));
+    items
+}
+
+impl Client {
+    fn agenda_in_joined_rooms(&self, items: Vec<models::AgendaItem>) -> Vec<AgendaItem> {
+        items
+            .into_iter()
+            .filter_map(|inner| {
+                let room = self.room_by_id_typed(inner.room_id()).ok()?;
+                matches!(room.state(), RoomState::Joined).then(|| AgendaItem {
+                    client: self.clone(),
+                    room,
+                    inner,
+                })
+            })
+            .collect()
+    }
+
+    async fn agenda_typed(&self, from: UtcDateTime, to: UtcDateTime) -> Result<Vec<AgendaItem>> {
+        let client = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let user_id = client.user_id()?;
+                let items = agenda_items(client.store(), &user_id, from, to, &Local).await?;
+                Ok(client.agenda_in_joined_rooms(items))
+            })
+            .await?
+    }
+
+    /// The items of `source` on the agenda, and the objects of that source
+    /// an update might move onto or off the agenda
+    async fn agenda_source_typed(
+        &self,
+        source: AgendaSource,
+        from: UtcDateTime,
+        to: UtcDateTime,
+    ) -> Result<(Vec<AgendaItem>, Vec<OwnedEventId>)> {
+        let client = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let user_id = client.user_id()?;
+                let store = client.store();
+                let object_ids = source
+                    .models(store)
+                    .await?
+                    .iter()
+                    .map(|mdl| mdl.event_id().to_owned())
+                    .collect();
+                let items = source.items(store, &user_id, from, to, &Local).await?;
+                Ok((client.agenda_in_joined_rooms(items), object_ids))
+            })
+            .await?
+    }
+
+    /// Reload the items of `source` and follow updates of exactly the
+    /// objects it has now
+    async fn refresh_agenda_source(
+        &self,
+        source: AgendaSource,
+        from: UtcDateTime,
+        to: UtcDateTime,
+        changes: &mut AgendaChanges,
+    ) -> Result<Vec<AgendaItem>> {
+        let (items, object_ids) = self.agenda_source_typed(source, from, to).await?;
+        let stale: Vec<_> = changes
+            .keys()
+            .filter(|(s, key)| {
+                *s == source
+                    && matches!(key, ExecuteReference::Model(id) if !object_ids.contains(id))
+            })
+            .cloned()
+            .collect();
+        for key in stale {
+            changes.remove(&key);
+        }
+        for object_id in object_ids {
+            let key = (source, ExecuteReference::Model(object_id.clone()));
+            if !changes.contains_key(&key) {
+                let receiver = self.subscribe(object_id);
+                changes.insert(key, BroadcastStream::new(receiver));
+            }
+        }
+        Ok(items)
+    }
+
+    pub async fn agenda(
+        &self,
+        from_rfc3339: String,
+        to_rfc3339: String,
+    ) -> Result<Vec<AgendaItem>> {
+        let from: UtcDateTime = DateTime::parse_from_rfc3339(&from_rfc3339)?.into();
+        let to: UtcDateTime = DateTime::parse_from_rfc3339(&to_rfc3339)?.into();
+        self.agenda_typed(from, to).await
+    }
+
+    pub fn agenda_stream(
+        &self,
+        from_rfc3339: String,
+        to_rfc3339: String,
+    ) -> Result<impl Stream<Item = AgendaDiff>> {
+        let from: UtcDateTime = DateTime::parse_from_rfc3339(&from_rfc3339)?.into();
+        let to: UtcDateTime = DateTime::parse_from_rfc3339(&to_rfc3339)?.into();
+        let client = self.clone();
+        // objects are added and removed through the indizes of each source,
+        // updates only change the object itself
+        let mut changes = AgendaChanges::new();
+        for source in AgendaSource::ALL {
+            for index in source.indizes() {
+                let receiver = self.subscribe(index.clone());
+                changes.insert(
+                    (source, ExecuteReference::Index(index)),
+                    BroadcastStream::new(receiver),
+                );
+            }
+        }
+        Ok(async_stream::stream! {
+            let mut per_source = HashMap::new();
+            for source in AgendaSource::ALL {
+                let items = client
+                    .refresh_agenda_source(source, from, to, &mut changes)
+                    .await
+                    .unwrap_or_else(|error| {
+                        warn!(?error, ?source, "Loading the agenda failed");
+                        vec![]
+                    });
+                per_source.insert(source, items);
+            }
+            let mut items = merged_agenda(&per_source);
+            yield AgendaDiff::current_items(items.clone());
+
+            while let Some(((source, _), _)) = changes.next().await {
+                match client.refresh_agenda_source(source, from, to, &mut changes).await {
+                    Ok(source_items) => {
+                        per_source.insert(source, source_items);
+                    }
+                    Err(error) => {
+                        warn!(?error, ?source, "Refreshing the agenda failed");
+                        continue;
+                    }
+                }
+                for diff in agenda_diffs(&mut items, merged_agenda(&per_source)) {
+                    yield remap_for_diff(diff, |x| x);
+                }
+            }
+        })
+    }
+}
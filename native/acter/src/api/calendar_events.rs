@@ -3,22 +3,23 @@ use acter_core::{
     events::{
         calendar::{
             self as calendar_events, CalendarEventBuilder, EventLocation, EventLocationInfo,
+            EventReminder, EventStatus,
         },
-        rsvp::RsvpStatus,
-        CalendarEventRefPreview, RefDetails as CoreRefDetails, UtcDateTime,
+        room::CalendarRemindersEventContent,
+        CalendarEventRefPreview, RecurrenceRule, RefDetails as CoreRefDetails, UtcDateTime,
     },
-    models::{self, can_redact, ActerModel, AnyActerModel},
+    models::{self, can_redact, ActerModel, AnyActerModel, CalendarEventOccurrence, DueReminder},
     referencing::{IndexKey, SectionIndex},
 };
 use anyhow::{bail, Result};
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use futures::{stream::StreamExt, FutureExt};
-use icalendar::Calendar as iCalendar;
 use matrix_sdk::room::Room;
 use matrix_sdk::ruma::EventId;
 use matrix_sdk_base::{
     ruma::{
-        events::{room::message::TextMessageEventContent, MessageLikeEventType},
+        events::{room::message::TextMessageEventContent, Mentions, MessageLikeEventType},
         OwnedEventId, OwnedRoomId, OwnedUserId,
     },
     RoomState,
@@ -68,6 +69,85 @@ impl Client {
             .collect();
         Ok(result)
     }
+
+    pub async fn calendar_events_ical(&self) -> Result<String> {
+        let client = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let events =
+                    calendar_events_for_ical(&client, IndexKey::Section(SectionIndex::Calendar))
+                        .await?;
+                render_ical_feed(&client, None, events).await
+            })
+            .await?
+    }
+
+    pub fn subscribe_calendar_reminders_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(self.core.reminders().subscribe()).map(|_| true)
+    }
+
+    pub fn due_calendar_reminders(&self) -> Result<Vec<DueReminder>> {
+        Ok(self.core.reminders().take_due()?)
+    }
+
+    pub async fn check_calendar_reminders(&self) -> Result<u32> {
+        let scheduler = self.core.reminders().clone();
+        RUNTIME
+            .spawn(async move {
+                let count = scheduler.check(Utc::now()).await?;
+                Ok(u32::try_from(count).unwrap_or(u32::MAX))
+            })
+            .await?
+    }
+
+    pub async fn next_calendar_reminder(&self) -> Result<Option<UtcDateTime>> {
+        let scheduler = self.core.reminders().clone();
+        RUNTIME
+            .spawn(async move { Ok(scheduler.next_due(Utc::now()).await?) })
+            .await?
+    }
+}
+
+async fn calendar_events_for_ical(
+    client: &Client,
+    key: IndexKey,
+) -> Result<Vec<models::CalendarEvent>> {
+    Ok(client
+        .store()
+        .get_list(&key)
+        .await?
+        .filter_map(|mdl| match mdl {
+            AnyActerModel::CalendarEvent(inner) => Some(inner),
+            _ => {
+                warn!(
+                    "Non calendar_event model found in `calendar_events` index: {:?}",
+                    mdl
+                );
+                None
+            }
+        })
+        .collect())
+}
+
+async fn render_ical_feed(
+    client: &Client,
+    name: Option<String>,
+    events: Vec<models::CalendarEvent>,
+) -> Result<String> {
+    let my_id = client.user_id()?;
+    let mut cal_events = Vec::with_capacity(events.len());
+    for event in &events {
+        let rsvps = models::RsvpManager::from_store_and_event_id(client.store(), event.event_id())
+            .await
+            .rsvp_entries()
+            .await?;
+        let reminders = client.core.reminders().reminders_for(event).await?;
+        cal_events.push(event.as_ical_event_with_rsvps(&rsvps, Some(&my_id), &reminders));
+    }
+    Ok(models::ical_document(
+        name.as_deref(),
+        events.iter().zip(cal_events),
+    ))
 }
 
 impl Space {
@@ -84,6 +164,22 @@ impl Space {
             .collect();
         Ok(result)
     }
+
+    pub async fn calendar_events_ical(&self) -> Result<String> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        RUNTIME
+            .spawn(async move {
+                let name = room.cached_display_name().map(|n| n.to_string());
+                let events = calendar_events_for_ical(
+                    &client,
+                    IndexKey::RoomSection(room.room_id().to_owned(), SectionIndex::Calendar),
+                )
+                .await?;
+                render_ical_feed(&client, name, events).await
+            })
+            .await?
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -184,6 +280,33 @@ impl CalendarEvent {
         })
     }
 
+    /// Update builder bringing all fields back to how they were at the given
+    /// version of the object history, 0 being the event as created
+    pub async fn revert_builder(&self, version: u32) -> Result<CalendarEventUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update calendar_events in joined rooms");
+        }
+        let key = self.inner.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let history = client.core.object_history(&key).await?;
+                let target = history
+                    .state_at::<calendar_events::CalendarEventEventContent>(version as usize)?;
+                let AnyActerModel::CalendarEvent(current) = client.store().get(&key).await? else {
+                    bail!("{key} not a calendar event")
+                };
+                Ok(CalendarEventUpdateBuilder {
+                    client,
+                    room,
+                    inner: current.reverter(&target),
+                })
+            })
+            .await?
+    }
+
     pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
         BroadcastStream::new(self.subscribe()).map(|f| true)
     }
@@ -221,18 +344,21 @@ impl CalendarEvent {
         crate::ReactionManager::new(client, room, event_id).await
     }
 
-    pub async fn participants(&self) -> Result<Vec<String>> {
+    pub async fn participants(&self) -> Result<Vec<crate::Rsvp>> {
+        let manager = self.rsvps().await?;
+        let confirmed = manager.confirmed_users().await?;
+        let mut entries = manager.rsvp_entries().await?;
+        entries.retain(|e| confirmed.contains(&e.sender()));
+        entries.sort_by_key(|e| confirmed.iter().position(|u| *u == e.sender()));
+        Ok(entries)
+    }
+
+    pub async fn participants_count(&self) -> Result<u32> {
         let me = self.clone();
         RUNTIME
             .spawn(async move {
                 let manager = me.rsvps().await?;
-                let users = manager
-                    .users_at_status_typed(RsvpStatus::Yes)
-                    .await?
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<String>>();
-                Ok(users)
+                Ok(manager.participation().await?.attendee_count())
             })
             .await?
     }
@@ -273,8 +399,129 @@ impl CalendarEvent {
             .await?
     }
 
+    pub fn recurrence_rule(&self) -> Option<String> {
+        self.inner.recurrence.as_ref().map(ToString::to_string)
+    }
+
+    pub fn has_unsupported_recurrence(&self) -> bool {
+        self.inner.has_unsupported_recurrence()
+    }
+
+    pub fn time_zone(&self) -> Option<String> {
+        self.inner.time_zone.map(|tz| tz.name().to_owned())
+    }
+
+    pub fn reminders(&self) -> Vec<EventReminder> {
+        self.inner.reminders.clone()
+    }
+
+    pub fn capacity(&self) -> Option<u32> {
+        self.inner.capacity
+    }
+
+    pub fn status(&self) -> String {
+        self.inner.status.to_string()
+    }
+
+    pub fn status_reason(&self) -> Option<String> {
+        self.inner.status_reason.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.inner.status, EventStatus::Cancelled)
+    }
+
+    pub async fn my_reminders(&self) -> Result<Vec<EventReminder>> {
+        let scheduler = self.client.core.reminders().clone();
+        let inner = self.inner.clone();
+        RUNTIME
+            .spawn(async move { Ok(scheduler.reminders_for(&inner).await?) })
+            .await?
+    }
+
+    pub async fn add_my_reminder(&self, minutes_before: u32) -> Result<bool> {
+        let mut reminders = self.my_reminders().await?;
+        let reminder = EventReminder::new(minutes_before);
+        if !reminders.contains(&reminder) {
+            reminders.push(reminder);
+            reminders.sort();
+        }
+        self.set_my_reminders(Some(reminders)).await
+    }
+
+    pub async fn remove_my_reminder(&self, minutes_before: u32) -> Result<bool> {
+        let mut reminders = self.my_reminders().await?;
+        reminders.retain(|r| r.minutes_before != minutes_before);
+        self.set_my_reminders(Some(reminders)).await
+    }
+
+    pub async fn reset_my_reminders(&self) -> Result<bool> {
+        self.set_my_reminders(None).await
+    }
+
+    async fn set_my_reminders(&self, reminders: Option<Vec<EventReminder>>) -> Result<bool> {
+        let room = self.room.clone();
+        let event_id = self.inner.event_id().to_owned();
+        RUNTIME
+            .spawn(async move {
+                let mut content = match room
+                    .account_data_static::<CalendarRemindersEventContent>()
+                    .await?
+                    .map(|raw| raw.deserialize())
+                {
+                    Some(Ok(e)) => e.content,
+                    Some(Err(error)) => {
+                        warn!(?error, room_id = ?room.room_id(), "Deserializing calendar reminders failed");
+                        Default::default()
+                    }
+                    None => Default::default(),
+                };
+                if let Some(reminders) = reminders {
+                    content.events.insert(event_id, reminders);
+                } else {
+                    content.events.remove(&event_id);
+                }
+                room.set_account_data(content).await?;
+                Ok(true)
+            })
+            .await?
+    }
+
+    pub fn local_start_rfc3339(&self) -> String {
+        self.inner.local_start().to_rfc3339()
+    }
+
+    pub fn local_end_rfc3339(&self) -> String {
+        self.inner.local_end().to_rfc3339()
+    }
+
+    pub fn occurrences_between(
+        &self,
+        from_rfc3339: String,
+        to_rfc3339: String,
+    ) -> Result<Vec<CalendarEventOccurrence>> {
+        let from: UtcDateTime = DateTime::parse_from_rfc3339(&from_rfc3339)?.into();
+        let to: UtcDateTime = DateTime::parse_from_rfc3339(&to_rfc3339)?.into();
+        Ok(self.inner.occurrences_between(from, to))
+    }
+
+    pub fn exclude_occurrence_builder(
+        &self,
+        start_rfc3339: String,
+    ) -> Result<CalendarEventUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update calendar_events in joined rooms");
+        }
+        let start: UtcDateTime = DateTime::parse_from_rfc3339(&start_rfc3339)?.into();
+        Ok(CalendarEventUpdateBuilder {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            inner: self.inner.exclude_occurrence_updater(start),
+        })
+    }
+
     pub fn ical_for_sharing(&self, file_name: String) -> Result<bool> {
-        let ical_data: String = (&iCalendar::from([self.inner.as_ical_event()])).try_into()?;
+        let ical_data = models::ical_document(None, [(&self.inner, self.inner.as_ical_event())]);
         std::fs::write(file_name, ical_data)?;
         Ok(true)
     }
@@ -286,7 +533,7 @@ impl CalendarEvent {
         let room_id = self.room.room_id().to_owned();
         let title = self.inner.title.clone();
         let start_at_utc = self.inner.utc_start;
-        let participants = self.participants().await?.len() as u32;
+        let participants = self.participants_count().await?;
 
         RUNTIME
             .spawn(async move {
@@ -378,6 +625,70 @@ impl CalendarEventDraft {
         Ok(())
     }
 
+    pub fn recurrence_rule(&mut self, rrule: String) -> Result<()> {
+        let rule = rrule.parse::<RecurrenceRule>()?;
+        self.inner.recurrence(Some(rule.into()));
+        Ok(())
+    }
+
+    pub fn unset_recurrence_rule(&mut self) -> &mut Self {
+        self.inner.recurrence(None);
+        self
+    }
+
+    pub fn time_zone(&mut self, time_zone: String) -> Result<()> {
+        let tz = time_zone.parse::<Tz>().map_err(anyhow::Error::msg)?;
+        self.inner.time_zone(Some(tz));
+        Ok(())
+    }
+
+    pub fn unset_time_zone(&mut self) -> &mut Self {
+        self.inner.time_zone(None);
+        self
+    }
+
+    pub fn add_reminder(&mut self, minutes_before: u32) -> &mut Self {
+        self.inner.add_reminder(EventReminder::new(minutes_before));
+        self
+    }
+
+    pub fn unset_reminders(&mut self) -> &mut Self {
+        self.inner.reminders(vec![]);
+        self
+    }
+
+    pub fn capacity(&mut self, capacity: u32) -> &mut Self {
+        self.inner.capacity(Some(capacity));
+        self
+    }
+
+    pub fn unset_capacity(&mut self) -> &mut Self {
+        self.inner.capacity(None);
+        self
+    }
+
+    pub fn status(&mut self, status: String) -> Result<()> {
+        self.inner.status(status.parse::<EventStatus>()?);
+        Ok(())
+    }
+
+    pub fn status_reason(&mut self, reason: String) -> &mut Self {
+        self.inner.status_reason(Some(reason));
+        self
+    }
+
+    pub fn add_exdate_from_rfc3339(&mut self, exdate: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&exdate)?.into();
+        self.inner.add_exdate(dt);
+        Ok(())
+    }
+
+    pub fn add_rdate_from_rfc3339(&mut self, rdate: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&rdate)?.into();
+        self.inner.add_rdate(dt);
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn physical_location(
         &mut self,
@@ -548,10 +859,108 @@ impl CalendarEventUpdateBuilder {
         self
     }
 
+    pub fn recurrence_rule(&mut self, rrule: String) -> Result<()> {
+        let rule = rrule.parse::<RecurrenceRule>()?;
+        self.inner.recurrence(Some(Some(rule.into())));
+        Ok(())
+    }
+
+    pub fn unset_recurrence_rule(&mut self) -> &mut Self {
+        self.inner.recurrence(Some(None));
+        self
+    }
+
+    pub fn unset_recurrence_rule_update(&mut self) -> &mut Self {
+        self.inner.recurrence(None);
+        self
+    }
+
+    pub fn time_zone(&mut self, time_zone: String) -> Result<()> {
+        let tz = time_zone.parse::<Tz>().map_err(anyhow::Error::msg)?;
+        self.inner.time_zone(Some(Some(tz)));
+        Ok(())
+    }
+
+    pub fn unset_time_zone(&mut self) -> &mut Self {
+        self.inner.time_zone(Some(None));
+        self
+    }
+
+    pub fn unset_time_zone_update(&mut self) -> &mut Self {
+        self.inner.time_zone(None);
+        self
+    }
+
+    pub fn add_reminder(&mut self, minutes_before: u32) -> &mut Self {
+        self.inner.add_reminder(EventReminder::new(minutes_before));
+        self
+    }
+
+    pub fn unset_reminders(&mut self) -> &mut Self {
+        self.inner.reminders(Some(vec![]));
+        self
+    }
+
+    pub fn unset_reminders_update(&mut self) -> &mut Self {
+        self.inner.reminders(None);
+        self
+    }
+
+    pub fn capacity(&mut self, capacity: u32) -> &mut Self {
+        self.inner.capacity(Some(Some(capacity)));
+        self
+    }
+
+    pub fn unset_capacity(&mut self) -> &mut Self {
+        self.inner.capacity(Some(None));
+        self
+    }
+
+    pub fn unset_capacity_update(&mut self) -> &mut Self {
+        self.inner.capacity(None);
+        self
+    }
+
+    pub fn status(&mut self, status: String) -> Result<()> {
+        self.inner.status(Some(status.parse::<EventStatus>()?));
+        Ok(())
+    }
+
+    pub fn unset_status_update(&mut self) -> &mut Self {
+        self.inner.status(None);
+        self
+    }
+
+    pub fn status_reason(&mut self, reason: String) -> &mut Self {
+        self.inner.status_reason(Some(Some(reason)));
+        self
+    }
+
+    pub fn unset_status_reason(&mut self) -> &mut Self {
+        self.inner.status_reason(Some(None));
+        self
+    }
+
+    pub fn unset_status_reason_update(&mut self) -> &mut Self {
+        self.inner.status_reason(None);
+        self
+    }
+
+    pub fn unset_exdates(&mut self) -> &mut Self {
+        self.inner.exdates(Some(vec![]));
+        self
+    }
+
+    pub fn unset_rdates(&mut self) -> &mut Self {
+        self.inner.rdates(Some(vec![]));
+        self
+    }
+
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
+        let client = self.client.clone();
         let my_id = self.client.user_id()?;
-        let inner = self.inner.build()?;
+        let mut inner = self.inner.build()?;
 
         RUNTIME
             .spawn(async move {
@@ -561,6 +970,17 @@ impl CalendarEventUpdateBuilder {
                 if !permitted {
                     bail!("No permissions to send message in this room");
                 }
+                if inner.status.is_some() {
+                    // let everyone planning to come know
+                    let attending = models::RsvpManager::from_store_and_event_id(
+                        client.store(),
+                        &inner.calendar_event.event_id,
+                    )
+                    .await
+                    .attending_users(&my_id)
+                    .await?;
+                    inner.mentions = Some(Mentions::with_user_ids(attending));
+                }
                 let response = room.send(inner).await?;
                 Ok(response.event_id)
             })
@@ -593,4 +1013,26 @@ impl Space {
             inner,
         })
     }
+
+    pub fn calendar_event_drafts_from_ical(&self, ics: String) -> Result<Vec<CalendarEventDraft>> {
+        if !self.is_joined() {
+            bail!("Unable to create calendar_events for spaces we are not part on");
+        }
+        Ok(models::drafts_from_ical(&ics)?
+            .into_iter()
+            .map(|inner| CalendarEventDraft {
+                client: self.client.clone(),
+                room: self.inner.room.clone(),
+                inner,
+            })
+            .collect())
+    }
+
+    pub fn calendar_event_drafts_from_ical_file(
+        &self,
+        file_name: String,
+    ) -> Result<Vec<CalendarEventDraft>> {
+        let ics = std::fs::read_to_string(file_name)?;
+        self.calendar_event_drafts_from_ical(ics)
+    }
 }
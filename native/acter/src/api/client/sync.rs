@@ -486,6 +486,30 @@ impl Client {
         let history_loading = sync_state.history_loading.clone();
         let first_sync_task = sync_state.first_sync_task.clone();
 
+        // keeps the overdue and due-soon task lists current as time passes
+        let task_due = self.core.task_due().clone();
+        RUNTIME.spawn(async move {
+            if let Err(error) = task_due.run().await {
+                error!(?error, "Watching task due dates failed");
+            }
+        });
+
+        // fires the calendar reminders as they come due
+        let reminders = self.core.reminders().clone();
+        RUNTIME.spawn(async move {
+            if let Err(error) = reminders.run().await {
+                error!(?error, "Scheduling calendar reminders failed");
+            }
+        });
+
+        // follows up on recurring tasks completed elsewhere
+        let recurring = self.clone();
+        RUNTIME.spawn(async move {
+            if let Err(error) = recurring.follow_up_recurring_tasks().await {
+                error!(?error, "Following up on recurring tasks failed");
+            }
+        });
+
         let handle = RUNTIME.spawn(async move {
             info!("spawning sync callback");
 
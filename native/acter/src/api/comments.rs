@@ -71,10 +71,11 @@ impl Comment {
         if !self.is_joined() {
             bail!("Can only comment in joined rooms");
         }
+        let my_id = self.client.user_id()?;
         Ok(CommentDraft {
             client: self.client.clone(),
             room: self.room.clone(),
-            inner: self.inner.reply_builder(),
+            inner: self.inner.reply_builder(&my_id),
         })
     }
 
@@ -114,6 +115,22 @@ impl Comment {
         (&self.inner.content).into()
     }
 
+    pub fn is_reply(&self) -> bool {
+        self.inner.is_reply()
+    }
+
+    pub fn reply_to_id_str(&self) -> Option<String> {
+        self.inner.reply_to_id().map(ToString::to_string)
+    }
+
+    /// the manager of the direct replies to this comment
+    pub async fn replies(&self) -> Result<CommentsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.inner.event_id().to_owned();
+        CommentsManager::new(client, room, event_id).await
+    }
+
     pub fn update_builder(&self) -> Result<CommentUpdateBuilder> {
         if !self.is_joined() {
             bail!("Can only update comments in joined rooms");
@@ -126,6 +143,40 @@ impl Comment {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    client: Client,
+    room: Room,
+    inner: models::CommentThread,
+}
+
+impl CommentThread {
+    pub fn comment(&self) -> Comment {
+        Comment::new(
+            self.client.clone(),
+            self.room.clone(),
+            self.inner.comment().clone(),
+        )
+    }
+
+    pub fn reply_count(&self) -> u32 {
+        *self.inner.reply_count()
+    }
+
+    pub fn replies(&self) -> Vec<Comment> {
+        self.inner
+            .replies()
+            .iter()
+            .cloned()
+            .map(|inner| Comment::new(self.client.clone(), self.room.clone(), inner))
+            .collect()
+    }
+
+    pub fn more_replies_count(&self) -> u32 {
+        self.inner.more_replies_count()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CommentsManager {
     client: Client,
@@ -286,6 +337,48 @@ impl CommentsManager {
             .await?
     }
 
+    /// top level comments, each with up to `replies_limit` of its replies
+    pub async fn threads(&self, replies_limit: u32) -> Result<Vec<CommentThread>> {
+        let manager = self.inner.clone();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let res = manager
+                    .threads(replies_limit as usize)
+                    .await?
+                    .into_iter()
+                    .map(|inner| CommentThread {
+                        client: client.clone(),
+                        room: room.clone(),
+                        inner,
+                    })
+                    .collect();
+                Ok(res)
+            })
+            .await?
+    }
+
+    /// the direct replies to the comment this manager is for, oldest first
+    pub async fn replies(&self, offset: u32, limit: u32) -> Result<Vec<Comment>> {
+        let manager = self.inner.clone();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let res = manager
+                    .replies(offset as usize, limit as usize)
+                    .await?
+                    .into_iter()
+                    .map(|inner| Comment::new(client.clone(), room.clone(), inner))
+                    .collect();
+                Ok(res)
+            })
+            .await?
+    }
+
     fn is_joined(&self) -> bool {
         matches!(self.room.state(), RoomState::Joined)
     }
@@ -0,0 +1,458 @@
+use acter_core::{
+    events::{
+        documents::{DocumentBuilder, DocumentRevisionBuilder},
+        Display,
+    },
+    models::{self, can_redact, ActerModel, AnyActerModel},
+    referencing::{IndexKey, SectionIndex},
+};
+use anyhow::{bail, Result};
+use futures::stream::StreamExt;
+use matrix_sdk::room::Room;
+use matrix_sdk_base::{
+    ruma::{
+        events::{
+            room::message::TextMessageEventContent, MessageLikeEventContent, MessageLikeEventType,
+        },
+        EventId, OwnedEventId, OwnedUserId,
+    },
+    RoomState,
+};
+use std::ops::Deref;
+use tokio::sync::broadcast::Receiver;
+use tokio_stream::{wrappers::BroadcastStream, Stream};
+
+use crate::MsgContent;
+
+use super::{client::Client, spaces::Space, RUNTIME};
+
+impl Client {
+    pub async fn wait_for_document(&self, key: String, timeout: Option<u8>) -> Result<Document> {
+        let me = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Document(content) = me.wait_for(key.clone(), timeout).await?
+                else {
+                    bail!("{key} is not a document");
+                };
+                let room = me.room_by_id_typed(content.room_id())?;
+                Ok(Document {
+                    client: me.clone(),
+                    room,
+                    content,
+                })
+            })
+            .await?
+    }
+
+    pub async fn documents(&self) -> Result<Vec<Document>> {
+        Ok(self
+            .models_of_list_with_room(IndexKey::Section(SectionIndex::Documents))
+            .await?
+            .map(|(inner, room)| Document {
+                client: self.clone(),
+                room,
+                content: inner,
+            })
+            .collect())
+    }
+
+    pub async fn document(&self, document_id: String) -> Result<Document> {
+        self.model_with_room(EventId::parse(document_id)?)
+            .await
+            .map(|(inner, room)| Document {
+                client: self.clone(),
+                room,
+                content: inner,
+            })
+    }
+}
+
+impl Space {
+    pub async fn documents(&self) -> Result<Vec<Document>> {
+        let room = self.room.clone();
+        Ok(self
+            .client
+            .models_of_list_with_room_under_check(
+                IndexKey::RoomSection(room.room_id().to_owned(), SectionIndex::Documents),
+                move |_r| Ok(room.clone()),
+            )
+            .await?
+            .map(|(inner, room)| Document {
+                client: self.client.clone(),
+                room,
+                content: inner,
+            })
+            .collect())
+    }
+
+    pub fn document_draft(&self) -> Result<DocumentDraft> {
+        if !self.is_joined() {
+            bail!("Unable to create documents for spaces we are not part on");
+        }
+        Ok(DocumentDraft {
+            client: self.client.clone(),
+            room: self.inner.room.clone(),
+            content: Default::default(),
+        })
+    }
+}
+
+async fn send_content<C>(client: &Client, room: &Room, content: C) -> Result<OwnedEventId>
+where
+    C: MessageLikeEventContent + Send + 'static,
+{
+    let room = room.clone();
+    let my_id = client.user_id()?;
+    RUNTIME
+        .spawn(async move {
+            let permitted = room
+                .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                .await?;
+            if !permitted {
+                bail!("No permissions to send message in this room");
+            }
+            let response = room.send(content).await?;
+            Ok(response.event_id)
+        })
+        .await?
+}
+
+#[derive(Clone, Debug)]
+pub struct Document {
+    client: Client,
+    room: Room,
+    content: models::Document,
+}
+
+impl Deref for Document {
+    type Target = models::Document;
+    fn deref(&self) -> &Self::Target {
+        &self.content
+    }
+}
+
+/// helpers for content
+impl Document {
+    pub fn title(&self) -> String {
+        self.content.title()
+    }
+
+    pub fn body(&self) -> MsgContent {
+        MsgContent::from(&self.content.body)
+    }
+
+    pub fn body_formatted(&self) -> Option<String> {
+        self.content.body.formatted.as_ref().map(|f| f.body.clone())
+    }
+
+    pub fn display(&self) -> Option<Display> {
+        self.content.display.clone()
+    }
+
+    pub fn current_revision_id_str(&self) -> String {
+        self.content.current_revision().to_string()
+    }
+
+    pub fn revision_count(&self) -> u32 {
+        self.content.revision_count()
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        self.content.has_conflict()
+    }
+
+    pub fn conflicting_revision_ids(&self) -> Vec<String> {
+        self.content
+            .conflicts()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn event_id_str(&self) -> String {
+        self.content.event_id().to_string()
+    }
+
+    pub fn room_id_str(&self) -> String {
+        self.content.room_id().to_string()
+    }
+
+    pub fn sender(&self) -> OwnedUserId {
+        self.content.sender().to_owned()
+    }
+}
+
+/// Custom functions
+impl Document {
+    pub async fn refresh(&self) -> Result<Document> {
+        let key = self.content.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Document(content) = client.store().get(&key).await? else {
+                    bail!("Refreshing failed. {key} not a document")
+                };
+                Ok(Document {
+                    client,
+                    room,
+                    content,
+                })
+            })
+            .await?
+    }
+
+    pub async fn can_redact(&self) -> Result<bool> {
+        let sender = self.content.sender().to_owned();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move { Ok(can_redact(&room, &sender).await?) })
+            .await?
+    }
+
+    fn is_joined(&self) -> bool {
+        matches!(self.room.state(), RoomState::Joined)
+    }
+
+    /// Edit the document, starting from its current revision
+    pub fn edit_draft(&self) -> Result<DocumentEditDraft> {
+        if !self.is_joined() {
+            bail!("Can only edit documents in joined rooms");
+        }
+        Ok(DocumentEditDraft {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            document_id: self.content.event_id().to_owned(),
+            content: self.content.reviser(),
+        })
+    }
+
+    /// All revisions, latest first
+    pub async fn revisions(&self) -> Result<Vec<DocumentRevision>> {
+        let client = self.client.clone();
+        let document_id = self.content.event_id().to_owned();
+        let conflicts = self.content.conflicts().to_vec();
+
+        RUNTIME
+            .spawn(async move {
+                Ok(
+                    models::DocumentRevision::all_for(client.store(), &document_id)
+                        .await?
+                        .into_iter()
+                        .map(|inner| DocumentRevision {
+                            is_conflicting: conflicts.contains(&inner.meta.event_id),
+                            inner,
+                        })
+                        .collect(),
+                )
+            })
+            .await?
+    }
+
+    /// Bring back the state of an older revision, or of the document as it
+    /// was created when given its own id
+    pub async fn restore(&self, revision_id: String) -> Result<OwnedEventId> {
+        if !self.is_joined() {
+            bail!("Can only restore documents in joined rooms");
+        }
+        let revision_id = EventId::parse(revision_id)?;
+        let client = self.client.clone();
+        let document = self.content.clone();
+        let content = RUNTIME
+            .spawn(async move {
+                let builder = document.restorer(client.store(), &revision_id).await?;
+                anyhow::Ok(builder.build()?)
+            })
+            .await??;
+        send_content(&self.client, &self.room, content).await
+    }
+
+    pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(self.subscribe()).map(|_| true)
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        let key = self.content.event_id().to_owned();
+        self.client.subscribe(key)
+    }
+
+    pub async fn comments(&self) -> Result<crate::CommentsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::CommentsManager::new(client, room, event_id).await
+    }
+
+    pub async fn attachments(&self) -> Result<crate::AttachmentsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::AttachmentsManager::new(client, room, event_id).await
+    }
+
+    pub async fn reactions(&self) -> Result<crate::ReactionManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::ReactionManager::new(client, room, event_id).await
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DocumentRevision {
+    inner: models::DocumentRevision,
+    is_conflicting: bool,
+}
+
+impl Deref for DocumentRevision {
+    type Target = models::DocumentRevision;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DocumentRevision {
+    pub fn event_id_str(&self) -> String {
+        self.inner.meta.event_id.to_string()
+    }
+
+    pub fn parent_revision_id_str(&self) -> String {
+        self.inner.parent_revision.to_string()
+    }
+
+    pub fn restored_from_id_str(&self) -> Option<String> {
+        self.inner.restored_from.as_ref().map(ToString::to_string)
+    }
+
+    pub fn sender(&self) -> OwnedUserId {
+        self.inner.meta.sender.clone()
+    }
+
+    pub fn origin_server_ts(&self) -> u64 {
+        self.inner.meta.origin_server_ts.get().into()
+    }
+
+    pub fn title(&self) -> String {
+        self.inner.title.clone()
+    }
+
+    pub fn body(&self) -> MsgContent {
+        MsgContent::from(&self.inner.body)
+    }
+
+    /// whether this revision wasn’t based on the latest one and might have
+    /// overwritten changes, until a newer revision resolved it
+    pub fn is_conflicting(&self) -> bool {
+        self.is_conflicting
+    }
+}
+
+#[derive(Clone)]
+pub struct DocumentDraft {
+    client: Client,
+    room: Room,
+    content: DocumentBuilder,
+}
+
+impl DocumentDraft {
+    pub fn title(&mut self, title: String) -> &mut Self {
+        self.content.title(title);
+        self
+    }
+
+    pub fn body_text(&mut self, body: String) -> &mut Self {
+        self.content.body(TextMessageEventContent::plain(body));
+        self
+    }
+
+    pub fn body_markdown(&mut self, body: String) -> &mut Self {
+        self.content.body(TextMessageEventContent::markdown(body));
+        self
+    }
+
+    pub fn body_html(&mut self, body: String, html_body: String) -> &mut Self {
+        self.content
+            .body(TextMessageEventContent::html(body, html_body));
+        self
+    }
+
+    pub fn display(&mut self, display: Box<Display>) -> &mut Self {
+        self.content.display(Some(*display));
+        self
+    }
+
+    pub fn unset_display(&mut self) -> &mut Self {
+        self.content.display(None);
+        self
+    }
+
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        let content = self.content.build()?;
+        send_content(&self.client, &self.room, content).await
+    }
+}
+
+#[derive(Clone)]
+pub struct DocumentEditDraft {
+    client: Client,
+    room: Room,
+    document_id: OwnedEventId,
+    content: DocumentRevisionBuilder,
+}
+
+impl DocumentEditDraft {
+    pub fn title(&mut self, title: String) -> &mut Self {
+        self.content.title(title);
+        self
+    }
+
+    pub fn body_text(&mut self, body: String) -> &mut Self {
+        self.content.body(TextMessageEventContent::plain(body));
+        self
+    }
+
+    pub fn body_markdown(&mut self, body: String) -> &mut Self {
+        self.content.body(TextMessageEventContent::markdown(body));
+        self
+    }
+
+    pub fn body_html(&mut self, body: String, html_body: String) -> &mut Self {
+        self.content
+            .body(TextMessageEventContent::html(body, html_body));
+        self
+    }
+
+    /// Send the new revision, fails if someone else revised the document
+    /// since this draft was started
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        let content = self.content.build()?;
+        let client = self.client.clone();
+        let document_id = self.document_id.clone();
+        let parent = content.parent_revision.clone();
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Document(document) = client.store().get(&document_id).await?
+                else {
+                    bail!("{document_id} is not a document");
+                };
+                if *document.current_revision() != *parent {
+                    bail!(
+                        "Conflict: the document was changed in {} since you started editing",
+                        document.current_revision()
+                    );
+                }
+                Ok(())
+            })
+            .await??;
+        send_content(&self.client, &self.room, content).await
+    }
+
+    /// Send the new revision even if it conflicts with a newer one
+    pub async fn send_anyway(&self) -> Result<OwnedEventId> {
+        let content = self.content.build()?;
+        send_content(&self.client, &self.room, content).await
+    }
+}
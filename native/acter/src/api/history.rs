@@ -0,0 +1,110 @@
+use acter_core::history::{
+    FieldChange as CoreFieldChange, HistoryEntry as CoreHistoryEntry,
+    ObjectHistory as CoreObjectHistory,
+};
+use anyhow::Result;
+use matrix_sdk::ruma::EventId;
+
+use super::{Client, RUNTIME};
+
+#[derive(Clone, Debug)]
+pub struct ObjectHistory {
+    inner: CoreObjectHistory,
+}
+
+impl ObjectHistory {
+    pub fn object_id_str(&self) -> String {
+        self.inner.meta().event_id.to_string()
+    }
+
+    pub fn creator_id_str(&self) -> String {
+        self.inner.meta().sender.to_string()
+    }
+
+    pub fn created_at(&self) -> u64 {
+        self.inner.meta().origin_server_ts.get().into()
+    }
+
+    pub fn entries(&self) -> Vec<ObjectHistoryEntry> {
+        self.inner
+            .entries()
+            .iter()
+            .cloned()
+            .map(|inner| ObjectHistoryEntry { inner })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectHistoryEntry {
+    inner: CoreHistoryEntry,
+}
+
+impl ObjectHistoryEntry {
+    pub fn event_id_str(&self) -> String {
+        self.inner.meta().event_id.to_string()
+    }
+
+    pub fn sender_id_str(&self) -> String {
+        self.inner.meta().sender.to_string()
+    }
+
+    pub fn origin_server_ts(&self) -> u64 {
+        self.inner.meta().origin_server_ts.get().into()
+    }
+
+    pub fn changed_fields(&self) -> Vec<String> {
+        self.inner
+            .changes()
+            .iter()
+            .map(|c| c.field().clone())
+            .collect()
+    }
+
+    pub fn changes(&self) -> Vec<FieldChange> {
+        self.inner
+            .changes()
+            .iter()
+            .cloned()
+            .map(|inner| FieldChange { inner })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldChange {
+    inner: CoreFieldChange,
+}
+
+impl FieldChange {
+    pub fn field(&self) -> String {
+        self.inner.field().clone()
+    }
+
+    /// the previous value as json, if it was set
+    pub fn before_json(&self) -> Option<String> {
+        self.inner.before().as_ref().map(ToString::to_string)
+    }
+
+    /// the new value as json, if it is set
+    pub fn after_json(&self) -> Option<String> {
+        self.inner.after().as_ref().map(ToString::to_string)
+    }
+}
+
+impl Client {
+    pub async fn object_history(&self, object_id: String) -> Result<ObjectHistory> {
+        let object_id = EventId::parse(object_id)?;
+        let client = self.clone();
+
+        Ok(RUNTIME
+            .spawn(async move {
+                client
+                    .core
+                    .object_history(&object_id)
+                    .await
+                    .map(|inner| ObjectHistory { inner })
+            })
+            .await??)
+    }
+}
@@ -1,4 +1,7 @@
-use acter_core::{events::explicit_invites::ExplicitInviteEventContent, models};
+use acter_core::{
+    events::explicit_invites::ExplicitInviteEventContent,
+    models::{self, AnyActerModel},
+};
 use futures::{Stream, StreamExt};
 use matrix_sdk::ruma::{OwnedEventId, OwnedUserId};
 use ruma::UserId;
@@ -6,7 +9,7 @@ use tokio::sync::broadcast::Receiver;
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::{Client, MsgContent, RUNTIME};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use matrix_sdk::Room;
 use std::ops::Deref;
 
@@ -92,6 +95,33 @@ impl ObjectInvitationsManager {
             .await?
     }
 
+    /// assign the user directly rather than inviting them, only supported for tasks
+    pub async fn assign(&self, user_id: String) -> Result<String> {
+        let user_id = UserId::parse(user_id)?;
+        let my_id = self.client.user_id()?;
+        let store = self.client.store().clone();
+        let object_id = self.inner.event_id();
+        let room = self.room.clone();
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Task(task) = store.get(&object_id).await? else {
+                    bail!("Only tasks can be assigned");
+                };
+                let event_id = if user_id == my_id {
+                    room.send(task.self_assign_event_content()).await?.event_id
+                } else {
+                    if !models::can_assign(&room, &my_id).await? {
+                        bail!("No permissions to assign others in this room");
+                    }
+                    room.send(task.assign_event_content(user_id))
+                        .await?
+                        .event_id
+                };
+                Ok(event_id.to_string())
+            })
+            .await?
+    }
+
     pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
         BroadcastStream::new(self.subscribe()).map(|_| true)
     }
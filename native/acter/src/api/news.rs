@@ -2,12 +2,13 @@ use acter_core::{
     events::{
         news::{self, FallbackNewsContent, NewsContent, NewsEntryBuilder, NewsSlideBuilder},
         Colorize, ColorizeBuilder, ObjRef as CoreObjRef, ObjRefBuilder,
-        RefDetails as CoreRefDetails, RefPreview,
+        RefDetails as CoreRefDetails, RefPreview, UtcDateTime,
     },
     models::{self, can_redact, ActerModel, AnyActerModel, ReactionManager},
     referencing::{IndexKey, SectionIndex},
 };
 use anyhow::{bail, Context, Result};
+use chrono::DateTime;
 use futures::stream::StreamExt;
 use matrix_sdk::room::Room;
 use matrix_sdk_base::{
@@ -446,6 +447,10 @@ impl NewsEntry {
         self.content.meta.origin_server_ts.get().into()
     }
 
+    pub fn deadline_rfc3339(&self) -> Option<String> {
+        self.content.deadline.map(|d| d.to_rfc3339())
+    }
+
     pub async fn ref_details(&self) -> Result<RefDetails> {
         let room = self.room.clone();
         let client = self.client.deref().clone();
@@ -507,6 +512,17 @@ impl NewsEntryDraft {
         self
     }
 
+    pub fn deadline_from_rfc3339(&mut self, deadline: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&deadline)?.into();
+        self.content.deadline(Some(dt));
+        Ok(())
+    }
+
+    pub fn unset_deadline(&mut self) -> &mut Self {
+        self.content.deadline(None);
+        self
+    }
+
     pub async fn send(&mut self) -> Result<OwnedEventId> {
         trace!("starting send");
         let client = self.client.clone();
@@ -588,6 +604,22 @@ impl NewsEntryUpdateBuilder {
         self
     }
 
+    pub fn deadline_from_rfc3339(&mut self, deadline: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&deadline)?.into();
+        self.content.deadline(Some(Some(dt)));
+        Ok(())
+    }
+
+    pub fn unset_deadline(&mut self) -> &mut Self {
+        self.content.deadline(Some(None));
+        self
+    }
+
+    pub fn unset_deadline_update(&mut self) -> &mut Self {
+        self.content.deadline(None::<Option<UtcDateTime>>);
+        self
+    }
+
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
         let my_id = self.client.user_id()?;
@@ -1,12 +1,13 @@
 use acter_core::{
     events::{
         pins::{self, PinBuilder},
-        Display, RefDetails as CoreRefDetails, RefPreview,
+        Display, RefDetails as CoreRefDetails, RefPreview, UtcDateTime,
     },
     models::{self, can_redact, ActerModel, AnyActerModel},
     referencing::{IndexKey, SectionIndex},
 };
 use anyhow::{bail, Result};
+use chrono::DateTime;
 use futures::stream::StreamExt;
 use matrix_sdk::room::Room;
 use matrix_sdk::ruma::EventId;
@@ -163,6 +164,10 @@ impl Pin {
         self.content.url.clone()
     }
 
+    pub fn deadline_rfc3339(&self) -> Option<String> {
+        self.content.deadline.map(|d| d.to_rfc3339())
+    }
+
     pub fn event_id_str(&self) -> String {
         self.content.event_id().to_string()
     }
@@ -178,6 +183,14 @@ impl Pin {
 
 /// Custom functions
 impl Pin {
+    pub(crate) fn new(client: Client, room: Room, content: models::Pin) -> Self {
+        Pin {
+            client,
+            room,
+            content,
+        }
+    }
+
     pub async fn refresh(&self) -> Result<Pin> {
         let key = self.content.event_id().to_owned();
         let client = self.client.clone();
@@ -221,6 +234,32 @@ impl Pin {
         })
     }
 
+    /// Update builder bringing all fields back to how they were at the given
+    /// version of the object history, 0 being the pin as created
+    pub async fn revert_builder(&self, version: u32) -> Result<PinUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update pins in joined rooms");
+        }
+        let key = self.content.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let history = client.core.object_history(&key).await?;
+                let target = history.state_at::<pins::PinEventContent>(version as usize)?;
+                let AnyActerModel::Pin(current) = client.store().get(&key).await? else {
+                    bail!("{key} not a pin")
+                };
+                Ok(PinUpdateBuilder {
+                    client,
+                    room,
+                    content: current.reverter(&target),
+                })
+            })
+            .await?
+    }
+
     pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
         BroadcastStream::new(self.subscribe()).map(|_| true)
     }
@@ -291,6 +330,17 @@ impl PinDraft {
         self
     }
 
+    pub fn deadline_from_rfc3339(&mut self, deadline: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&deadline)?.into();
+        self.content.deadline(Some(dt));
+        Ok(())
+    }
+
+    pub fn unset_deadline(&mut self) -> &mut Self {
+        self.content.deadline(None);
+        self
+    }
+
     pub fn display(&mut self, display: Box<Display>) -> &mut Self {
         self.content.display(Some(*display));
         self
@@ -383,6 +433,22 @@ impl PinUpdateBuilder {
         self
     }
 
+    pub fn deadline_from_rfc3339(&mut self, deadline: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&deadline)?.into();
+        self.content.deadline(Some(Some(dt)));
+        Ok(())
+    }
+
+    pub fn unset_deadline(&mut self) -> &mut Self {
+        self.content.deadline(Some(None));
+        self
+    }
+
+    pub fn unset_deadline_update(&mut self) -> &mut Self {
+        self.content.deadline(None::<Option<UtcDateTime>>);
+        self
+    }
+
     pub fn display(&mut self, display: Box<Display>) -> &mut Self {
         self.content.display(Some(Some(*display)));
         self
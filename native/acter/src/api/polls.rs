@@ -0,0 +1,588 @@
+use acter_core::{
+    events::{
+        polls::{self, PollAnswer, PollBuilder, PollVoteBuilder},
+        Display, UtcDateTime,
+    },
+    models::{self, can_redact, ActerModel, AnyActerModel},
+    referencing::{IndexKey, SectionIndex},
+};
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use futures::stream::StreamExt;
+use matrix_sdk::room::Room;
+use matrix_sdk_base::{
+    ruma::{
+        events::{room::message::TextMessageEventContent, MessageLikeEventType},
+        EventId, OwnedEventId, OwnedUserId,
+    },
+    RoomState,
+};
+use std::ops::Deref;
+use tokio::sync::broadcast::Receiver;
+use tokio_stream::{wrappers::BroadcastStream, Stream};
+
+use crate::MsgContent;
+
+use super::{client::Client, spaces::Space, RUNTIME};
+
+impl Client {
+    pub async fn wait_for_poll(&self, key: String, timeout: Option<u8>) -> Result<Poll> {
+        let me = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Poll(content) = me.wait_for(key.clone(), timeout).await? else {
+                    bail!("{key} is not a poll");
+                };
+                let room = me.room_by_id_typed(content.room_id())?;
+                Ok(Poll {
+                    client: me.clone(),
+                    room,
+                    content,
+                })
+            })
+            .await?
+    }
+
+    pub async fn polls(&self) -> Result<Vec<Poll>> {
+        Ok(self
+            .models_of_list_with_room(IndexKey::Section(SectionIndex::Polls))
+            .await?
+            .map(|(inner, room)| Poll {
+                client: self.clone(),
+                room,
+                content: inner,
+            })
+            .collect())
+    }
+
+    pub async fn poll(&self, poll_id: String) -> Result<Poll> {
+        self.model_with_room(EventId::parse(poll_id)?)
+            .await
+            .map(|(inner, room)| Poll {
+                client: self.clone(),
+                room,
+                content: inner,
+            })
+    }
+}
+
+impl Space {
+    pub async fn polls(&self) -> Result<Vec<Poll>> {
+        let room = self.room.clone();
+        Ok(self
+            .client
+            .models_of_list_with_room_under_check(
+                IndexKey::RoomSection(room.room_id().to_owned(), SectionIndex::Polls),
+                move |_r| Ok(room.clone()),
+            )
+            .await?
+            .map(|(inner, room)| Poll {
+                client: self.client.clone(),
+                room,
+                content: inner,
+            })
+            .collect())
+    }
+
+    pub fn poll_draft(&self) -> Result<PollDraft> {
+        if !self.is_joined() {
+            bail!("Unable to create polls for spaces we are not part on");
+        }
+        Ok(PollDraft {
+            client: self.client.clone(),
+            room: self.inner.room.clone(),
+            content: Default::default(),
+            answers: vec![],
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Poll {
+    client: Client,
+    room: Room,
+    content: models::Poll,
+}
+
+impl Deref for Poll {
+    type Target = models::Poll;
+    fn deref(&self) -> &Self::Target {
+        &self.content
+    }
+}
+
+/// helpers for content
+impl Poll {
+    pub fn question(&self) -> String {
+        self.content.question()
+    }
+
+    pub fn description(&self) -> Option<MsgContent> {
+        self.content.description.as_ref().map(MsgContent::from)
+    }
+
+    pub fn answer_ids(&self) -> Vec<String> {
+        self.content.answers.iter().map(|a| a.id.clone()).collect()
+    }
+
+    pub fn answer_text(&self, answer_id: String) -> Option<String> {
+        self.content.answer(&answer_id).map(|a| a.text.clone())
+    }
+
+    pub fn max_selections(&self) -> u32 {
+        self.content.max_selections
+    }
+
+    pub fn is_multiple_choice(&self) -> bool {
+        self.content.is_multiple_choice()
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.content.anonymous
+    }
+
+    pub fn allows_vote_change(&self) -> bool {
+        self.content.allow_vote_change
+    }
+
+    pub fn closes_at_rfc3339(&self) -> Option<String> {
+        self.content.closes_at.map(|d| d.to_rfc3339())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.content.is_closed_at(chrono::Utc::now())
+    }
+
+    pub fn display(&self) -> Option<Display> {
+        self.content.display.clone()
+    }
+
+    pub fn event_id_str(&self) -> String {
+        self.content.event_id().to_string()
+    }
+
+    pub fn room_id_str(&self) -> String {
+        self.content.room_id().to_string()
+    }
+
+    pub fn sender(&self) -> OwnedUserId {
+        self.content.sender().to_owned()
+    }
+}
+
+/// Custom functions
+impl Poll {
+    pub async fn refresh(&self) -> Result<Poll> {
+        let key = self.content.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let AnyActerModel::Poll(content) = client.store().get(&key).await? else {
+                    bail!("Refreshing failed. {key} not a poll")
+                };
+                Ok(Poll {
+                    client,
+                    room,
+                    content,
+                })
+            })
+            .await?
+    }
+
+    pub async fn can_redact(&self) -> Result<bool> {
+        let sender = self.content.sender().to_owned();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move { Ok(can_redact(&room, &sender).await?) })
+            .await?
+    }
+
+    fn is_joined(&self) -> bool {
+        matches!(self.room.state(), RoomState::Joined)
+    }
+
+    pub fn update_builder(&self) -> Result<PollUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update polls in joined rooms");
+        }
+        Ok(PollUpdateBuilder {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            content: self.content.updater(),
+        })
+    }
+
+    pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(self.subscribe()).map(|_| true)
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        let key = self.content.event_id().to_owned();
+        self.client.subscribe(key)
+    }
+
+    pub async fn votes(&self) -> Result<PollsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        PollsManager::new(client, room, event_id, self.content.anonymous).await
+    }
+
+    pub async fn comments(&self) -> Result<crate::CommentsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::CommentsManager::new(client, room, event_id).await
+    }
+
+    pub async fn attachments(&self) -> Result<crate::AttachmentsManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::AttachmentsManager::new(client, room, event_id).await
+    }
+
+    pub async fn reactions(&self) -> Result<crate::ReactionManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::ReactionManager::new(client, room, event_id).await
+    }
+}
+
+#[derive(Clone)]
+pub struct PollDraft {
+    client: Client,
+    room: Room,
+    content: PollBuilder,
+    answers: Vec<PollAnswer>,
+}
+
+impl PollDraft {
+    pub fn question(&mut self, question: String) -> &mut Self {
+        self.content.question(question);
+        self
+    }
+
+    pub fn description_text(&mut self, body: String) -> &mut Self {
+        self.content
+            .description(Some(TextMessageEventContent::plain(body)));
+        self
+    }
+
+    pub fn description_markdown(&mut self, body: String) -> &mut Self {
+        self.content
+            .description(Some(TextMessageEventContent::markdown(body)));
+        self
+    }
+
+    pub fn unset_description(&mut self) -> &mut Self {
+        self.content.description(None);
+        self
+    }
+
+    /// add an answer, its id is its position
+    pub fn add_answer(&mut self, text: String) -> &mut Self {
+        self.answers.push(PollAnswer {
+            id: self.answers.len().to_string(),
+            text,
+        });
+        self
+    }
+
+    pub fn max_selections(&mut self, max_selections: u32) -> &mut Self {
+        self.content.max_selections(max_selections);
+        self
+    }
+
+    pub fn anonymous(&mut self, anonymous: bool) -> &mut Self {
+        self.content.anonymous(anonymous);
+        self
+    }
+
+    pub fn allow_vote_change(&mut self, allow_vote_change: bool) -> &mut Self {
+        self.content.allow_vote_change(allow_vote_change);
+        self
+    }
+
+    pub fn closes_at_from_rfc3339(&mut self, closes_at: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&closes_at)?.into();
+        self.content.closes_at(Some(dt));
+        Ok(())
+    }
+
+    pub fn unset_closes_at(&mut self) -> &mut Self {
+        self.content.closes_at(None);
+        self
+    }
+
+    pub fn display(&mut self, display: Box<Display>) -> &mut Self {
+        self.content.display(Some(*display));
+        self
+    }
+
+    pub fn unset_display(&mut self) -> &mut Self {
+        self.content.display(None);
+        self
+    }
+
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        if self.answers.len() < 2 {
+            bail!("A poll needs at least two answers");
+        }
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let content = self.content.clone().answers(self.answers.clone()).build()?;
+        if content.max_selections == 0 || content.max_selections as usize > content.answers.len() {
+            bail!("Voters must be able to choose between one and all answers");
+        }
+
+        RUNTIME
+            .spawn(async move {
+                let permitted = room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?;
+                if !permitted {
+                    bail!("No permissions to send message in this room");
+                }
+                let response = room.send(content).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+}
+
+#[derive(Clone)]
+pub struct PollUpdateBuilder {
+    client: Client,
+    room: Room,
+    content: polls::PollUpdateBuilder,
+}
+
+impl PollUpdateBuilder {
+    pub fn question(&mut self, question: String) -> &mut Self {
+        self.content.question(Some(question));
+        self
+    }
+
+    pub fn unset_question_update(&mut self) -> &mut Self {
+        self.content.question(None);
+        self
+    }
+
+    pub fn description_text(&mut self, body: String) -> &mut Self {
+        self.content
+            .description(Some(Some(TextMessageEventContent::plain(body))));
+        self
+    }
+
+    pub fn description_markdown(&mut self, body: String) -> &mut Self {
+        self.content
+            .description(Some(Some(TextMessageEventContent::markdown(body))));
+        self
+    }
+
+    pub fn unset_description(&mut self) -> &mut Self {
+        self.content.description(Some(None));
+        self
+    }
+
+    pub fn unset_description_update(&mut self) -> &mut Self {
+        self.content
+            .description(None::<Option<TextMessageEventContent>>);
+        self
+    }
+
+    pub fn closes_at_from_rfc3339(&mut self, closes_at: String) -> Result<()> {
+        let dt: UtcDateTime = DateTime::parse_from_rfc3339(&closes_at)?.into();
+        self.content.closes_at(Some(Some(dt)));
+        Ok(())
+    }
+
+    /// close the poll right away
+    pub fn close_now(&mut self) -> &mut Self {
+        self.content.closes_at(Some(Some(chrono::Utc::now())));
+        self
+    }
+
+    pub fn unset_closes_at(&mut self) -> &mut Self {
+        self.content.closes_at(Some(None));
+        self
+    }
+
+    pub fn unset_closes_at_update(&mut self) -> &mut Self {
+        self.content.closes_at(None::<Option<UtcDateTime>>);
+        self
+    }
+
+    pub fn display(&mut self, display: Box<Display>) -> &mut Self {
+        self.content.display(Some(Some(*display)));
+        self
+    }
+
+    pub fn unset_display(&mut self) -> &mut Self {
+        self.content.display(Some(None));
+        self
+    }
+
+    pub fn unset_display_update(&mut self) -> &mut Self {
+        self.content.display(None::<Option<Display>>);
+        self
+    }
+
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let content = self.content.build()?;
+
+        RUNTIME
+            .spawn(async move {
+                let permitted = room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?;
+                if !permitted {
+                    bail!("No permissions to send message in this room");
+                }
+                let response = room.send(content).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PollsManager {
+    client: Client,
+    room: Room,
+    inner: models::PollsManager,
+    anonymous: bool,
+}
+
+impl Deref for PollsManager {
+    type Target = models::PollsManager;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl PollsManager {
+    pub(crate) async fn new(
+        client: Client,
+        room: Room,
+        event_id: OwnedEventId,
+        anonymous: bool,
+    ) -> Result<PollsManager> {
+        RUNTIME
+            .spawn(async move {
+                let inner =
+                    models::PollsManager::from_store_and_event_id(client.store(), &event_id).await;
+                Ok(PollsManager {
+                    client,
+                    room,
+                    inner,
+                    anonymous,
+                })
+            })
+            .await?
+    }
+
+    pub fn stats(&self) -> models::PollStats {
+        self.inner.stats().clone()
+    }
+
+    pub fn has_votes(&self) -> bool {
+        *self.stats().has_votes()
+    }
+
+    pub fn total_voter_count(&self) -> u32 {
+        *self.stats().total_voter_count()
+    }
+
+    pub fn count_for(&self, answer_id: String) -> u32 {
+        self.inner.stats().count_for(&answer_id)
+    }
+
+    /// the answers my vote counts for, empty if I haven’t voted
+    pub async fn my_vote(&self) -> Result<Vec<String>> {
+        let manager = self.inner.clone();
+        let my_id = self.client.user_id()?;
+        RUNTIME
+            .spawn(async move {
+                let results = manager.results().await?;
+                Ok(results.vote_of(&my_id).cloned().unwrap_or_default())
+            })
+            .await?
+    }
+
+    /// who chose the given answer, not available on anonymous polls
+    pub async fn voters_for(&self, answer_id: String) -> Result<Vec<OwnedUserId>> {
+        if self.anonymous {
+            bail!("Voters of anonymous polls are hidden");
+        }
+        let manager = self.inner.clone();
+        RUNTIME
+            .spawn(async move { Ok(manager.results().await?.voters_for(&answer_id)) })
+            .await?
+    }
+
+    fn is_joined(&self) -> bool {
+        matches!(self.room.state(), RoomState::Joined)
+    }
+
+    pub fn vote_draft(&self) -> Result<PollVoteDraft> {
+        if !self.is_joined() {
+            bail!("Can only vote in joined rooms");
+        }
+        Ok(PollVoteDraft {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            inner: self.inner.draft_builder(),
+            answers: vec![],
+        })
+    }
+
+    pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(self.subscribe()).map(|_| true)
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        self.client.subscribe(self.inner.update_key())
+    }
+}
+
+pub struct PollVoteDraft {
+    client: Client,
+    room: Room,
+    inner: PollVoteBuilder,
+    answers: Vec<String>,
+}
+
+impl PollVoteDraft {
+    pub fn add_answer(&mut self, answer_id: String) -> &mut Self {
+        self.answers.push(answer_id);
+        self
+    }
+
+    /// sending without answers retracts the vote
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let inner = self.inner.clone().answers(self.answers.clone()).build()?;
+
+        RUNTIME
+            .spawn(async move {
+                let permitted = room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?;
+                if !permitted {
+                    bail!("No permissions to send message in this room");
+                }
+                let response = room.send(inner).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+}
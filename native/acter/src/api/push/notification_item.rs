@@ -262,7 +262,8 @@ impl NotificationItemInner {
                 ActivityContent::DescriptionChange { content, .. } => {
                     content.clone().map(MsgContent::from)
                 }
-                ActivityContent::Comment { content, .. } => Some(MsgContent::from(content)),
+                ActivityContent::Comment { content, .. }
+                | ActivityContent::CommentReply { content, .. } => Some(MsgContent::from(content)),
                 ActivityContent::Boost {
                     first_slide: Some(first_slide),
                     ..
@@ -461,15 +462,25 @@ impl NotificationItemBuilder {
         event: AnyActerEvent,
     ) -> Result<NotificationItem> {
         let user_id = client.user_id()?;
-        let activity = match convert_acter_model(client, event).await {
+        let activity = match convert_acter_model(client.clone(), event).await {
             Err(error) => {
                 warn!(?error, "Could not convert acter activity");
                 return Ok(self.build()?);
             }
             Ok(a) => a,
         };
+        // what came of it for us, e.g. our task being unblocked, matters most
+        let activity = activity
+            .follow_ups()
+            .iter()
+            .find(|a| a.whom().contains(&user_id.to_string()))
+            .cloned()
+            .unwrap_or(activity);
 
         let mut builder = self;
+        if activity.hides_sender() {
+            builder.sender(NotificationSender::fallback(client));
+        }
 
         // a few special cases we want to deal with
         let builder = match activity.content() {
@@ -561,6 +572,9 @@ impl NotificationItemBuilder {
             ActivityContent::TaskDueDateChange { new_due_date, .. } => {
                 builder.title("removed due date".to_owned())
             }
+            ActivityContent::TaskMove { to, .. } => {
+                builder.title(to.title().unwrap_or("Task list".to_owned()))
+            }
             ActivityContent::TaskAdd { task_title, .. } => builder.title(task_title.clone()),
             ActivityContent::DescriptionChange {
                 object,
@@ -569,6 +583,33 @@ impl NotificationItemBuilder {
             ActivityContent::ObjectInvitation { object, invitees } => builder
                 .title(object.title().unwrap_or("Object".to_owned()))
                 .mentions_you(invitees.contains(&user_id)),
+            ActivityContent::EventStatusChange {
+                object,
+                reason,
+                notified,
+                ..
+            } => {
+                if let Some(reason) = reason {
+                    builder.msg_content(MsgContent::from(TextMessageEventContent::plain(
+                        reason.clone(),
+                    )));
+                }
+                builder
+                    .title(object.title().unwrap_or("Event".to_owned()))
+                    .mentions_you(notified.contains(&user_id))
+            }
+            ActivityContent::RsvpWaitlistPromotion { object, promoted } => builder
+                .title(object.title().unwrap_or("Event".to_owned()))
+                .mentions_you(promoted.contains(&user_id)),
+            ActivityContent::TaskBlockerComplete {
+                object, assignees, ..
+            } => builder
+                .title(object.title().unwrap_or("Task".to_owned()))
+                .mentions_you(assignees.contains(&user_id)),
+            ActivityContent::TaskAssign { object, assignees }
+            | ActivityContent::TaskUnassign { object, assignees } => builder
+                .title(object.title().unwrap_or("Task".to_owned()))
+                .mentions_you(assignees.contains(&user_id)),
             _ => &mut builder,
         };
 
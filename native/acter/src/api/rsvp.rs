@@ -1,6 +1,6 @@
 pub use acter_core::events::rsvp::RsvpStatus;
 use acter_core::{
-    events::rsvp::RsvpBuilder,
+    events::rsvp::{RsvpBuilder, MAX_GUESTS},
     models::{self, ActerModel, AnyActerModel},
     referencing::{IndexKey, SectionIndex},
 };
@@ -201,6 +201,14 @@ impl Rsvp {
     pub fn status(&self) -> String {
         self.inner.status.to_string()
     }
+
+    pub fn guests(&self) -> u32 {
+        self.inner.guest_count()
+    }
+
+    pub fn note(&self) -> Option<String> {
+        self.inner.note.clone()
+    }
 }
 
 pub struct RsvpDraft {
@@ -219,10 +227,23 @@ impl RsvpDraft {
         self
     }
 
+    pub fn guests(&mut self, guests: u32) -> &mut Self {
+        self.inner.guests(Some(guests));
+        self
+    }
+
+    pub fn note(&mut self, note: String) -> &mut Self {
+        self.inner.note(Some(note));
+        self
+    }
+
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
         let my_id = self.client.user_id()?;
         let inner = self.inner.build()?;
+        if inner.guests.is_some_and(|guests| guests > MAX_GUESTS) {
+            bail!("Can’t bring more than {MAX_GUESTS} guests");
+        }
 
         RUNTIME
             .spawn(async move {
@@ -284,6 +305,44 @@ impl RsvpManager {
         *self.stats().total_rsvp_count()
     }
 
+    pub fn confirmed_count(&self) -> u32 {
+        *self.stats().confirmed_count()
+    }
+
+    pub fn waitlist_count(&self) -> u32 {
+        *self.stats().waitlist_count()
+    }
+
+    pub fn attendee_count(&self) -> u32 {
+        *self.stats().attendee_count()
+    }
+
+    pub fn guest_count(&self) -> u32 {
+        *self.stats().guest_count()
+    }
+
+    pub async fn confirmed_users(&self) -> Result<Vec<OwnedUserId>> {
+        let manager = self.inner.clone();
+        RUNTIME
+            .spawn(async move { Ok(manager.participation().await?.confirmed().clone()) })
+            .await?
+    }
+
+    pub async fn waitlisted_users(&self) -> Result<Vec<OwnedUserId>> {
+        let manager = self.inner.clone();
+        RUNTIME
+            .spawn(async move { Ok(manager.participation().await?.waitlisted().clone()) })
+            .await?
+    }
+
+    pub async fn my_waitlist_position(&self) -> Result<Option<u32>> {
+        let manager = self.inner.clone();
+        let my_id = self.client.user_id()?;
+        RUNTIME
+            .spawn(async move { Ok(manager.participation().await?.waitlist_position(&my_id)) })
+            .await?
+    }
+
     pub async fn rsvp_entries(&self) -> Result<Vec<Rsvp>> {
         let manager = self.inner.clone();
         let client = self.client.clone();
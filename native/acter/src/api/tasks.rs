@@ -1,19 +1,22 @@
 use acter_core::{
     events::{
-        tasks::{self, Priority, TaskBuilder, TaskListBuilder},
-        Display, RefDetails as CoreRefDetails, RefPreview,
+        tasks::{
+            self, ChecklistItem, ChecklistOperation, Priority, SpecialTaskListRole, TaskBuilder,
+            TaskListBuilder, TaskState,
+        },
+        Display, Recurrence, RecurrenceRule, RefDetails as CoreRefDetails, RefPreview,
     },
     models::{self, can_redact, ActerModel, AnyActerModel, TaskStats},
     referencing::{IndexKey, SectionIndex, SpecialListsIndex},
 };
 use anyhow::{bail, Context, Result};
-use chrono::DateTime;
+use chrono::{DateTime, Local};
 use futures::stream::StreamExt;
 use matrix_sdk::room::Room;
 use matrix_sdk_base::{
     ruma::{
         events::{room::message::TextMessageEventContent, MessageLikeEventType},
-        EventId, OwnedEventId, OwnedRoomId, OwnedUserId,
+        EventId, OwnedEventId, OwnedRoomId, OwnedUserId, UserId,
     },
     RoomState,
 };
@@ -22,14 +25,19 @@ use std::{
     collections::{hash_map::Entry, HashMap},
     ops::Deref,
 };
-use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
 use tokio_stream::{wrappers::BroadcastStream, Stream};
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::MsgContent;
 
 use super::{client::Client, deep_linking::RefDetails, spaces::Space, RUNTIME};
 
+/// How long to wait for the client completing a recurring task to send its
+/// next instance before we do
+const NEXT_INSTANCE_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(60);
+
 impl Client {
     pub async fn task_list(&self, key: String, timeout: Option<u8>) -> Result<TaskList> {
         let client = self.clone();
@@ -92,6 +100,43 @@ impl Client {
             .collect())
     }
 
+    /// Keep creating the next instance of my recurring tasks that were done
+    /// without one being sent, e.g. from another client. Instances sent
+    /// concurrently are deduplicated when applied.
+    pub(crate) async fn follow_up_recurring_tasks(&self) -> Result<()> {
+        let mut done_tasks = self
+            .executor()
+            .subscribe(IndexKey::Special(SpecialListsIndex::MyDoneTasks));
+        loop {
+            // give whoever completed it the chance to send it first
+            tokio::time::sleep(NEXT_INSTANCE_GRACE_PERIOD).await;
+            let my_id = self.user_id()?;
+            let done = self
+                .models_of_list_with_room::<models::Task>(IndexKey::Special(
+                    SpecialListsIndex::MyDoneTasks,
+                ))
+                .await?;
+            for (task, room) in done {
+                let Some(next_instance) = task.missing_next_instance(Local::now().date_naive())
+                else {
+                    continue;
+                };
+                if !room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?
+                {
+                    continue;
+                }
+                if let Err(error) = room.send(next_instance.build()?).await {
+                    warn!(?error, task_id = ?task.event_id(), "Sending next instance failed");
+                }
+            }
+            if matches!(done_tasks.recv().await, Err(RecvError::Closed)) {
+                return Ok(());
+            }
+        }
+    }
+
     pub fn subscribe_my_open_tasks_stream(&self) -> impl Stream<Item = bool> {
         BroadcastStream::new(self.subscribe_my_open_tasks()).map(|_| true)
     }
@@ -100,6 +145,80 @@ impl Client {
         self.executor()
             .subscribe(IndexKey::Special(SpecialListsIndex::MyOpenTasks))
     }
+
+    pub async fn my_overdue_tasks(&self) -> Result<Vec<Task>> {
+        self.my_due_tasks(SpecialListsIndex::MyOverdueTasks).await
+    }
+
+    pub async fn my_tasks_due_today(&self) -> Result<Vec<Task>> {
+        self.my_due_tasks(SpecialListsIndex::MyTasksDueToday).await
+    }
+
+    pub async fn my_tasks_due_this_week(&self) -> Result<Vec<Task>> {
+        self.my_due_tasks(SpecialListsIndex::MyTasksDueThisWeek)
+            .await
+    }
+
+    async fn my_due_tasks(&self, list: SpecialListsIndex) -> Result<Vec<Task>> {
+        let client = self.clone();
+        RUNTIME
+            .spawn(async move {
+                let tasks = client
+                    .core
+                    .task_due()
+                    .tasks_at(list, &Local::now())
+                    .await?
+                    .into_iter()
+                    .filter_map(|content| {
+                        let room = client.room_by_id_typed(content.room_id()).ok()?;
+                        Some(Task {
+                            client: client.clone(),
+                            room,
+                            content,
+                        })
+                    })
+                    .collect();
+                Ok(tasks)
+            })
+            .await?
+    }
+
+    pub fn subscribe_my_overdue_tasks_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(
+            self.core
+                .task_due()
+                .subscribe(SpecialListsIndex::MyOverdueTasks),
+        )
+        .map(|_| true)
+    }
+
+    pub fn subscribe_my_tasks_due_today_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(
+            self.core
+                .task_due()
+                .subscribe(SpecialListsIndex::MyTasksDueToday),
+        )
+        .map(|_| true)
+    }
+
+    pub fn subscribe_my_tasks_due_this_week_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(
+            self.core
+                .task_due()
+                .subscribe(SpecialListsIndex::MyTasksDueThisWeek),
+        )
+        .map(|_| true)
+    }
+
+    pub async fn check_task_due_lists(&self) -> Result<u32> {
+        let task_due = self.core.task_due().clone();
+        RUNTIME
+            .spawn(async move {
+                let count = task_due.check(&Local::now()).await?;
+                Ok(u32::try_from(count).unwrap_or(u32::MAX))
+            })
+            .await?
+    }
 }
 
 impl Space {
@@ -144,6 +263,7 @@ pub struct TaskListDraft {
     client: Client,
     room: Room,
     content: TaskListBuilder,
+    states: Vec<TaskState>,
 }
 
 impl TaskListDraft {
@@ -152,6 +272,12 @@ impl TaskListDraft {
         self
     }
 
+    pub fn add_state(&mut self, name: String, done: bool) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.states.push(TaskState::new(id.clone(), name, done));
+        id
+    }
+
     pub fn description_text(&mut self, body: String) -> &mut Self {
         let desc = TextMessageEventContent::plain(body);
         self.content.description(Some(desc));
@@ -215,7 +341,11 @@ impl TaskListDraft {
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
         let my_id = self.client.user_id()?;
-        let content = self.content.build()?;
+        let mut builder = self.content.clone();
+        if !self.states.is_empty() {
+            builder.states(self.states.clone());
+        }
+        let content = builder.build()?;
 
         RUNTIME
             .spawn(async move {
@@ -232,6 +362,23 @@ impl TaskListDraft {
     }
 }
 
+/// The tasks of a list in one workflow state, e.g. a kanban column
+#[derive(Clone, Debug)]
+pub struct TaskStateGroup {
+    state: TaskState,
+    tasks: Vec<Task>,
+}
+
+impl TaskStateGroup {
+    pub fn state(&self) -> TaskState {
+        self.state.clone()
+    }
+
+    pub fn tasks(&self) -> Vec<Task> {
+        self.tasks.clone()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TaskList {
     client: Client,
@@ -406,9 +553,39 @@ impl TaskList {
             client: self.client.clone(),
             room: self.room.clone(),
             content: self.content.updater(),
+            states: self.content.states.clone(),
+            states_changed: false,
         })
     }
 
+    /// Update builder bringing all fields back to how they were at the given
+    /// version of the object history, 0 being the tasklist as created
+    pub async fn revert_builder(&self, version: u32) -> Result<TaskListUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update tasks in joined rooms");
+        }
+        let key = self.content.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let history = client.core.object_history(&key).await?;
+                let target = history.state_at::<tasks::TaskListEventContent>(version as usize)?;
+                let AnyActerModel::TaskList(current) = client.store().get(&key).await? else {
+                    bail!("{key} not a tasklist")
+                };
+                Ok(TaskListUpdateBuilder {
+                    client,
+                    room,
+                    content: current.reverter(&target),
+                    states: target.states,
+                    states_changed: false,
+                })
+            })
+            .await?
+    }
+
     pub fn tasks_stats(&self) -> Result<TaskStats> {
         Ok(self.content.stats().clone())
     }
@@ -417,6 +594,99 @@ impl TaskList {
         self.tasks_with_filter(|_| true).await
     }
 
+    pub fn states(&self) -> Vec<TaskState> {
+        self.content.states()
+    }
+
+    pub async fn tasks_by_state(&self) -> Result<Vec<TaskStateGroup>> {
+        let mut groups = self
+            .content
+            .states()
+            .into_iter()
+            .map(|state| TaskStateGroup {
+                state,
+                tasks: vec![],
+            })
+            .collect::<Vec<_>>();
+        for task in self.tasks().await? {
+            let state = self.content.state_of(&task.content);
+            if let Some(group) = groups.iter_mut().find(|g| g.state.id == state.id) {
+                group.tasks.push(task);
+            }
+        }
+        Ok(groups)
+    }
+
+    pub async fn ical(&self) -> Result<String> {
+        let tasks = self.tasks().await?;
+        Ok(models::task_list_ical_document(
+            &self.content,
+            tasks.iter().map(|t| &t.content),
+        ))
+    }
+
+    pub async fn ical_for_sharing(&self, file_name: String) -> Result<bool> {
+        let ical_data = self.ical().await?;
+        std::fs::write(file_name, ical_data)?;
+        Ok(true)
+    }
+
+    pub async fn import_ical(&self, ics: String) -> Result<Vec<String>> {
+        if !self.is_joined() {
+            bail!("Can only create tasks in joined rooms");
+        }
+        let drafts = models::task_drafts_from_ical(&ics)?;
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let task_list_id = self.content.event_id().to_owned();
+
+        RUNTIME
+            .spawn(async move {
+                let permitted = room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?;
+                if !permitted {
+                    bail!("No permissions to send message in this room");
+                }
+                let can_assign_others = models::can_assign(&room, &my_id).await?;
+                let mut task_ids = vec![];
+                for models::IcalTaskDraft {
+                    mut draft,
+                    assignees,
+                } in drafts
+                {
+                    let content = draft.task_list_id(task_list_id.clone()).build()?;
+                    let task_id = room.send(content).await?.event_id;
+                    for user_id in assignees {
+                        if user_id != my_id && !can_assign_others {
+                            warn!(
+                                ?user_id,
+                                "No permissions to assign others, skipping assignee"
+                            );
+                            continue;
+                        }
+                        if user_id == my_id {
+                            room.send(tasks::TaskSelfAssignEventContent {
+                                task: task_id.clone().into(),
+                            })
+                            .await?;
+                        } else {
+                            room.send(tasks::TaskAssignEventContent::new(task_id.clone(), user_id))
+                                .await?;
+                        }
+                    }
+                    task_ids.push(task_id.to_string());
+                }
+                Ok(task_ids)
+            })
+            .await?
+    }
+
+    pub async fn import_ical_file(&self, file_name: String) -> Result<Vec<String>> {
+        let ics = std::fs::read_to_string(file_name)?;
+        self.import_ical(ics).await
+    }
+
     pub async fn task(&self, task_id: String) -> Result<Task> {
         let event_id = EventId::parse(task_id)?;
         self.tasks_with_filter(move |t| t.event_id() == event_id)
@@ -472,6 +742,13 @@ impl TaskList {
         let event_id = self.content.event_id().to_owned();
         crate::AttachmentsManager::new(client, room, event_id).await
     }
+
+    pub async fn time_tracking(&self) -> Result<crate::TimeTrackingManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::TimeTrackingManager::new(client, room, event_id).await
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -506,6 +783,66 @@ impl Task {
         self.content.task_list_id.event_id.to_string()
     }
 
+    pub fn parent_task_id_str(&self) -> Option<String> {
+        self.content.parent_task().map(ToString::to_string)
+    }
+
+    pub fn is_subtask(&self) -> bool {
+        self.content.is_subtask()
+    }
+
+    pub fn subtasks_count(&self) -> u32 {
+        *self.content.subtask_stats().tasks_count()
+    }
+
+    pub fn done_subtasks_count(&self) -> u32 {
+        *self.content.subtask_stats().done_count()
+    }
+
+    pub fn open_subtasks_count(&self) -> u32 {
+        *self.content.subtask_stats().open_count()
+    }
+
+    pub fn blocked_by_ids(&self) -> Vec<String> {
+        self.content
+            .blocked_by()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn blocks_ids(&self) -> Vec<String> {
+        self.content
+            .blocks()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.content.is_blocked()
+    }
+
+    pub fn recurrence_rule(&self) -> Option<String> {
+        self.content.recurrence.as_ref().map(ToString::to_string)
+    }
+
+    pub fn original_task_id_str(&self) -> String {
+        self.content.original_task().to_string()
+    }
+
+    pub fn next_instance_id_str(&self) -> Option<String> {
+        self.content.superseded_by().map(ToString::to_string)
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.content.is_trashed()
+    }
+
+    pub fn checklist(&self) -> Vec<ChecklistItem> {
+        self.content.checklist.clone()
+    }
+
     pub fn description(&self) -> Option<MsgContent> {
         self.content.description.as_ref().map(MsgContent::from)
     }
@@ -557,6 +894,14 @@ impl Task {
         self.content.progress_percent
     }
 
+    pub fn estimated_minutes(&self) -> Option<u32> {
+        self.content.estimated_minutes
+    }
+
+    pub fn state_id(&self) -> Option<String> {
+        self.content.state.clone()
+    }
+
     pub fn keywords(&self) -> Vec<String> {
         // don’t use cloned().
         // create string vector to deallocate string item using toDartString().
@@ -582,6 +927,14 @@ impl Task {
 
 /// Custom functions
 impl Task {
+    pub(crate) fn new(client: Client, room: Room, content: models::Task) -> Self {
+        Task {
+            client,
+            room,
+            content,
+        }
+    }
+
     pub async fn refresh(&self) -> Result<Task> {
         let key = self.content.event_id().to_owned();
         let client = self.client.clone();
@@ -669,6 +1022,104 @@ impl Task {
             .await?
     }
 
+    pub async fn can_assign_others(&self) -> Result<bool> {
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        RUNTIME
+            .spawn(async move { Ok(models::can_assign(&room, &my_id).await?) })
+            .await?
+    }
+
+    pub async fn assign(&self, user_id: String) -> Result<OwnedEventId> {
+        if !self.is_joined() {
+            bail!("Can only update tasks in joined rooms");
+        }
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let user_id = UserId::parse(user_id)?;
+        if user_id == my_id {
+            // doesn’t take the power to assign others
+            return self.assign_self().await;
+        }
+        let content = self.content.assign_event_content(user_id.clone());
+
+        RUNTIME
+            .spawn(async move {
+                if !models::can_assign(&room, &my_id).await? {
+                    bail!("No permissions to assign others in this room");
+                }
+                let response = room.send(content).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+
+    pub async fn unassign(&self, user_id: String) -> Result<OwnedEventId> {
+        if !self.is_joined() {
+            bail!("Can only update tasks in joined rooms");
+        }
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let user_id = UserId::parse(user_id)?;
+        if user_id == my_id {
+            // doesn’t take the power to assign others
+            return self.unassign_self().await;
+        }
+        let content = self.content.unassign_event_content(user_id.clone());
+
+        RUNTIME
+            .spawn(async move {
+                if !models::can_assign(&room, &my_id).await? {
+                    bail!("No permissions to unassign others in this room");
+                }
+                let response = room.send(content).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+
+    fn space(&self) -> Space {
+        Space::new(
+            self.client.clone(),
+            crate::Room::new(self.client.core.clone(), self.room.clone()),
+        )
+    }
+
+    pub async fn trash(&self) -> Result<OwnedEventId> {
+        if self.content.is_trashed() {
+            bail!("Task is already in the trash");
+        }
+        let trash = self.space().trash_task_list().await?;
+        let mut updater = self.update_builder()?;
+        updater.task_list(trash.event_id_str())?;
+        updater.send().await
+    }
+
+    pub async fn restore(&self) -> Result<OwnedEventId> {
+        let Some(origin) = self.content.trashed_from().map(ToOwned::to_owned) else {
+            bail!("Task isn’t in the trash");
+        };
+        let client = self.client.clone();
+        let key = origin.clone();
+        let origin_exists = RUNTIME
+            .spawn(async move {
+                matches!(
+                    client.store().get(&key).await,
+                    Ok(AnyActerModel::TaskList(_))
+                )
+            })
+            .await?;
+        // if the original list is gone, it lands in the inbox
+        let target = if origin_exists {
+            origin.to_string()
+        } else {
+            self.space().inbox_task_list().await?.event_id_str()
+        };
+        let mut updater = self.update_builder()?;
+        updater.task_list(target)?;
+        updater.send().await
+    }
+
     pub fn update_builder(&self) -> Result<TaskUpdateBuilder> {
         if !self.is_joined() {
             bail!("Can only update tasks in joined rooms");
@@ -676,10 +1127,83 @@ impl Task {
         Ok(TaskUpdateBuilder {
             client: self.client.clone(),
             room: self.room.clone(),
+            task: self.content.clone(),
             content: self.content.updater(),
+            checklist: vec![],
+            progress_from_state: false,
+        })
+    }
+
+    /// Update builder bringing all fields back to how they were at the given
+    /// version of the object history, 0 being the task as created
+    pub async fn revert_builder(&self, version: u32) -> Result<TaskUpdateBuilder> {
+        if !self.is_joined() {
+            bail!("Can only update tasks in joined rooms");
+        }
+        let key = self.content.event_id().to_owned();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let history = client.core.object_history(&key).await?;
+                let target = history.state_at::<tasks::TaskEventContent>(version as usize)?;
+                let AnyActerModel::Task(current) = client.store().get(&key).await? else {
+                    bail!("{key} not a task")
+                };
+                Ok(TaskUpdateBuilder {
+                    client,
+                    room,
+                    content: current.reverter(&target),
+                    task: current,
+                    checklist: vec![],
+                    progress_from_state: false,
+                })
+            })
+            .await?
+    }
+
+    pub fn subtask_builder(&self) -> Result<TaskDraft> {
+        if !self.is_joined() {
+            bail!("Can only create tasks in joined rooms");
+        }
+        let mut content = TaskBuilder::default();
+        content
+            .task_list_id(self.content.task_list_id.event_id.clone())
+            .parent_task(self.content.event_id().to_owned());
+        Ok(TaskDraft {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            content,
         })
     }
 
+    pub async fn subtasks(&self) -> Result<Vec<Task>> {
+        let subtasks_key = self.content.subtasks_key();
+        let client = self.client.clone();
+        let room = self.room.clone();
+        RUNTIME
+            .spawn(async move {
+                let res = client
+                    .store()
+                    .get_list(&subtasks_key)
+                    .await
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|e| match e {
+                        AnyActerModel::Task(content) => Some(Task {
+                            client: client.clone(),
+                            room: room.clone(),
+                            content,
+                        }),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(res)
+            })
+            .await?
+    }
+
     pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
         BroadcastStream::new(self.subscribe()).map(|_| true)
     }
@@ -703,6 +1227,13 @@ impl Task {
         crate::AttachmentsManager::new(client, room, event_id).await
     }
 
+    pub async fn time_tracking(&self) -> Result<crate::TimeTrackingManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.content.event_id().to_owned();
+        crate::TimeTrackingManager::new(client, room, event_id).await
+    }
+
     pub async fn invitations(&self) -> Result<crate::ObjectInvitationsManager> {
         let client = self.client.clone();
         let room = self.room.clone();
@@ -746,6 +1277,31 @@ impl TaskDraft {
         self
     }
 
+    pub fn blocked_by(&mut self, task_ids: &mut Vec<String>) -> Result<()> {
+        let task_ids = task_ids
+            .iter()
+            .map(EventId::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.content.blocked_by(task_ids);
+        Ok(())
+    }
+
+    pub fn unset_blocked_by(&mut self) -> &mut Self {
+        self.content.blocked_by(vec![]);
+        self
+    }
+
+    pub fn recurrence_rule(&mut self, rrule: String) -> Result<()> {
+        let rule = rrule.parse::<RecurrenceRule>()?;
+        self.content.recurrence(Recurrence::Rule(rule));
+        Ok(())
+    }
+
+    pub fn unset_recurrence_rule(&mut self) -> &mut Self {
+        self.content.recurrence(None);
+        self
+    }
+
     pub fn due_date(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
         self.content
             .due_date(chrono::NaiveDate::from_ymd_opt(year, month, day));
@@ -803,6 +1359,38 @@ impl TaskDraft {
         self
     }
 
+    pub fn state(&mut self, state: Box<TaskState>) -> &mut Self {
+        if state.done {
+            self.content.progress_percent(Some(100));
+        }
+        self.content.state(Some(state.id));
+        self
+    }
+
+    pub fn estimated_minutes(&mut self, minutes: u32) -> &mut Self {
+        self.content.estimated_minutes(Some(minutes));
+        self
+    }
+
+    pub fn unset_estimated_minutes(&mut self) -> &mut Self {
+        self.content.estimated_minutes(None);
+        self
+    }
+
+    #[allow(clippy::ptr_arg)]
+    pub fn checklist_items(&mut self, texts: &mut Vec<String>) -> &mut Self {
+        let items = texts
+            .iter()
+            .map(|text| ChecklistItem {
+                id: Uuid::new_v4().to_string(),
+                text: text.clone(),
+                done: false,
+            })
+            .collect::<Vec<_>>();
+        self.content.checklist(items);
+        self
+    }
+
     #[allow(clippy::ptr_arg)]
     pub fn keywords(&mut self, keywords: &mut Vec<String>) -> &mut Self {
         self.content.keywords(keywords.to_vec());
@@ -859,7 +1447,11 @@ impl TaskDraft {
 pub struct TaskUpdateBuilder {
     client: Client,
     room: Room,
+    task: models::Task,
     content: tasks::TaskUpdateBuilder,
+    checklist: Vec<ChecklistOperation>,
+    /// whether the progress change was made by moving the state
+    progress_from_state: bool,
 }
 
 impl TaskUpdateBuilder {
@@ -873,6 +1465,87 @@ impl TaskUpdateBuilder {
         self
     }
 
+    pub fn task_list(&mut self, task_list_id: String) -> Result<()> {
+        let task_list_id = EventId::parse(task_list_id)?;
+        self.content.task_list_id(Some(task_list_id));
+        Ok(())
+    }
+
+    pub fn unset_task_list_update(&mut self) -> &mut Self {
+        self.content.task_list_id(None);
+        self
+    }
+
+    /// returns the id of the new item
+    pub fn add_checklist_item(&mut self, text: String) -> String {
+        self.add_checklist_item_inner(text, None)
+    }
+
+    /// returns the id of the new item
+    pub fn add_checklist_item_after(&mut self, text: String, after_id: String) -> String {
+        self.add_checklist_item_inner(text, Some(after_id))
+    }
+
+    fn add_checklist_item_inner(&mut self, text: String, after: Option<String>) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.checklist.push(ChecklistOperation::Add {
+            item: ChecklistItem {
+                id: id.clone(),
+                text,
+                done: false,
+            },
+            after,
+        });
+        id
+    }
+
+    pub fn toggle_checklist_item(&mut self, item_id: String, done: bool) -> &mut Self {
+        self.checklist
+            .push(ChecklistOperation::Toggle { id: item_id, done });
+        self
+    }
+
+    pub fn remove_checklist_item(&mut self, item_id: String) -> &mut Self {
+        self.checklist
+            .push(ChecklistOperation::Remove { id: item_id });
+        self
+    }
+
+    pub fn parent_task(&mut self, parent_task_id: String) -> Result<()> {
+        let parent_task_id = EventId::parse(parent_task_id)?;
+        self.content.parent_task(Some(Some(parent_task_id)));
+        Ok(())
+    }
+
+    pub fn unset_parent_task(&mut self) -> &mut Self {
+        self.content.parent_task(Some(None));
+        self
+    }
+
+    pub fn unset_parent_task_update(&mut self) -> &mut Self {
+        self.content.parent_task(None);
+        self
+    }
+
+    pub fn blocked_by(&mut self, task_ids: &mut Vec<String>) -> Result<()> {
+        let task_ids = task_ids
+            .iter()
+            .map(EventId::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.content.blocked_by(Some(task_ids));
+        Ok(())
+    }
+
+    pub fn unset_blocked_by(&mut self) -> &mut Self {
+        self.content.blocked_by(Some(vec![]));
+        self
+    }
+
+    pub fn unset_blocked_by_update(&mut self) -> &mut Self {
+        self.content.blocked_by(None);
+        self
+    }
+
     pub fn description_text(&mut self, body: String) -> &mut Self {
         let desc = TextMessageEventContent::plain(body);
         self.content.description(Some(Some(desc)));
@@ -1025,23 +1698,101 @@ impl TaskUpdateBuilder {
             progress_percent = 100;
         }
         self.content.progress_percent(Some(Some(progress_percent)));
+        self.progress_from_state = false;
         self
     }
 
     pub fn unset_progress_percent(&mut self) -> &mut Self {
         self.content.progress_percent(Some(None));
+        self.progress_from_state = false;
         self
     }
 
     pub fn unset_progress_percent_update(&mut self) -> &mut Self {
         self.content.progress_percent(None);
+        self.progress_from_state = false;
+        self
+    }
+
+    /// moving into the done state marks the task done, moving out reopens it
+    pub fn state(&mut self, state: Box<TaskState>) -> &mut Self {
+        if state.done {
+            self.content.progress_percent(Some(Some(100)));
+            self.progress_from_state = true;
+        } else if self.task.is_done() {
+            self.content.progress_percent(Some(None));
+            self.progress_from_state = true;
+        }
+        self.content.state(Some(Some(state.id)));
+        self
+    }
+
+    /// drops the state change, along with the progress change it made
+    pub fn unset_state_update(&mut self) -> &mut Self {
+        self.content.state(None);
+        if std::mem::take(&mut self.progress_from_state) {
+            self.content.progress_percent(None);
+        }
+        self
+    }
+
+    pub fn estimated_minutes(&mut self, minutes: u32) -> &mut Self {
+        self.content.estimated_minutes(Some(Some(minutes)));
+        self
+    }
+
+    pub fn unset_estimated_minutes(&mut self) -> &mut Self {
+        self.content.estimated_minutes(Some(None));
+        self
+    }
+
+    pub fn unset_estimated_minutes_update(&mut self) -> &mut Self {
+        self.content.estimated_minutes(None);
+        self
+    }
+
+    pub fn recurrence_rule(&mut self, rrule: String) -> Result<()> {
+        let rule = rrule.parse::<RecurrenceRule>()?;
+        self.content.recurrence(Some(Some(Recurrence::Rule(rule))));
+        Ok(())
+    }
+
+    pub fn unset_recurrence_rule(&mut self) -> &mut Self {
+        self.content.recurrence(Some(None));
+        self
+    }
+
+    pub fn unset_recurrence_rule_update(&mut self) -> &mut Self {
+        self.content.recurrence(None);
         self
     }
 
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
         let my_id = self.client.user_id()?;
-        let content = self.content.build()?;
+        let mut builder = self.content.clone();
+        if !self.checklist.is_empty() {
+            builder.checklist(self.checklist.clone());
+        }
+        let content = builder.build()?;
+
+        // completing the current instance of a recurring task creates the next one,
+        // be it directly or by checking the last item of its checklist
+        let mut updated = (*self.task).clone();
+        content.apply(&mut updated)?;
+        let marks_done = updated.progress_percent.is_some_and(|p| p >= 100);
+        let next_instance = if marks_done
+            && !self.task.is_done()
+            && self.task.superseded_by().is_none()
+            && content.recurrence.is_none()
+        {
+            self.task
+                .next_instance(chrono::Local::now().date_naive())
+                .map(|builder| builder.build())
+                .transpose()?
+        } else {
+            None
+        };
 
         RUNTIME
             .spawn(async move {
@@ -1052,6 +1803,9 @@ impl TaskUpdateBuilder {
                     bail!("No permissions to send message in this room");
                 }
                 let response = room.send(content).await?;
+                if let Some(next_instance) = next_instance {
+                    room.send(next_instance).await?;
+                }
                 Ok(response.event_id)
             })
             .await?
@@ -1063,9 +1817,30 @@ pub struct TaskListUpdateBuilder {
     client: Client,
     room: Room,
     content: tasks::TaskListUpdateBuilder,
+    states: Vec<TaskState>,
+    states_changed: bool,
 }
 
 impl TaskListUpdateBuilder {
+    pub fn add_state(&mut self, name: String, done: bool) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.states.push(TaskState::new(id.clone(), name, done));
+        self.states_changed = true;
+        id
+    }
+
+    pub fn remove_state(&mut self, state_id: String) -> &mut Self {
+        self.states.retain(|s| s.id != state_id);
+        self.states_changed = true;
+        self
+    }
+
+    pub fn reset_states(&mut self) -> &mut Self {
+        self.states.clear();
+        self.states_changed = true;
+        self
+    }
+
     pub fn name(&mut self, name: String) -> &mut Self {
         self.content.name(Some(name));
         self
@@ -1154,7 +1929,12 @@ impl TaskListUpdateBuilder {
     pub async fn send(&self) -> Result<OwnedEventId> {
         let room = self.room.clone();
         let my_id = self.client.user_id()?;
-        let content = self.content.build()?;
+        let mut builder = self.content.clone();
+        if self.states_changed {
+            TaskState::validate_workflow(&self.states).map_err(anyhow::Error::msg)?;
+            builder.states(self.states.clone());
+        }
+        let content = builder.build()?;
 
         RUNTIME
             .spawn(async move {
@@ -1180,6 +1960,7 @@ impl Space {
             client: self.client.clone(),
             room: self.inner.room.clone(),
             content: Default::default(),
+            states: vec![],
         })
     }
 
@@ -1191,6 +1972,42 @@ impl Space {
             client: self.client.clone(),
             room: self.inner.room.clone(),
             content,
+            states: vec![],
         })
     }
+
+    pub async fn inbox_task_list(&self) -> Result<TaskList> {
+        self.task_list_with_role(SpecialTaskListRole::Inbox, "Inbox")
+            .await
+    }
+
+    pub async fn trash_task_list(&self) -> Result<TaskList> {
+        self.task_list_with_role(SpecialTaskListRole::Trash, "Trash")
+            .await
+    }
+
+    pub async fn quick_capture_task(&self, title: String) -> Result<OwnedEventId> {
+        let inbox = self.inbox_task_list().await?;
+        let mut draft = inbox.task_builder()?;
+        draft.title(title);
+        draft.send().await
+    }
+
+    /// The tasklist with that role, created if there is none yet. If several
+    /// members created one at the same time, the oldest one wins.
+    async fn task_list_with_role(&self, role: SpecialTaskListRole, name: &str) -> Result<TaskList> {
+        let existing = self
+            .task_lists()
+            .await?
+            .into_iter()
+            .filter(|list| list.role.as_ref() == Some(&role))
+            .min_by_key(|list| list.event_meta().origin_server_ts);
+        if let Some(list) = existing {
+            return Ok(list);
+        }
+        let mut content = TaskListBuilder::default();
+        content.name(name.to_owned()).role(role);
+        let event_id = self.task_list_draft_with_builder(content)?.send().await?;
+        self.client.task_list(event_id.to_string(), Some(30)).await
+    }
 }
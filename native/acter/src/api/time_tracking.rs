@@ -0,0 +1,223 @@
+use acter_core::{
+    events::tasks::TaskTimeLogBuilder,
+    models::{self, ActerModel},
+};
+use anyhow::{bail, Result};
+use futures::stream::StreamExt;
+use matrix_sdk::room::Room;
+use matrix_sdk_base::{
+    ruma::{events::MessageLikeEventType, OwnedEventId, OwnedUserId, UserId},
+    RoomState,
+};
+use std::ops::Deref;
+use tokio::sync::broadcast::Receiver;
+use tokio_stream::{wrappers::BroadcastStream, Stream};
+
+use super::{client::Client, RUNTIME};
+
+#[derive(Clone, Debug)]
+pub struct TimeLog {
+    client: Client,
+    room: Room,
+    inner: models::TaskTimeLog,
+}
+
+impl Deref for TimeLog {
+    type Target = models::TaskTimeLog;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl TimeLog {
+    pub fn event_id_str(&self) -> String {
+        self.inner.event_id().to_string()
+    }
+
+    pub fn task_id_str(&self) -> String {
+        self.inner.task.event_id.to_string()
+    }
+
+    pub fn room_id_str(&self) -> String {
+        self.room.room_id().to_string()
+    }
+
+    pub fn sender(&self) -> OwnedUserId {
+        self.inner.meta.sender.clone()
+    }
+
+    pub fn origin_server_ts(&self) -> u64 {
+        self.inner.meta.origin_server_ts.get().into()
+    }
+
+    pub fn minutes(&self) -> u32 {
+        self.inner.minutes
+    }
+
+    pub fn date(&self) -> Option<String> {
+        self.inner.date.map(|d| d.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn note(&self) -> Option<String> {
+        self.inner.note.clone()
+    }
+}
+
+#[derive(Clone)]
+pub struct TimeLogDraft {
+    client: Client,
+    room: Room,
+    inner: TaskTimeLogBuilder,
+}
+
+impl TimeLogDraft {
+    pub fn minutes(&mut self, minutes: u32) -> &mut Self {
+        self.inner.minutes(minutes);
+        self
+    }
+
+    pub fn date(&mut self, year: i32, month: u32, day: u32) -> &mut Self {
+        self.inner
+            .date(chrono::NaiveDate::from_ymd_opt(year, month, day));
+        self
+    }
+
+    pub fn note(&mut self, note: String) -> &mut Self {
+        self.inner.note(Some(note));
+        self
+    }
+
+    pub async fn send(&self) -> Result<OwnedEventId> {
+        let room = self.room.clone();
+        let my_id = self.client.user_id()?;
+        let inner = self.inner.build()?;
+
+        RUNTIME
+            .spawn(async move {
+                let permitted = room
+                    .can_user_send_message(&my_id, MessageLikeEventType::RoomMessage)
+                    .await?;
+                if !permitted {
+                    bail!("No permissions to send message in this room");
+                }
+                let response = room.send(inner).await?;
+                Ok(response.event_id)
+            })
+            .await?
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TimeTrackingManager {
+    client: Client,
+    room: Room,
+    inner: models::TimeTrackingManager,
+}
+
+impl Deref for TimeTrackingManager {
+    type Target = models::TimeTrackingManager;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl TimeTrackingManager {
+    pub(crate) async fn new(
+        client: Client,
+        room: Room,
+        event_id: OwnedEventId,
+    ) -> Result<TimeTrackingManager> {
+        RUNTIME
+            .spawn(async move {
+                let inner =
+                    models::TimeTrackingManager::from_store_and_event_id(client.store(), &event_id)
+                        .await?;
+                Ok(TimeTrackingManager {
+                    client,
+                    room,
+                    inner,
+                })
+            })
+            .await?
+    }
+
+    pub fn object_id_str(&self) -> String {
+        self.inner.event_id().to_string()
+    }
+
+    pub fn room_id_str(&self) -> String {
+        self.room.room_id().to_string()
+    }
+
+    pub fn total_minutes(&self) -> u64 {
+        *self.inner.total_minutes()
+    }
+
+    pub fn entries_count(&self) -> u32 {
+        *self.inner.total_entries_count()
+    }
+
+    pub fn users_str(&self) -> Vec<String> {
+        self.inner
+            .minutes_per_user()
+            .keys()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    pub fn minutes_by(&self, user_id: String) -> Result<u64> {
+        let user_id = UserId::parse(user_id)?;
+        Ok(self.inner.minutes_by(&user_id))
+    }
+
+    pub async fn reload(&self) -> Result<TimeTrackingManager> {
+        let client = self.client.clone();
+        let room = self.room.clone();
+        let event_id = self.inner.event_id().to_owned();
+        TimeTrackingManager::new(client, room, event_id).await
+    }
+
+    pub async fn time_logs(&self) -> Result<Vec<TimeLog>> {
+        let manager = self.inner.clone();
+        let client = self.client.clone();
+        let room = self.room.clone();
+
+        RUNTIME
+            .spawn(async move {
+                let res = manager
+                    .time_logs()
+                    .await?
+                    .into_iter()
+                    .map(|inner| TimeLog {
+                        client: client.clone(),
+                        room: room.clone(),
+                        inner,
+                    })
+                    .collect();
+                Ok(res)
+            })
+            .await?
+    }
+
+    pub fn log_draft(&self) -> Result<TimeLogDraft> {
+        if !matches!(self.room.state(), RoomState::Joined) {
+            bail!("Can only log time in joined rooms");
+        }
+        if self.inner.is_task_list() {
+            bail!("Time can only be logged on tasks");
+        }
+        Ok(TimeLogDraft {
+            client: self.client.clone(),
+            room: self.room.clone(),
+            inner: self.inner.draft_builder(),
+        })
+    }
+
+    pub fn subscribe_stream(&self) -> impl Stream<Item = bool> {
+        BroadcastStream::new(self.subscribe()).map(|_| true)
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        self.client.subscribe(self.inner.update_key())
+    }
+}
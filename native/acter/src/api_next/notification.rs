@@ -79,6 +79,13 @@ impl UniffiNotificationItem {
                     msg_title = "💬 Comment".to_owned();
                 }
             }
+            "commentReply" => {
+                if let Some(pt) = parent_title {
+                    msg_title = format!("💬 Reply on {pt}");
+                } else {
+                    msg_title = "💬 Reply".to_owned();
+                }
+            }
             "reaction" => {
                 short_msg = Some(sender_name);
                 let reaction = inner.reaction_key().unwrap_or("❤️".to_owned());
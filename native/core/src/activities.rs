@@ -6,8 +6,8 @@ use urlencoding::encode;
 use crate::{
     client::CoreClient,
     events::{
-        attachments::AttachmentContent, news::NewsContent, rsvp::RsvpStatus, RefDetails,
-        UtcDateTime,
+        attachments::AttachmentContent, calendar::EventStatus, news::NewsContent, rsvp::RsvpStatus,
+        RefDetails, UtcDateTime,
     },
     models::{
         status::{
@@ -18,7 +18,7 @@ use crate::{
             RoomServerAclContent, RoomTombstoneContent, RoomTopicContent, SpaceChildContent,
             SpaceParentContent,
         },
-        ActerModel, ActerSupportedRoomStatusEvents, AnyActerModel, EventMeta,
+        ActerModel, ActerSupportedRoomStatusEvents, AnyActerModel, EventMeta, RsvpManager, Task,
     },
     store::Store,
 };
@@ -62,6 +62,13 @@ pub enum ActivityContent {
         object: ActivityObject,
         content: TextMessageEventContent,
     },
+    CommentReply {
+        object: ActivityObject,
+        parent_id: OwnedEventId,
+        /// the author of the comment replied to
+        notified: Vec<OwnedUserId>,
+        content: TextMessageEventContent,
+    },
     Reaction {
         object: ActivityObject,
         key: String,
@@ -83,10 +90,28 @@ pub enum ActivityContent {
         new_date: UtcDateTime,
     },
     // event specific
+    EventStatusChange {
+        object: ActivityObject,
+        status: EventStatus,
+        reason: Option<String>,
+        notified: Vec<OwnedUserId>,
+    },
+    // event specific
     Rsvp {
         object: ActivityObject,
         rsvp: RsvpStatus,
     },
+    // event specific: the RSVP freed spots for people on the waitlist
+    RsvpWaitlistPromotion {
+        object: ActivityObject,
+        promoted: Vec<OwnedUserId>,
+    },
+    // poll specific
+    PollVote {
+        object: ActivityObject,
+        /// the poll hides its voters, so must the activity
+        anonymous: bool,
+    },
     // tasks and task list specific
     TaskAdd {
         object: ActivityObject,
@@ -96,16 +121,38 @@ pub enum ActivityContent {
         object: ActivityObject,
         done: bool,
     },
+    // a task other tasks were blocked by is done
+    TaskBlockerComplete {
+        object: ActivityObject,
+        unblocked: Vec<ActivityObject>,
+        assignees: Vec<OwnedUserId>,
+    },
     TaskDueDateChange {
         object: ActivityObject,
         new_due_date: Option<NaiveDate>,
     },
+    // the task was moved to another tasklist
+    TaskMove {
+        object: ActivityObject,
+        from: ActivityObject,
+        to: ActivityObject,
+    },
     TaskAccept {
         object: ActivityObject,
     },
     TaskDecline {
         object: ActivityObject,
     },
+    // someone else was assigned to the task
+    TaskAssign {
+        object: ActivityObject,
+        assignees: Vec<OwnedUserId>,
+    },
+    // someone else was removed from the task
+    TaskUnassign {
+        object: ActivityObject,
+        assignees: Vec<OwnedUserId>,
+    },
     ObjectInvitation {
         object: ActivityObject,
         invitees: Vec<OwnedUserId>,
@@ -119,16 +166,27 @@ pub enum ActivityContent {
 pub struct Activity {
     inner: ActivityContent,
     meta: EventMeta,
+    follow_ups: Vec<Activity>,
 }
 
 impl Activity {
     fn new(meta: EventMeta, inner: ActivityContent) -> Self {
-        Self { meta, inner }
+        Self {
+            meta,
+            inner,
+            follow_ups: vec![],
+        }
     }
     pub fn content(&self) -> &ActivityContent {
         &self.inner
     }
 
+    /// further activities caused by the same event, e.g. the tasks unblocked
+    /// by completing this one
+    pub fn follow_ups(&self) -> &[Activity] {
+        &self.follow_ups
+    }
+
     // attachment and other might have internal subtypes
     pub fn sub_type_str(&self) -> Option<String> {
         match &self.inner {
@@ -173,6 +231,7 @@ impl Activity {
             ActivityContent::SpaceChild(_) => "spaceChild",
             ActivityContent::SpaceParent(_) => "spaceParent",
             ActivityContent::Comment { .. } => "comment",
+            ActivityContent::CommentReply { .. } => "commentReply",
             ActivityContent::Reaction { .. } => "reaction",
             ActivityContent::Attachment { .. } => "attachment",
             ActivityContent::Reference { .. } => "references",
@@ -183,20 +242,31 @@ impl Activity {
                     "taskReOpen"
                 }
             }
+            ActivityContent::TaskBlockerComplete { .. } => "taskBlockerComplete",
             ActivityContent::TaskDueDateChange { .. } => "taskDueDateChange",
+            ActivityContent::TaskMove { .. } => "taskMove",
             ActivityContent::TaskAccept { .. } => "taskAccept",
             ActivityContent::TaskDecline { .. } => "taskDecline",
+            ActivityContent::TaskAssign { .. } => "taskAssign",
+            ActivityContent::TaskUnassign { .. } => "taskUnassign",
             ActivityContent::Boost { .. } => "news",
             ActivityContent::Creation { .. } => "creation",
             ActivityContent::TitleChange { .. } => "titleChange",
             ActivityContent::DescriptionChange { .. } => "descriptionChange",
             ActivityContent::EventDateChange { .. } => "eventDateChange",
+            ActivityContent::EventStatusChange { status, .. } => match status {
+                EventStatus::Confirmed => "eventConfirmed",
+                EventStatus::Tentative => "eventTentative",
+                EventStatus::Cancelled => "eventCancelled",
+            },
 
             ActivityContent::Rsvp { rsvp, .. } => match rsvp {
                 RsvpStatus::Yes => "rsvpYes",
                 RsvpStatus::Maybe => "rsvpMaybe",
                 RsvpStatus::No => "rsvpNo",
             },
+            ActivityContent::RsvpWaitlistPromotion { .. } => "rsvpWaitlistPromotion",
+            ActivityContent::PollVote { .. } => "pollVote",
             ActivityContent::TaskAdd { .. } => "taskAdd",
             ActivityContent::ObjectInvitation { .. } => "objectInvitation",
             ActivityContent::OtherChanges { .. } => "otherChanges",
@@ -360,6 +430,18 @@ impl Activity {
         &self.meta
     }
 
+    /// whether who caused this activity must not be shown, e.g. votes on
+    /// anonymous polls
+    pub fn hides_sender(&self) -> bool {
+        matches!(
+            self.inner,
+            ActivityContent::PollVote {
+                anonymous: true,
+                ..
+            }
+        )
+    }
+
     pub fn title(&self) -> Option<String> {
         match &self.inner {
             ActivityContent::Attachment { content, .. } => content.name(),
@@ -368,6 +450,14 @@ impl Activity {
         }
     }
 
+    /// why the event status was changed
+    pub fn reason(&self) -> Option<String> {
+        match &self.inner {
+            ActivityContent::EventStatusChange { reason, .. } => reason.clone(),
+            _ => None,
+        }
+    }
+
     pub fn room_avatar(&self) -> Option<String> {
         match &self.inner {
             ActivityContent::RoomAvatar(c) => c.url_new_val(),
@@ -416,22 +506,54 @@ impl Activity {
             ActivityContent::Attachment { object, .. }
             | ActivityContent::Reference { object, .. }
             | ActivityContent::Comment { object, .. }
+            | ActivityContent::CommentReply { object, .. }
             | ActivityContent::Reaction { object, .. }
             | ActivityContent::Creation { object }
             | ActivityContent::TitleChange { object, .. }
             | ActivityContent::DescriptionChange { object, .. }
             | ActivityContent::EventDateChange { object, .. }
+            | ActivityContent::EventStatusChange { object, .. }
             | ActivityContent::OtherChanges { object }
             | ActivityContent::Rsvp { object, .. }
+            | ActivityContent::RsvpWaitlistPromotion { object, .. }
+            | ActivityContent::PollVote { object, .. }
             | ActivityContent::TaskAdd { object, .. }
             | ActivityContent::TaskProgress { object, .. }
+            | ActivityContent::TaskBlockerComplete { object, .. }
             | ActivityContent::TaskDueDateChange { object, .. }
+            | ActivityContent::TaskMove { object, .. }
             | ActivityContent::TaskAccept { object }
             | ActivityContent::TaskDecline { object }
+            | ActivityContent::TaskAssign { object, .. }
+            | ActivityContent::TaskUnassign { object, .. }
             | ActivityContent::ObjectInvitation { object, .. } => Some(object.clone()),
         }
     }
 
+    /// the tasks that aren’t blocked anymore on `taskBlockerComplete`
+    pub fn unblocked(&self) -> Vec<ActivityObject> {
+        match &self.inner {
+            ActivityContent::TaskBlockerComplete { unblocked, .. } => unblocked.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// the tasklist the task was moved away from on `taskMove`
+    pub fn moved_from(&self) -> Option<ActivityObject> {
+        match &self.inner {
+            ActivityContent::TaskMove { from, .. } => Some(from.clone()),
+            _ => None,
+        }
+    }
+
+    /// the tasklist the task was moved to on `taskMove`
+    pub fn moved_to(&self) -> Option<ActivityObject> {
+        match &self.inner {
+            ActivityContent::TaskMove { to, .. } => Some(to.clone()),
+            _ => None,
+        }
+    }
+
     pub fn reaction_key(&self) -> Option<String> {
         if let ActivityContent::Reaction { key, .. } = &self.inner {
             Some(key.clone())
@@ -470,11 +592,18 @@ impl Activity {
             ActivityContent::TitleChange { object, .. }
             | ActivityContent::DescriptionChange { object, .. }
             | ActivityContent::EventDateChange { object, .. }
+            | ActivityContent::EventStatusChange { object, .. }
             | ActivityContent::Rsvp { object, .. }
+            | ActivityContent::RsvpWaitlistPromotion { object, .. }
+            | ActivityContent::PollVote { object, .. }
             | ActivityContent::TaskProgress { object, .. }
+            | ActivityContent::TaskBlockerComplete { object, .. }
             | ActivityContent::TaskDueDateChange { object, .. }
+            | ActivityContent::TaskMove { object, .. }
             | ActivityContent::TaskAccept { object, .. }
             | ActivityContent::TaskDecline { object, .. }
+            | ActivityContent::TaskAssign { object, .. }
+            | ActivityContent::TaskUnassign { object, .. }
             | ActivityContent::OtherChanges { object }
             | ActivityContent::Creation { object, .. }
             | ActivityContent::ObjectInvitation { object, .. } => object.target_url(),
@@ -489,7 +618,8 @@ impl Activity {
                 object.target_url(),
                 encode(self.meta.event_id.as_str()),
             ),
-            ActivityContent::Comment { object, .. } => format!(
+            ActivityContent::Comment { object, .. }
+            | ActivityContent::CommentReply { object, .. } => format!(
                 "{}?section=comments&commentId={}",
                 object.target_url(),
                 encode(self.meta.event_id.as_str()),
@@ -526,21 +656,40 @@ impl Activity {
     }
 
     pub fn whom(&self) -> Vec<String> {
-        let ActivityContent::ObjectInvitation { ref invitees, .. } = self.content() else {
-            return vec![];
+        let users = match self.content() {
+            ActivityContent::ObjectInvitation { invitees, .. } => invitees,
+            ActivityContent::RsvpWaitlistPromotion { promoted, .. } => promoted,
+            ActivityContent::EventStatusChange { notified, .. }
+            | ActivityContent::CommentReply { notified, .. } => notified,
+            ActivityContent::TaskBlockerComplete { assignees, .. }
+            | ActivityContent::TaskAssign { assignees, .. }
+            | ActivityContent::TaskUnassign { assignees, .. } => assignees,
+            _ => return vec![],
         };
-        invitees
+        users
             .iter()
             .map(ToString::to_string)
             .collect::<Vec<String>>()
     }
 
+    /// the comment replied to, for `commentReply`
+    pub fn parent_comment_id_str(&self) -> Option<String> {
+        match &self.inner {
+            ActivityContent::CommentReply { parent_id, .. } => Some(parent_id.to_string()),
+            _ => None,
+        }
+    }
+
     pub fn task_list_id_str(&self) -> Option<String> {
         match &self.inner {
             ActivityContent::TaskAccept { object }
             | ActivityContent::TaskAdd { object, .. }
             | ActivityContent::TaskDecline { object }
             | ActivityContent::TaskDueDateChange { object, .. }
+            | ActivityContent::TaskMove { object, .. }
+            | ActivityContent::TaskBlockerComplete { object, .. }
+            | ActivityContent::TaskAssign { object, .. }
+            | ActivityContent::TaskUnassign { object, .. }
             | ActivityContent::TaskProgress { object, .. } => object.task_list_id_str(),
             _ => None,
         }
@@ -627,11 +776,29 @@ impl Activity {
                     .unwrap_or_else(|| ActivityObject::Unknown {
                         object_id: e.inner.on.event_id.clone(),
                     });
+                // replies tell whose comment they answer, if we know it
+                let parent = match e.reply_to_id() {
+                    Some(parent_id) => match store.get(parent_id).await {
+                        Ok(AnyActerModel::Comment(parent)) => Some(parent),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                let content = e.content.clone();
                 Ok(Self::new(
                     meta,
-                    ActivityContent::Comment {
-                        object,
-                        content: e.content.clone(),
+                    match parent {
+                        Some(parent) => ActivityContent::CommentReply {
+                            object,
+                            parent_id: parent.meta.event_id,
+                            // no need to tell them about their own reply
+                            notified: Some(parent.meta.sender)
+                                .filter(|author| *author != e.meta.sender)
+                                .into_iter()
+                                .collect(),
+                            content,
+                        },
+                        None => ActivityContent::Comment { object, content },
                     },
                 ))
             }
@@ -769,7 +936,116 @@ impl Activity {
                         object_id: e.inner.calendar_event.event_id.clone(),
                     });
 
-                if let Some(new_title) = e.inner.title {
+                let promoted = RsvpManager::capacity_promotions(store, &e)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error finding waitlist promotions");
+                    })
+                    .unwrap_or_default();
+
+                let content = if let Some(status) = e.inner.status {
+                    ActivityContent::EventStatusChange {
+                        object: object.clone(),
+                        status,
+                        reason: e.inner.status_reason.flatten(),
+                        notified: e
+                            .inner
+                            .mentions
+                            .map(|m| m.user_ids.into_iter().collect())
+                            .unwrap_or_default(),
+                    }
+                } else if let Some(new_title) = e.inner.title {
+                    ActivityContent::TitleChange {
+                        object: object.clone(),
+                        new_title,
+                    }
+                } else if let Some(Some(new_content)) = e.inner.description {
+                    ActivityContent::DescriptionChange {
+                        object: object.clone(),
+                        content: Some(new_content),
+                    }
+                } else if let Some(new_date) = e.inner.utc_start.or(e.inner.utc_end) {
+                    ActivityContent::EventDateChange {
+                        object: object.clone(),
+                        new_date,
+                    }
+                } else {
+                    // fallback: other changes
+                    ActivityContent::OtherChanges {
+                        object: object.clone(),
+                    }
+                };
+
+                let mut activity = Self::new(meta.clone(), content);
+                if !promoted.is_empty() {
+                    // raising the capacity let people in
+                    activity.follow_ups.push(Self::new(
+                        meta,
+                        ActivityContent::RsvpWaitlistPromotion { object, promoted },
+                    ));
+                }
+                Ok(activity)
+            }
+
+            // ---- Event
+            AnyActerModel::Rsvp(e) => {
+                let object = store
+                    .get(&e.inner.to.event_id)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error loading parent of comment");
+                    })
+                    .ok()
+                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .unwrap_or_else(|| ActivityObject::Unknown {
+                        object_id: e.inner.to.event_id.clone(),
+                    });
+
+                let promoted = RsvpManager::waitlist_promotions(store, &e)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error finding waitlist promotions");
+                    })
+                    .unwrap_or_default();
+                let mut activity = Self::new(
+                    meta.clone(),
+                    ActivityContent::Rsvp {
+                        object: object.clone(),
+                        rsvp: e.inner.status,
+                    },
+                );
+                if !promoted.is_empty() {
+                    activity.follow_ups.push(Self::new(
+                        meta,
+                        ActivityContent::RsvpWaitlistPromotion { object, promoted },
+                    ));
+                }
+                Ok(activity)
+            }
+
+            // ---- Poll
+            AnyActerModel::Poll(e) => {
+                let object = ActivityObject::Poll {
+                    object_id: e.event_id().to_owned(),
+                    title: e.question(),
+                };
+                Ok(Self::new(meta, ActivityContent::Creation { object }))
+            }
+
+            AnyActerModel::PollUpdate(e) => {
+                let object = store
+                    .get(&e.inner.poll.event_id)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error loading parent of poll update");
+                    })
+                    .ok()
+                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .unwrap_or_else(|| ActivityObject::Unknown {
+                        object_id: e.inner.poll.event_id.clone(),
+                    });
+
+                if let Some(new_title) = e.inner.question {
                     Ok(Self::new(
                         meta,
                         ActivityContent::TitleChange { object, new_title },
@@ -782,45 +1058,61 @@ impl Activity {
                             content: Some(new_content),
                         },
                     ))
-                } else if let Some(new_date) = e.inner.utc_start {
-                    Ok(Self::new(
-                        meta,
-                        ActivityContent::EventDateChange { object, new_date },
-                    ))
-                } else if let Some(new_date) = e.inner.utc_end {
-                    Ok(Self::new(
-                        meta,
-                        ActivityContent::EventDateChange { object, new_date },
-                    ))
                 } else {
                     // fallback: other changes
                     Ok(Self::new(meta, ActivityContent::OtherChanges { object }))
                 }
             }
 
-            // ---- Event
-            AnyActerModel::Rsvp(e) => {
-                let object = store
+            AnyActerModel::PollVote(e) => {
+                let parent = store
                     .get(&e.inner.to.event_id)
                     .await
                     .map_err(|error| {
-                        tracing::error!(?error, "Error loading parent of comment");
+                        tracing::error!(?error, "Error loading parent of poll vote");
                     })
-                    .ok()
+                    .ok();
+                // if we can’t tell, we better don’t reveal the voter
+                let anonymous = match &parent {
+                    Some(AnyActerModel::Poll(poll)) => poll.anonymous,
+                    _ => true,
+                };
+                let object = parent
                     .and_then(|o| ActivityObject::try_from(&o).ok())
                     .unwrap_or_else(|| ActivityObject::Unknown {
                         object_id: e.inner.to.event_id.clone(),
                     });
-
                 Ok(Self::new(
                     meta,
-                    ActivityContent::Rsvp {
-                        object,
-                        rsvp: e.inner.status,
-                    },
+                    ActivityContent::PollVote { object, anonymous },
                 ))
             }
 
+            // ---- Document
+            AnyActerModel::Document(e) => {
+                let object = ActivityObject::Document {
+                    object_id: e.event_id().to_owned(),
+                    title: e.title(),
+                };
+                Ok(Self::new(meta, ActivityContent::Creation { object }))
+            }
+
+            AnyActerModel::DocumentRevision(e) => {
+                let object = store
+                    .get(&e.inner.document.event_id)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error loading parent of document revision");
+                    })
+                    .ok()
+                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .unwrap_or_else(|| ActivityObject::Unknown {
+                        object_id: e.inner.document.event_id.clone(),
+                    });
+                // revisions hold the full state, we can’t tell what changed
+                Ok(Self::new(meta, ActivityContent::OtherChanges { object }))
+            }
+
             // --- Task lists
             AnyActerModel::TaskList(e) => {
                 let object = ActivityObject::TaskList {
@@ -884,28 +1176,68 @@ impl Activity {
                 ))
             }
             AnyActerModel::TaskUpdate(e) => {
-                let object = store
+                let task = store
                     .get(&e.inner.task.event_id)
                     .await
                     .map_err(|error| {
                         tracing::error!(?error, "Error loading parent of comment");
                     })
-                    .ok()
-                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .ok();
+                let object = task
+                    .as_ref()
+                    .and_then(|o| ActivityObject::try_from(o).ok())
                     .unwrap_or_else(|| ActivityObject::Unknown {
                         object_id: e.inner.task.event_id.clone(),
                     });
 
-                if let Some(new_percent) = e.inner.progress_percent {
-                    Ok(Self::new(
+                // on push we see the update before it has been applied, so
+                // the list it leaves is where the stored task still is
+                let moved_from = e.moved_from().map(ToOwned::to_owned).or_else(|| {
+                    let (Some(AnyActerModel::Task(task)), Some(to)) =
+                        (&task, &e.inner.task_list_id)
+                    else {
+                        return None;
+                    };
+                    if &task.task_list_id.event_id != to {
+                        Some(task.task_list_id.event_id.clone())
+                    } else {
+                        // already applied, a trashed one still knows
+                        task.trashed_from().map(ToOwned::to_owned)
+                    }
+                });
+                if let (Some(from), Some(to)) = (moved_from, &e.inner.task_list_id) {
+                    let from = object_or_unknown(store, from).await;
+                    let to = object_or_unknown(store, to.clone()).await;
+                    return Ok(Self::new(
                         meta,
+                        ActivityContent::TaskMove { object, from, to },
+                    ));
+                }
+                if let Some(new_percent) = e.inner.progress_percent {
+                    let done = new_percent
+                        .map(|percent| percent >= 100)
+                        .unwrap_or_default();
+                    let mut activity = Self::new(
+                        meta.clone(),
                         ActivityContent::TaskProgress {
-                            object,
-                            done: new_percent
-                                .map(|percent| percent >= 100)
-                                .unwrap_or_default(),
+                            object: object.clone(),
+                            done,
                         },
-                    ))
+                    );
+                    if let Some(AnyActerModel::Task(task)) = task.filter(|_| done) {
+                        let (unblocked, assignees) = unblocked_by(store, &task).await;
+                        if !unblocked.is_empty() {
+                            activity.follow_ups.push(Self::new(
+                                meta,
+                                ActivityContent::TaskBlockerComplete {
+                                    object,
+                                    unblocked,
+                                    assignees,
+                                },
+                            ));
+                        }
+                    }
+                    Ok(activity)
                 } else if let Some(due_date) = e.inner.due_date {
                     Ok(Self::new(
                         meta,
@@ -963,12 +1295,57 @@ impl Activity {
 
                 Ok(Self::new(meta, ActivityContent::TaskDecline { object }))
             }
+
+            AnyActerModel::TaskAssign(e) => {
+                let object = store
+                    .get(&e.inner.task.event_id)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error loading task of assignment");
+                    })
+                    .ok()
+                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .unwrap_or_else(|| ActivityObject::Unknown {
+                        object_id: e.inner.task.event_id.clone(),
+                    });
+
+                Ok(Self::new(
+                    meta,
+                    ActivityContent::TaskAssign {
+                        object,
+                        assignees: e.assignees(),
+                    },
+                ))
+            }
+
+            AnyActerModel::TaskUnassign(e) => {
+                let object = store
+                    .get(&e.inner.task.event_id)
+                    .await
+                    .map_err(|error| {
+                        tracing::error!(?error, "Error loading task of unassignment");
+                    })
+                    .ok()
+                    .and_then(|o| ActivityObject::try_from(&o).ok())
+                    .unwrap_or_else(|| ActivityObject::Unknown {
+                        object_id: e.inner.task.event_id.clone(),
+                    });
+
+                Ok(Self::new(
+                    meta,
+                    ActivityContent::TaskUnassign {
+                        object,
+                        assignees: e.assignees(),
+                    },
+                ))
+            }
             AnyActerModel::RedactedActerModel(_)
             | AnyActerModel::NewsEntryUpdate(_)
             | AnyActerModel::Story(_)
             | AnyActerModel::StoryUpdate(_)
             | AnyActerModel::CommentUpdate(_)
             | AnyActerModel::AttachmentUpdate(_)
+            | AnyActerModel::TaskTimeLog(_)
             | AnyActerModel::ReadReceipt(_) => Err(crate::Error::Custom(
                 "Converting model into activity not yet supported".to_owned(),
             )),
@@ -980,9 +1357,406 @@ impl Activity {
     }
 }
 
+/// The stored object with `object_id` or, if we don’t have it, an unknown one.
+async fn object_or_unknown(store: &Store, object_id: OwnedEventId) -> ActivityObject {
+    store
+        .get(&object_id)
+        .await
+        .ok()
+        .and_then(|o| ActivityObject::try_from(&o).ok())
+        .unwrap_or(ActivityObject::Unknown { object_id })
+}
+
+/// The tasks that aren’t blocked anymore once `task` is done and who they are
+/// assigned to. Only relies on what was stored before, as on push we see the
+/// completing update before it has been applied.
+async fn unblocked_by(store: &Store, task: &Task) -> (Vec<ActivityObject>, Vec<OwnedUserId>) {
+    let mut unblocked = vec![];
+    let mut assignees = vec![];
+    for blocked in store.get_many(task.blocks().to_vec()).await {
+        let Some(AnyActerModel::Task(blocked)) = blocked else {
+            continue;
+        };
+        if blocked.is_done() || blocked.open_blockers().iter().any(|b| b != task.event_id()) {
+            continue;
+        }
+        assignees.extend(blocked.assignees());
+        unblocked.push(ActivityObject::Task {
+            object_id: blocked.event_id().to_owned(),
+            tl_id: blocked.task_list_id.event_id.clone(),
+            title: blocked.title(),
+        });
+    }
+    assignees.sort();
+    assignees.dedup();
+    (unblocked, assignees)
+}
+
 impl CoreClient {
     pub async fn activity(&self, key: &OwnedEventId) -> crate::Result<Activity> {
         let model = self.store.get(key).await?;
         Activity::for_acter_model(&self.store, model).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events::AnyActerEvent, models::fixtures};
+
+    fn model(
+        event_id: &str,
+        sender: &str,
+        ts: u64,
+        event_type: &str,
+        content: &str,
+    ) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"{sender}",
+            "content":{content},
+            "origin_server_ts":{ts},
+            "event_id":"{event_id}",
+            "user_id":"{sender}"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    fn vote(poll_id: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            &format!("{poll_id}-vote"),
+            "@kira:ds9.acter.global",
+            1672407531453,
+            "global.acter.dev.poll.vote",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"{poll_id}"}},"answers":["quarks"]}}"#
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn votes_on_anonymous_polls_hide_the_voter() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        for (poll_id, anonymous) in [("$anonymous", true), ("$named", false)] {
+            let poll = model(
+                poll_id,
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.poll",
+                &format!(
+                    r#"{{"question":"Where to eat?","anonymous":{anonymous},
+                    "answers":[{{"id":"quarks","text":"Quark’s"}}]}}"#
+                ),
+            )?;
+            executor.handle(poll).await?;
+
+            let activity = Activity::for_acter_model(executor.store(), vote(poll_id)?).await?;
+            assert_eq!(activity.type_str(), "pollVote");
+            assert_eq!(activity.hides_sender(), anonymous);
+        }
+
+        // we don’t know the poll yet, so we can’t tell
+        let activity = Activity::for_acter_model(executor.store(), vote("$unknown")?).await?;
+        assert!(activity.hides_sender());
+        Ok(())
+    }
+    #[tokio::test]
+    async fn completing_a_blocker_is_followed_by_unblocking() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        let list = "$renovation";
+        for model in [
+            model(
+                list,
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.tasklist",
+                r#"{"name":"Renovation"}"#,
+            )?,
+            model(
+                "$plaster",
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.task",
+                &format!(
+                    r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                    "event_id":"{list}"}},"title":"Plaster the walls"}}"#
+                ),
+            )?,
+            model(
+                "$paint",
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.task",
+                &format!(
+                    r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                    "event_id":"{list}"}},"title":"Paint the walls","blocked_by":["$plaster"]}}"#
+                ),
+            )?,
+            model(
+                "$assign",
+                "@kira:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.task.self_assign",
+                r#"{"m.relates_to":{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$paint"}}"#,
+            )?,
+        ] {
+            executor.handle(model).await?;
+        }
+
+        let done = model(
+            "$done",
+            "@odo:ds9.acter.global",
+            1672407531453,
+            "global.acter.dev.task.update",
+            r#"{"m.relates_to":{"rel_type":"global.acter.dev.update",
+            "event_id":"$plaster"},"progress_percent":100}"#,
+        )?;
+        // on push we see it before it was applied, in the feed afterwards
+        for applied in [false, true] {
+            if applied {
+                executor.handle(done.clone()).await?;
+            }
+            let activity = Activity::for_acter_model(executor.store(), done.clone()).await?;
+            assert_eq!(activity.type_str(), "taskComplete");
+            let [follow_up] = activity.follow_ups() else {
+                anyhow::bail!("expected the unblocking to follow up");
+            };
+            assert_eq!(follow_up.type_str(), "taskBlockerComplete");
+            let unblocked = follow_up.unblocked();
+            assert_eq!(unblocked.len(), 1);
+            assert_eq!(unblocked[0].object_id_str(), "$paint");
+            assert_eq!(follow_up.whom(), ["@kira:ds9.acter.global"]);
+        }
+        Ok(())
+    }
+
+    fn rsvp(idx: u64, sender: &str, status: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            &format!("$rsvp{idx}"),
+            sender,
+            1672407531453 + idx,
+            "global.acter.dev.rsvp",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$party"}},"status":{{"type":"{status}"}}}}"#
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn waitlist_promotions_follow_up_rsvps_and_capacity_changes() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        for model in [
+            model(
+                "$party",
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.calendar_event",
+                r#"{"title":"Holosuite party","capacity":1,
+                "utc_start":"2024-03-06T20:00:00Z","utc_end":"2024-03-06T23:00:00Z"}"#,
+            )?,
+            rsvp(1, "@odo:ds9.acter.global", "yes")?,
+            rsvp(2, "@kira:ds9.acter.global", "yes")?,
+        ] {
+            executor.handle(model).await?;
+        }
+
+        // odo drops out and kira gets the spot
+        let drop_out = rsvp(3, "@odo:ds9.acter.global", "no")?;
+        for applied in [false, true] {
+            if applied {
+                executor.handle(drop_out.clone()).await?;
+            }
+            let activity = Activity::for_acter_model(executor.store(), drop_out.clone()).await?;
+            assert_eq!(activity.type_str(), "rsvpNo");
+            let [follow_up] = activity.follow_ups() else {
+                anyhow::bail!("expected the promotion to follow up");
+            };
+            assert_eq!(follow_up.type_str(), "rsvpWaitlistPromotion");
+            assert_eq!(follow_up.whom(), ["@kira:ds9.acter.global"]);
+        }
+
+        let waiting = rsvp(4, "@quark:ds9.acter.global", "yes")?;
+        executor.handle(waiting.clone()).await?;
+        let activity = Activity::for_acter_model(executor.store(), waiting).await?;
+        assert!(activity.follow_ups().is_empty());
+
+        // making room lets quark in
+        let more_room = model(
+            "$more-room",
+            "@odo:ds9.acter.global",
+            1672407531458,
+            "global.acter.dev.calendar_event.update",
+            r#"{"m.relates_to":{"rel_type":"global.acter.dev.update","event_id":"$party"},
+            "capacity":2}"#,
+        )?;
+        for applied in [false, true] {
+            if applied {
+                executor.handle(more_room.clone()).await?;
+            }
+            let activity = Activity::for_acter_model(executor.store(), more_room.clone()).await?;
+            assert_eq!(activity.type_str(), "otherChanges");
+            let [follow_up] = activity.follow_ups() else {
+                anyhow::bail!("expected the promotion to follow up");
+            };
+            assert_eq!(follow_up.type_str(), "rsvpWaitlistPromotion");
+            assert_eq!(follow_up.whom(), ["@quark:ds9.acter.global"]);
+        }
+
+        let manager = RsvpManager::from_store_and_event_id(
+            executor.store(),
+            &OwnedEventId::try_from("$party")?,
+        )
+        .await;
+        assert_eq!(*manager.confirmed_count(), 2);
+        assert_eq!(*manager.waitlist_count(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn status_changes_notify_those_attending() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        for model in [
+            model(
+                "$party",
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.calendar_event",
+                r#"{"title":"Holosuite party",
+                "utc_start":"2024-03-06T20:00:00Z","utc_end":"2024-03-06T23:00:00Z"}"#,
+            )?,
+            rsvp(1, "@odo:ds9.acter.global", "yes")?,
+            rsvp(2, "@kira:ds9.acter.global", "maybe")?,
+            rsvp(3, "@quark:ds9.acter.global", "no")?,
+            rsvp(4, "@sisko:ds9.acter.global", "yes")?,
+        ] {
+            executor.handle(model).await?;
+        }
+
+        // sisko cancels, everyone else coming is told
+        let manager = RsvpManager::from_store_and_event_id(
+            executor.store(),
+            &OwnedEventId::try_from("$party")?,
+        )
+        .await;
+        let sisko = OwnedUserId::try_from("@sisko:ds9.acter.global")?;
+        let attending = manager.attending_users(&sisko).await?;
+        assert_eq!(
+            attending.iter().map(|u| u.as_str()).collect::<Vec<_>>(),
+            ["@kira:ds9.acter.global", "@odo:ds9.acter.global"]
+        );
+
+        let cancel = model(
+            "$cancel",
+            sisko.as_str(),
+            1672407531453,
+            "global.acter.dev.calendar_event.update",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.update","event_id":"$party"}},
+                "status":"cancelled","status_reason":"The holosuite is broken",
+                "m.mentions":{{"user_ids":{}}}}}"#,
+                serde_json::to_string(&attending)?
+            ),
+        )?;
+        let activity = Activity::for_acter_model(executor.store(), cancel.clone()).await?;
+        assert_eq!(activity.type_str(), "eventCancelled");
+        assert_eq!(
+            activity.reason().as_deref(),
+            Some("The holosuite is broken")
+        );
+        assert_eq!(
+            activity.whom(),
+            ["@kira:ds9.acter.global", "@odo:ds9.acter.global"]
+        );
+
+        executor.handle(cancel).await?;
+        let AnyActerModel::CalendarEvent(party) = executor
+            .store()
+            .get(&OwnedEventId::try_from("$party")?)
+            .await?
+        else {
+            anyhow::bail!("Not a calendar event");
+        };
+        assert_eq!(party.status, EventStatus::Cancelled);
+        assert_eq!(
+            party.status_reason.as_deref(),
+            Some("The holosuite is broken")
+        );
+
+        // fixed after all, the reason goes away with it
+        executor
+            .handle(model(
+                "$fixed",
+                "@odo:ds9.acter.global",
+                1672407531463,
+                "global.acter.dev.calendar_event.update",
+                r#"{"m.relates_to":{"rel_type":"global.acter.dev.update","event_id":"$party"},
+                "status":"confirmed","status_reason":null}"#,
+            )?)
+            .await?;
+        let AnyActerModel::CalendarEvent(party) = executor
+            .store()
+            .get(&OwnedEventId::try_from("$party")?)
+            .await?
+        else {
+            anyhow::bail!("Not a calendar event");
+        };
+        assert_eq!(party.status, EventStatus::Confirmed);
+        assert_eq!(party.status_reason, None);
+        Ok(())
+    }
+
+    fn comment(
+        event_id: &str,
+        sender: &str,
+        reply_to: Option<&str>,
+    ) -> anyhow::Result<AnyActerModel> {
+        let reply_to = reply_to
+            .map(|parent| {
+                format!(r#","reply_to":{{"rel_type":"m.references","event_ids":["{parent}"]}}"#)
+            })
+            .unwrap_or_default();
+        model(
+            event_id,
+            sender,
+            1672407531453,
+            "global.acter.dev.comment",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$cleanup"}},"content":{{"body":"Who took my bucket?"}}{reply_to}}}"#
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn replies_notify_the_author_but_not_themselves() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        for model in [
+            model(
+                "$cleanup",
+                "@odo:ds9.acter.global",
+                1672407531453,
+                "global.acter.dev.calendar_event",
+                r#"{"title":"Station cleanup",
+                "utc_start":"2024-03-06T08:00:00Z","utc_end":"2024-03-06T12:00:00Z"}"#,
+            )?,
+            comment("$bucket", "@odo:ds9.acter.global", None)?,
+        ] {
+            executor.handle(model).await?;
+        }
+
+        let answer = comment("$answer", "@quark:ds9.acter.global", Some("$bucket"))?;
+        let activity = Activity::for_acter_model(executor.store(), answer).await?;
+        assert_eq!(activity.type_str(), "commentReply");
+        assert_eq!(activity.whom(), ["@odo:ds9.acter.global"]);
+
+        let follow_up = comment("$follow-up", "@odo:ds9.acter.global", Some("$bucket"))?;
+        let activity = Activity::for_acter_model(executor.store(), follow_up).await?;
+        assert_eq!(activity.type_str(), "commentReply");
+        assert!(activity.whom().is_empty());
+        Ok(())
+    }
+}
@@ -28,6 +28,14 @@ pub enum ActivityObject {
         object_id: OwnedEventId,
         title: String,
     },
+    Poll {
+        object_id: OwnedEventId,
+        title: String,
+    },
+    Document {
+        object_id: OwnedEventId,
+        title: String,
+    },
     Unknown {
         object_id: OwnedEventId,
     },
@@ -42,6 +50,8 @@ impl ActivityObject {
             ActivityObject::TaskList { .. } => "task-list",
             ActivityObject::Task { .. } => "task",
             ActivityObject::Story { .. } => "story",
+            ActivityObject::Poll { .. } => "poll",
+            ActivityObject::Document { .. } => "document",
             ActivityObject::Unknown { .. } => "unknown",
         }
         .to_owned()
@@ -54,6 +64,8 @@ impl ActivityObject {
             | ActivityObject::Task { object_id, .. }
             | ActivityObject::Unknown { object_id, .. }
             | ActivityObject::CalendarEvent { object_id, .. }
+            | ActivityObject::Poll { object_id, .. }
+            | ActivityObject::Document { object_id, .. }
             | ActivityObject::Story { object_id, .. } => object_id.to_string(),
         }
     }
@@ -65,6 +77,8 @@ impl ActivityObject {
             ActivityObject::Pin { title, .. }
             | ActivityObject::TaskList { title, .. }
             | ActivityObject::Task { title, .. }
+            | ActivityObject::Poll { title, .. }
+            | ActivityObject::Document { title, .. }
             | ActivityObject::CalendarEvent { title, .. } => Some(title.clone()),
         }
     }
@@ -81,6 +95,8 @@ impl ActivityObject {
             ActivityObject::CalendarEvent { object_id, .. } => {
                 format!("/events/{}", object_id)
             }
+            ActivityObject::Poll { object_id, .. } => format!("/polls/{}", object_id),
+            ActivityObject::Document { object_id, .. } => format!("/documents/{}", object_id),
             ActivityObject::Unknown { object_id } => {
                 format!("/forward?eventId={}", encode(object_id.as_str()),)
             }
@@ -101,6 +117,8 @@ impl ActivityObject {
             ActivityObject::TaskList { .. } => "📋",      // tasklist-> clipboard
             ActivityObject::CalendarEvent { .. } => "🗓️", // calendar
             ActivityObject::Task { .. } => "☑️",          // task -> checkoff
+            ActivityObject::Poll { .. } => "📊",          // poll -> bar chart
+            ActivityObject::Document { .. } => "📄",      // document -> page
             ActivityObject::Unknown { .. } => "🧩",       // puzzle piece if unknown
             ActivityObject::Story { .. } => "📰",         //  for story
         }
@@ -136,6 +154,14 @@ impl TryFrom<&AnyActerModel> for ActivityObject {
                 tl_id: e.task_list_id.event_id.clone(),
                 title: e.title(),
             }),
+            AnyActerModel::Poll(e) => Ok(ActivityObject::Poll {
+                object_id: e.event_id().to_owned(),
+                title: e.question(),
+            }),
+            AnyActerModel::Document(e) => Ok(ActivityObject::Document {
+                object_id: e.event_id().to_owned(),
+                title: e.title(),
+            }),
             AnyActerModel::RedactedActerModel(_)
             | AnyActerModel::ExplicitInvite(_)
             | AnyActerModel::CalendarEventUpdate(_)
@@ -143,7 +169,13 @@ impl TryFrom<&AnyActerModel> for ActivityObject {
             | AnyActerModel::TaskUpdate(_)
             | AnyActerModel::TaskSelfAssign(_)
             | AnyActerModel::TaskSelfUnassign(_)
+            | AnyActerModel::TaskAssign(_)
+            | AnyActerModel::TaskUnassign(_)
+            | AnyActerModel::TaskTimeLog(_)
             | AnyActerModel::PinUpdate(_)
+            | AnyActerModel::PollUpdate(_)
+            | AnyActerModel::PollVote(_)
+            | AnyActerModel::DocumentRevision(_)
             | AnyActerModel::NewsEntryUpdate(_)
             | AnyActerModel::StoryUpdate(_)
             | AnyActerModel::Comment(_)
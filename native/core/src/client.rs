@@ -1,7 +1,13 @@
 use derive_getters::Getters;
 use matrix_sdk::Client;
 
-use crate::{error::Error, executor::Executor, referencing::ExecuteReference, store::Store};
+use crate::{
+    error::Error,
+    executor::Executor,
+    models::{ReminderScheduler, TaskDueWatcher},
+    referencing::ExecuteReference,
+    store::Store,
+};
 use tokio::sync::broadcast::Receiver;
 
 /// Core Client wrapper
@@ -10,6 +16,8 @@ pub struct CoreClient {
     pub(crate) client: Client,
     pub(crate) store: Store,
     pub(crate) executor: Executor,
+    pub(crate) reminders: ReminderScheduler,
+    pub(crate) task_due: TaskDueWatcher,
 }
 
 impl CoreClient {
@@ -17,10 +25,14 @@ impl CoreClient {
         let store = Store::new(client.clone()).await?;
         let executor = Executor::new(store.clone()).await?;
         client.add_event_handler_context(executor.clone());
+        let reminders = ReminderScheduler::new(executor.clone());
+        let task_due = TaskDueWatcher::new(executor.clone());
 
         Ok(CoreClient {
             store,
             executor,
+            reminders,
+            task_due,
             client,
         })
     }
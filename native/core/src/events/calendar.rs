@@ -1,7 +1,11 @@
+use chrono_tz::Tz;
 use derive_builder::Builder;
 use derive_getters::Getters;
-use matrix_sdk_base::ruma::events::{macros::EventContent, room::message::TextMessageEventContent};
+use matrix_sdk_base::ruma::events::{
+    macros::EventContent, room::message::TextMessageEventContent, Mentions,
+};
 use serde::{Deserialize, Serialize};
+use strum::{Display as StrumDisplay, EnumString};
 use tracing::trace;
 
 use crate::{models::TextMessageContent, util::deserialize_some, Result};
@@ -10,7 +14,7 @@ use crate::{models::TextMessageContent, util::deserialize_some, Result};
 /// modeled after [JMAP Calendar Events](https://jmap.io/spec-calendars.html#calendar-events), extensions to
 /// [ietf rfc8984](https://www.rfc-editor.org/rfc/rfc8984.html#name-event).
 ///
-use super::{Display, Icon, Update, UtcDateTime};
+use super::{Display, Icon, Recurrence, Update, UtcDateTime};
 
 /// Event Location
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -67,6 +71,46 @@ pub enum EventLocation {
     },
 }
 
+/// Whether the event is taking place
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, StrumDisplay, EnumString, Eq, PartialEq,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum EventStatus {
+    #[default]
+    Confirmed,
+    Tentative,
+    Cancelled,
+}
+
+impl EventStatus {
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self, EventStatus::Confirmed)
+    }
+}
+
+/// Reminder ahead of each occurrence of an event, exported as `VALARM`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventReminder {
+    /// How many minutes before the start this reminder is due
+    pub minutes_before: u32,
+}
+
+impl EventReminder {
+    pub fn new(minutes_before: u32) -> Self {
+        EventReminder { minutes_before }
+    }
+
+    pub fn minutes_before(&self) -> u32 {
+        self.minutes_before
+    }
+
+    pub fn offset(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.minutes_before as i64)
+    }
+}
+
 pub struct EventLocationInfo {
     pub inner: EventLocation,
 }
@@ -177,7 +221,8 @@ impl EventLocationInfo {
 ///
 /// modeled after [JMAP Calendar Events](https://jmap.io/spec-calendars.html#calendar-events)
 /// see also the [IETF CalendarEvent](https://www.rfc-editor.org/rfc/rfc8984.html#name-event)
-/// but all timezones have been dumbed down to UTC-only.
+/// but all times are stored in UTC. An optional `time_zone` gives the zone the
+/// event is planned in, which recurrences and all-day events are calculated in.
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
 #[ruma_event(type = "global.acter.dev.calendar_event", kind = MessageLike)]
 #[builder(name = "CalendarEventBuilder", derive(Debug))]
@@ -208,11 +253,52 @@ pub struct CalendarEventEventContent {
     #[serde(default)]
     pub show_without_time: bool,
 
+    /// The IANA time zone this event takes place in
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<Tz>,
+
     /// Where is this event happening?
     #[builder(default)]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub locations: Vec<EventLocation>,
 
+    /// Does this event repeat? `utc_start` and `utc_end` define the first occurrence.
+    /// Rules we don’t support are kept as they are.
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+
+    /// Starts of occurrences excluded from the recurrence (`EXDATE`)
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exdates: Vec<UtcDateTime>,
+
+    /// Starts of additional occurrences outside of the recurrence (`RDATE`)
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rdates: Vec<UtcDateTime>,
+
+    /// Default reminders for everyone, users may override them for themselves
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<EventReminder>,
+
+    /// How many people can attend, further `yes` responses are put on the waitlist
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u32>,
+
+    /// Is this event still taking place?
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "EventStatus::is_confirmed")]
+    pub status: EventStatus,
+
+    /// Why the event was cancelled or is tentative
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+
     // FIXME: manage through `label` as in [MSC2326](https://github.com/matrix-org/matrix-doc/pull/2326)
     #[builder(setter(into), default)]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -265,6 +351,27 @@ impl CalendarEventBuilder {
         self.locations = Some(locations);
         self
     }
+
+    pub fn add_exdate(&mut self, date: UtcDateTime) -> &mut Self {
+        let mut exdates = self.exdates.clone().unwrap_or_default();
+        exdates.push(date);
+        self.exdates = Some(exdates);
+        self
+    }
+
+    pub fn add_rdate(&mut self, date: UtcDateTime) -> &mut Self {
+        let mut rdates = self.rdates.clone().unwrap_or_default();
+        rdates.push(date);
+        self.rdates = Some(rdates);
+        self
+    }
+
+    pub fn add_reminder(&mut self, reminder: EventReminder) -> &mut Self {
+        let mut reminders = self.reminders.clone().unwrap_or_default();
+        reminders.push(reminder);
+        self.reminders = Some(reminders);
+        self
+    }
 }
 
 /// The CalendarEvent Update Event
@@ -322,6 +429,15 @@ pub struct CalendarEventUpdateEventContent {
     )]
     pub show_without_time: Option<bool>,
 
+    /// The IANA time zone this event takes place in
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub time_zone: Option<Option<Tz>>,
+
     /// Where is this event happening?
     #[builder(default)]
     #[serde(
@@ -331,6 +447,79 @@ pub struct CalendarEventUpdateEventContent {
     )]
     pub locations: Option<Vec<EventLocation>>,
 
+    /// Change or remove the recurrence rule
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub recurrence: Option<Option<Recurrence>>,
+
+    /// Replace the excluded occurrences
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub exdates: Option<Vec<UtcDateTime>>,
+
+    /// Replace the additional occurrences
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub rdates: Option<Vec<UtcDateTime>>,
+
+    /// Replace the default reminders
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub reminders: Option<Vec<EventReminder>>,
+
+    /// Change or remove the capacity
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub capacity: Option<Option<u32>>,
+
+    /// Change the status of the event
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub status: Option<EventStatus>,
+
+    /// Change or remove the reason for the status
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub status_reason: Option<Option<String>>,
+
+    /// The users to be notified about this update, e.g. everyone attending
+    /// when the event is cancelled
+    #[builder(setter(into), default)]
+    #[serde(
+        rename = "m.mentions",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mentions: Option<Mentions>,
+
     // FIXME: manage through `label` as in [MSC2326](https://github.com/matrix-org/matrix-doc/pull/2326)
     #[builder(default)]
     #[serde(
@@ -358,6 +547,16 @@ pub struct CalendarEventUpdateEventContent {
     pub display: Option<Option<Display>>,
 }
 
+impl CalendarEventUpdateBuilder {
+    /// Add a reminder, the reminders of this update replace the existing ones
+    pub fn add_reminder(&mut self, reminder: EventReminder) -> &mut Self {
+        let mut reminders = self.reminders.clone().flatten().unwrap_or_default();
+        reminders.push(reminder);
+        self.reminders = Some(Some(reminders));
+        self
+    }
+}
+
 impl CalendarEventUpdateEventContent {
     pub fn apply(&self, calendar_event: &mut CalendarEventEventContent) -> Result<bool> {
         let mut updated = false;
@@ -386,11 +585,51 @@ impl CalendarEventUpdateEventContent {
             updated = true;
         }
 
+        if let Some(recurrence) = &self.recurrence {
+            calendar_event.recurrence.clone_from(recurrence);
+            updated = true;
+        }
+
+        if let Some(exdates) = &self.exdates {
+            calendar_event.exdates.clone_from(exdates);
+            updated = true;
+        }
+
+        if let Some(rdates) = &self.rdates {
+            calendar_event.rdates.clone_from(rdates);
+            updated = true;
+        }
+
+        if let Some(reminders) = &self.reminders {
+            calendar_event.reminders.clone_from(reminders);
+            updated = true;
+        }
+
+        if let Some(capacity) = &self.capacity {
+            calendar_event.capacity = *capacity;
+            updated = true;
+        }
+
+        if let Some(status) = &self.status {
+            calendar_event.status = *status;
+            updated = true;
+        }
+
+        if let Some(status_reason) = &self.status_reason {
+            calendar_event.status_reason.clone_from(status_reason);
+            updated = true;
+        }
+
         if let Some(show_without_time) = &self.show_without_time {
             calendar_event.show_without_time = *show_without_time;
             updated = true;
         }
 
+        if let Some(time_zone) = &self.time_zone {
+            calendar_event.time_zone = *time_zone;
+            updated = true;
+        }
+
         if let Some(display) = &self.display {
             calendar_event.display.clone_from(display);
             updated = true;
@@ -1,6 +1,8 @@
 use derive_builder::Builder;
 use derive_getters::Getters;
-use matrix_sdk_base::ruma::events::{macros::EventContent, room::message::TextMessageEventContent};
+use matrix_sdk_base::ruma::events::{
+    macros::EventContent, room::message::TextMessageEventContent, Mentions,
+};
 use serde::{Deserialize, Serialize};
 
 use super::{BelongsTo, References, Update};
@@ -20,6 +22,16 @@ pub struct CommentEventContent {
     pub reply_to: Option<References>,
 
     pub content: TextMessageEventContent,
+
+    /// The users to be notified about this comment, e.g. the author of the
+    /// comment replied to
+    #[builder(setter(into), default)]
+    #[serde(
+        rename = "m.mentions",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mentions: Option<Mentions>,
 }
 
 /// The Comment Update Event
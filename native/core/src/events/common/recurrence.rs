@@ -0,0 +1,706 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::UtcDateTime;
+use crate::Error;
+
+/// upper limit of periods we walk through without finding a single match
+/// before we consider the rule exhausted
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+fn parse_error(msg: impl Into<String>) -> Error {
+    Error::FailedToParse {
+        model_type: "RRULE".to_owned(),
+        msg: msg.into(),
+    }
+}
+
+/// Frequency of a recurrence rule
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "DAILY" => Ok(Frequency::Daily),
+            "WEEKLY" => Ok(Frequency::Weekly),
+            "MONTHLY" => Ok(Frequency::Monthly),
+            "YEARLY" => Ok(Frequency::Yearly),
+            other => Err(parse_error(format!("unsupported FREQ {other}"))),
+        }
+    }
+}
+
+fn weekday_str(day: &Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, Error> {
+    Ok(match s.to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        other => return Err(parse_error(format!("unknown weekday {other}"))),
+    })
+}
+
+/// A `BYDAY` entry: a weekday with an optional ordinal, e.g. `2TU` for
+/// "the second tuesday" or `-1FR` for "the last friday"
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i8>,
+    pub weekday: Weekday,
+}
+
+impl fmt::Display for WeekdayNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ordinal) = self.ordinal {
+            write!(f, "{ordinal}")?;
+        }
+        f.write_str(weekday_str(&self.weekday))
+    }
+}
+
+impl FromStr for WeekdayNum {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < 2 || !s.is_char_boundary(s.len() - 2) {
+            return Err(parse_error(format!("invalid BYDAY value {s}")));
+        }
+        let (ordinal, day) = s.split_at(s.len() - 2);
+        let ordinal = if ordinal.is_empty() {
+            None
+        } else {
+            let ord = ordinal
+                .parse::<i8>()
+                .map_err(|e| parse_error(format!("invalid BYDAY ordinal {ordinal}: {e}")))?;
+            if ord == 0 || !(-53..=53).contains(&ord) {
+                return Err(parse_error(format!("BYDAY ordinal out of range: {ord}")));
+            }
+            Some(ord)
+        };
+        Ok(WeekdayNum {
+            ordinal,
+            weekday: parse_weekday(day)?,
+        })
+    }
+}
+
+/// Recurrence rule as specified by [RFC 5545 `RRULE`](https://www.rfc-editor.org/rfc/rfc5545#section-3.3.10)
+///
+/// Supports `FREQ` (daily to yearly), `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`,
+/// `BYMONTHDAY`, `BYMONTH` and `WKST`. Serialized as the plain rule string,
+/// e.g. `FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<UtcDateTime>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i8>,
+    pub by_month: Vec<u32>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    pub fn new(frequency: Frequency) -> Self {
+        RecurrenceRule {
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: vec![],
+            by_month_day: vec![],
+            by_month: vec![],
+            week_start: Weekday::Mon,
+        }
+    }
+
+    /// Iterate over all occurrences of this rule for a series starting at
+    /// `start`. The first item is always `start` itself. `until` is the
+    /// `UNTIL` of this rule, converted to the same (local) time as `start`.
+    pub fn iter_from(
+        &self,
+        start: NaiveDateTime,
+        until: Option<NaiveDateTime>,
+    ) -> RecurrenceIter<'_> {
+        RecurrenceIter {
+            rule: self,
+            start,
+            until,
+            period: 0,
+            pending: vec![],
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    /// Iterate over occurrences where `start` and the `UNTIL` are both in UTC
+    pub fn iter_utc(&self, start: UtcDateTime) -> impl Iterator<Item = UtcDateTime> + '_ {
+        self.iter_from(start.naive_utc(), self.until.map(|u| u.naive_utc()))
+            .map(|d| d.and_utc())
+    }
+
+    /// all days of the `idx`-th period of this rule
+    fn period_days(&self, start: NaiveDate, idx: u32) -> Option<Vec<NaiveDate>> {
+        let step = self.interval.max(1).checked_mul(idx)?;
+        Some(match self.frequency {
+            Frequency::Daily => {
+                vec![start.checked_add_signed(Duration::try_days(step as i64)?)?]
+            }
+            Frequency::Weekly => {
+                let offset = (7 + start.weekday().num_days_from_monday()
+                    - self.week_start.num_days_from_monday())
+                    % 7;
+                let week_start = start
+                    .checked_sub_signed(Duration::days(offset as i64))?
+                    .checked_add_signed(Duration::try_weeks(step as i64)?)?;
+                (0..7)
+                    .filter_map(|d| week_start.checked_add_signed(Duration::days(d)))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = start.month0().checked_add(step)?;
+                let year = start.year().checked_add(i32::try_from(months / 12).ok()?)?;
+                let first = NaiveDate::from_ymd_opt(year, months % 12 + 1, 1)?;
+                first
+                    .iter_days()
+                    .take_while(|d| d.month() == first.month())
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                first
+                    .iter_days()
+                    .take_while(|d| d.year() == first.year())
+                    .collect()
+            }
+        })
+    }
+
+    /// whether the given day (taken from `days`, the current period) is part of the rule
+    fn matches(&self, start: NaiveDate, day: &NaiveDate, days: &[NaiveDate]) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&day.month()) {
+            return false;
+        }
+
+        if !self.by_month_day.is_empty() {
+            let last = days_in_month(day);
+            let matched = self.by_month_day.iter().any(|n| {
+                let n = *n as i32;
+                if n > 0 {
+                    n == day.day() as i32
+                } else {
+                    last as i32 + 1 + n == day.day() as i32
+                }
+            });
+            if !matched {
+                return false;
+            }
+        }
+
+        if !self.by_day.is_empty() {
+            let matched = self.by_day.iter().any(|wd| {
+                if wd.weekday != day.weekday() {
+                    return false;
+                }
+                let Some(ordinal) = wd.ordinal else {
+                    return true;
+                };
+                if matches!(self.frequency, Frequency::Daily | Frequency::Weekly) {
+                    // ordinals are not meaningful in daily or weekly rules
+                    return true;
+                }
+                // ordinals are relative to the month or the year
+                let same: Vec<&NaiveDate> = days
+                    .iter()
+                    .filter(|d| {
+                        d.weekday() == wd.weekday
+                            && (self.frequency != Frequency::Yearly
+                                || self.by_month.is_empty()
+                                || d.month() == day.month())
+                    })
+                    .collect();
+                let pos = if ordinal > 0 {
+                    same.get(ordinal as usize - 1)
+                } else {
+                    same.len()
+                        .checked_sub(ordinal.unsigned_abs() as usize)
+                        .and_then(|i| same.get(i))
+                };
+                pos.is_some_and(|d| *d == day)
+            });
+            if !matched {
+                return false;
+            }
+        }
+
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            // without further specification, the start defines the day
+            return match self.frequency {
+                Frequency::Daily => true,
+                Frequency::Weekly => day.weekday() == start.weekday(),
+                Frequency::Monthly => day.day() == start.day(),
+                Frequency::Yearly => {
+                    day.day() == start.day()
+                        && (!self.by_month.is_empty() || day.month() == start.month())
+                }
+            };
+        }
+
+        true
+    }
+}
+
+fn days_in_month(day: &NaiveDate) -> u32 {
+    let (year, month) = if day.month() == 12 {
+        (day.year() + 1, 1)
+    } else {
+        (day.year(), day.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(31)
+}
+
+/// Iterator over the occurrences of a [`RecurrenceRule`]
+pub struct RecurrenceIter<'a> {
+    rule: &'a RecurrenceRule,
+    start: NaiveDateTime,
+    until: Option<NaiveDateTime>,
+    period: u32,
+    pending: Vec<NaiveDateTime>,
+    emitted: u32,
+    done: bool,
+}
+
+impl Iterator for RecurrenceIter<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Some(count) = self.rule.count {
+            if self.emitted >= count {
+                self.done = true;
+                return None;
+            }
+        }
+
+        let next = if self.emitted == 0 {
+            // the start is always the first occurrence
+            Some(self.start)
+        } else {
+            self.next_from_rule()
+        };
+
+        match next {
+            Some(dt) if self.until.is_none_or(|until| dt <= until) => {
+                self.emitted += 1;
+                Some(dt)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl RecurrenceIter<'_> {
+    fn next_from_rule(&mut self) -> Option<NaiveDateTime> {
+        let start_date = self.start.date();
+        let time: NaiveTime = self.start.time();
+        let mut empty_periods = 0;
+        while self.pending.is_empty() {
+            if empty_periods > MAX_EMPTY_PERIODS {
+                return None;
+            }
+            let days = self.rule.period_days(start_date, self.period)?;
+            self.period += 1;
+            if let (Some(until), Some(first)) = (self.until, days.first()) {
+                if first.and_time(time) > until {
+                    return None;
+                }
+            }
+            // reversed so we can pop from the end
+            self.pending = days
+                .iter()
+                .filter(|d| self.rule.matches(start_date, d, &days))
+                .map(|d| d.and_time(time))
+                .filter(|dt| *dt > self.start)
+                .rev()
+                .collect();
+            empty_periods += 1;
+        }
+        self.pending.pop()
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self.by_day.iter().map(ToString::to_string).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(ToString::to_string).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter().map(ToString::to_string).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_str(&self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+/// parse a RFC 5545 `DATE` or `DATE-TIME` into UTC. Floating times are
+/// treated as UTC, a date-only value means the end of that day.
+pub(crate) fn parse_ical_utc(value: &str) -> Result<UtcDateTime, Error> {
+    let value = value.trim();
+    let naive = value.trim_end_matches('Z');
+    if let Ok(dt) = NaiveDateTime::parse_from_str(naive, "%Y%m%dT%H%M%S") {
+        return Ok(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(naive, "%Y%m%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(23, 59, 59))
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| parse_error(format!("invalid date {value}")))
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+        let mut frequency = None;
+        let mut rule = RecurrenceRule::new(Frequency::Daily);
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let Some((key, value)) = part.split_once('=') else {
+                return Err(parse_error(format!("invalid part {part}")));
+            };
+            match key.to_uppercase().as_str() {
+                "FREQ" => frequency = Some(value.parse::<Frequency>()?),
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .map_err(|e| parse_error(format!("invalid INTERVAL {value}: {e}")))?;
+                    if rule.interval == 0 {
+                        return Err(parse_error("INTERVAL must be positive"));
+                    }
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        value
+                            .parse()
+                            .map_err(|e| parse_error(format!("invalid COUNT {value}: {e}")))?,
+                    )
+                }
+                "UNTIL" => rule.until = Some(parse_ical_utc(value)?),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(WeekdayNum::from_str)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = value
+                        .split(',')
+                        .map(|d| match d.parse::<i8>() {
+                            Ok(n) if n != 0 && (-31..=31).contains(&n) => Ok(n),
+                            _ => Err(parse_error(format!("invalid BYMONTHDAY {d}"))),
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTH" => {
+                    rule.by_month = value
+                        .split(',')
+                        .map(|m| match m.parse::<u32>() {
+                            Ok(n) if (1..=12).contains(&n) => Ok(n),
+                            _ => Err(parse_error(format!("invalid BYMONTH {m}"))),
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "WKST" => rule.week_start = parse_weekday(value)?,
+                other => return Err(parse_error(format!("{other} is not supported"))),
+            }
+        }
+
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(parse_error("COUNT and UNTIL must not both be set"));
+        }
+
+        rule.frequency = frequency.ok_or_else(|| parse_error("FREQ is missing"))?;
+        Ok(rule)
+    }
+}
+
+impl Serialize for RecurrenceRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RecurrenceRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        RecurrenceRule::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The recurrence as sent: a rule we can compute the occurrences of or, for
+/// rules using parts we don’t support, the raw rule string kept as is so it
+/// isn’t lost when the event is shown, updated or exported.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Recurrence {
+    Rule(RecurrenceRule),
+    Unsupported(String),
+}
+
+impl Recurrence {
+    /// Parse the rule, keeping the raw string if it isn’t supported
+    pub fn from_raw(raw: &str) -> Self {
+        match raw.parse::<RecurrenceRule>() {
+            Ok(rule) => Recurrence::Rule(rule),
+            Err(_) => Recurrence::Unsupported(raw.trim().to_owned()),
+        }
+    }
+
+    /// The rule, if we support it
+    pub fn rule(&self) -> Option<&RecurrenceRule> {
+        match self {
+            Recurrence::Rule(rule) => Some(rule),
+            Recurrence::Unsupported(_) => None,
+        }
+    }
+
+    pub fn is_supported(&self) -> bool {
+        matches!(self, Recurrence::Rule(_))
+    }
+}
+
+impl From<RecurrenceRule> for Recurrence {
+    fn from(rule: RecurrenceRule) -> Self {
+        Recurrence::Rule(rule)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Rule(rule) => rule.fmt(f),
+            Recurrence::Unsupported(raw) => f.write_str(raw),
+        }
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Recurrence::from_raw(&s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn dt(y: i32, m: u32, d: u32, h: u32) -> UtcDateTime {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn roundtrip() -> Result<(), Error> {
+        for input in [
+            "FREQ=DAILY",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH",
+            "FREQ=MONTHLY;COUNT=5;BYDAY=-1FR",
+            "FREQ=YEARLY;UNTIL=20300101T000000Z;BYMONTHDAY=1;BYMONTH=1,7",
+            "FREQ=WEEKLY;WKST=SU",
+        ] {
+            let rule = input.parse::<RecurrenceRule>()?;
+            assert_eq!(rule.to_string(), input);
+            let json = serde_json::to_string(&rule)?;
+            assert_eq!(serde_json::from_str::<RecurrenceRule>(&json)?, rule);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        for input in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;COUNT=2;UNTIL=20300101",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+        ] {
+            assert!(input.parse::<RecurrenceRule>().is_err(), "{input} parsed");
+        }
+    }
+
+    #[test]
+    fn unsupported_rules_are_kept() -> Result<(), Error> {
+        let json = r#""FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1""#;
+        let recurrence = serde_json::from_str::<Recurrence>(json)?;
+        assert!(!recurrence.is_supported());
+        assert!(recurrence.rule().is_none());
+        assert_eq!(serde_json::to_string(&recurrence)?, json);
+
+        let recurrence = serde_json::from_str::<Recurrence>(r#""FREQ=WEEKLY;BYDAY=TU""#)?;
+        assert!(recurrence.is_supported());
+        assert_eq!(
+            recurrence.rule().map(|r| r.frequency),
+            Some(Frequency::Weekly)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn weekly_with_count() -> Result<(), Error> {
+        let rule = "FREQ=WEEKLY;COUNT=3".parse::<RecurrenceRule>()?;
+        let occurrences: Vec<_> = rule.iter_utc(dt(2024, 3, 5, 10)).collect();
+        assert_eq!(
+            occurrences,
+            vec![dt(2024, 3, 5, 10), dt(2024, 3, 12, 10), dt(2024, 3, 19, 10)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn weekly_by_day_with_until() -> Result<(), Error> {
+        // start on a tuesday, every other week on tuesday and thursday
+        let rule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;UNTIL=20240320T000000Z"
+            .parse::<RecurrenceRule>()?;
+        let occurrences: Vec<_> = rule.iter_utc(dt(2024, 3, 5, 10)).collect();
+        assert_eq!(
+            occurrences,
+            vec![dt(2024, 3, 5, 10), dt(2024, 3, 7, 10), dt(2024, 3, 19, 10)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn monthly_last_friday() -> Result<(), Error> {
+        let rule = "FREQ=MONTHLY;COUNT=4;BYDAY=-1FR".parse::<RecurrenceRule>()?;
+        let occurrences: Vec<_> = rule.iter_utc(dt(2024, 1, 26, 18)).collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                dt(2024, 1, 26, 18),
+                dt(2024, 2, 23, 18),
+                dt(2024, 3, 29, 18),
+                dt(2024, 4, 26, 18)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn monthly_skips_short_months() -> Result<(), Error> {
+        let rule = "FREQ=MONTHLY;COUNT=3".parse::<RecurrenceRule>()?;
+        let occurrences: Vec<_> = rule.iter_utc(dt(2024, 1, 31, 9)).collect();
+        assert_eq!(
+            occurrences,
+            vec![dt(2024, 1, 31, 9), dt(2024, 3, 31, 9), dt(2024, 5, 31, 9)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn yearly_leap_day() -> Result<(), Error> {
+        let rule = "FREQ=YEARLY;COUNT=2".parse::<RecurrenceRule>()?;
+        let start = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap();
+        let occurrences: Vec<_> = rule.iter_from(start, None).collect();
+        assert_eq!(occurrences.len(), 2);
+        assert_eq!(
+            occurrences[1].date(),
+            NaiveDate::from_ymd_opt(2028, 2, 29).unwrap()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn huge_intervals_end_the_series() -> Result<(), Error> {
+        for freq in ["DAILY", "WEEKLY", "MONTHLY", "YEARLY"] {
+            let rule = format!("FREQ={freq};INTERVAL=4294967295").parse::<RecurrenceRule>()?;
+            let occurrences: Vec<_> = rule.iter_utc(dt(2024, 3, 5, 10)).collect();
+            assert_eq!(occurrences, vec![dt(2024, 3, 5, 10)], "{freq}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn daily_interval() -> Result<(), Error> {
+        let rule = "FREQ=DAILY;INTERVAL=3".parse::<RecurrenceRule>()?;
+        let occurrences: Vec<_> = rule.iter_utc(dt(2024, 12, 30, 8)).take(3).collect();
+        assert_eq!(
+            occurrences,
+            vec![dt(2024, 12, 30, 8), dt(2025, 1, 2, 8), dt(2025, 1, 5, 8)]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use derive_builder::Builder;
+use derive_getters::Getters;
+use matrix_sdk_base::ruma::{
+    events::{macros::EventContent, room::message::TextMessageEventContent},
+    OwnedEventId,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Display, Update};
+
+/// The Document Event
+///
+/// A collaborative document, like meeting notes or a policy, living in a
+/// space. It is changed through [`DocumentRevisionEventContent`]s, each one
+/// holding the full new state and the revision it was based on.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.document", kind = MessageLike)]
+#[builder(name = "DocumentBuilder", derive(Debug))]
+pub struct DocumentEventContent {
+    /// The title of the document
+    #[builder(setter(into))]
+    pub title: String,
+
+    /// The rich text body
+    pub body: TextMessageEventContent,
+
+    /// Optionally, a document can be colored
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<Display>,
+}
+
+/// The Document Revision Event
+///
+/// A new version of a document. `parent_revision` is the revision the editor
+/// started from: the document event itself for the first revision. If another
+/// revision based on the same parent got in first, the two are in conflict.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.document.revision", kind = MessageLike)]
+#[builder(name = "DocumentRevisionBuilder", derive(Debug))]
+pub struct DocumentRevisionEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub document: Update,
+
+    /// The revision this one is based on
+    #[builder(setter(into))]
+    pub parent_revision: OwnedEventId,
+
+    /// The title of the document in this revision
+    #[builder(setter(into))]
+    pub title: String,
+
+    /// The body of the document in this revision
+    pub body: TextMessageEventContent,
+
+    /// Set if this revision brings back the state of an older one
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restored_from: Option<OwnedEventId>,
+}
+
+impl DocumentRevisionEventContent {
+    /// Revisions hold the full state, so they replace title and body
+    pub fn apply(&self, document: &mut DocumentEventContent) {
+        document.title.clone_from(&self.title);
+        document.body = self.body.clone();
+    }
+}
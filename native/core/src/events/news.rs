@@ -9,7 +9,7 @@ use matrix_sdk_base::ruma::events::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::{Colorize, ObjRef, Update};
+use super::{Colorize, ObjRef, Update, UtcDateTime};
 use crate::{util::deserialize_some, Result};
 
 // if you change the order of these enum variables, enum value will change and parsing of old content will fail
@@ -241,6 +241,11 @@ pub struct NewsEntryEventContent {
     /// A news entry may have one or more slides of news
     /// which are scrolled through horizontally
     pub slides: Vec<NewsSlide>,
+
+    /// Optionally, the news announce a deadline and show up on the agenda
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<UtcDateTime>,
 }
 
 /// The payload for our news update event.
@@ -261,6 +266,15 @@ pub struct NewsEntryUpdateEventContent {
         deserialize_with = "deserialize_some"
     )]
     pub slides: Option<Vec<NewsSlide>>,
+
+    /// Optionally set or clear the deadline
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub deadline: Option<Option<UtcDateTime>>,
 }
 
 impl NewsEntryUpdateEventContent {
@@ -270,6 +284,10 @@ impl NewsEntryUpdateEventContent {
             task.slides.clone_from(slides);
             updated = true;
         }
+        if let Some(deadline) = &self.deadline {
+            task.deadline.clone_from(deadline);
+            updated = true;
+        }
         Ok(updated)
     }
 }
@@ -4,7 +4,7 @@ use matrix_sdk_base::ruma::events::{macros::EventContent, room::message::TextMes
 use serde::{Deserialize, Serialize};
 use tracing::trace;
 
-use super::{Display, Update};
+use super::{Display, Update, UtcDateTime};
 use crate::{util::deserialize_some, Result};
 
 /// The Pin Event
@@ -29,6 +29,11 @@ pub struct PinEventContent {
     #[builder(setter(into), default)]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display: Option<Display>,
+
+    /// Optionally, a pin marks a deadline and shows up on the agenda
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<UtcDateTime>,
 }
 
 /// The Pin Event
@@ -75,6 +80,15 @@ pub struct PinUpdateEventContent {
         deserialize_with = "deserialize_some"
     )]
     pub display: Option<Option<Display>>,
+
+    /// Optionally set or clear the deadline
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub deadline: Option<Option<UtcDateTime>>,
 }
 
 impl PinUpdateEventContent {
@@ -96,6 +110,10 @@ impl PinUpdateEventContent {
             pin.display.clone_from(display);
             updated = true;
         }
+        if let Some(deadline) = &self.deadline {
+            pin.deadline.clone_from(deadline);
+            updated = true;
+        }
 
         trace!(update = ?self, ?updated, ?pin, "Pin updated");
 
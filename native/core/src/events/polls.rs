@@ -0,0 +1,211 @@
+use derive_builder::Builder;
+use derive_getters::Getters;
+use matrix_sdk_base::ruma::events::{macros::EventContent, room::message::TextMessageEventContent};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use super::{BelongsTo, Display, Update, UtcDateTime};
+use crate::{util::deserialize_some, Result};
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_selections() -> u32 {
+    1
+}
+
+fn is_single_choice(value: &u32) -> bool {
+    *value == 1
+}
+
+/// One of the answers to choose from
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Builder, Getters)]
+pub struct PollAnswer {
+    /// Unique within the poll, referenced by the votes
+    #[builder(setter(into))]
+    pub id: String,
+
+    /// The text shown for this answer
+    #[builder(setter(into))]
+    pub text: String,
+}
+
+/// The Poll Event
+///
+/// A poll living in a space next to pins and events. Each member votes by
+/// sending a [`PollVoteEventContent`], only their latest vote counts unless
+/// vote changes are disabled.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.poll", kind = MessageLike)]
+#[builder(name = "PollBuilder", derive(Debug))]
+pub struct PollEventContent {
+    /// The question asked
+    pub question: String,
+
+    /// Optionally some further explanation
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<TextMessageEventContent>,
+
+    /// The answers to choose from
+    pub answers: Vec<PollAnswer>,
+
+    /// How many answers each voter may choose, 1 for single choice
+    #[builder(default = "1")]
+    #[serde(
+        default = "default_max_selections",
+        skip_serializing_if = "is_single_choice"
+    )]
+    pub max_selections: u32,
+
+    /// Whether the voters are hidden from everyone else
+    #[builder(default)]
+    #[serde(default)]
+    pub anonymous: bool,
+
+    /// Whether voters may change or retract their vote
+    #[builder(default = "true")]
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub allow_vote_change: bool,
+
+    /// Votes sent after this time aren’t counted
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closes_at: Option<UtcDateTime>,
+
+    /// Optionally, a poll can be colored
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<Display>,
+}
+
+impl PollEventContent {
+    pub fn is_multiple_choice(&self) -> bool {
+        self.max_selections > 1
+    }
+
+    /// Whether a vote sent at `at` is too late to be counted
+    pub fn is_closed_at(&self, at: UtcDateTime) -> bool {
+        self.closes_at.is_some_and(|closes_at| at >= closes_at)
+    }
+
+    pub fn answer(&self, id: &str) -> Option<&PollAnswer> {
+        self.answers.iter().find(|a| a.id == id)
+    }
+}
+
+/// The Poll Update Event
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.poll.update", kind = MessageLike)]
+#[builder(name = "PollUpdateBuilder", derive(Debug))]
+pub struct PollUpdateEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub poll: Update,
+
+    /// If you want to update the question
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub question: Option<String>,
+
+    /// Optionally update the description
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub description: Option<Option<TextMessageEventContent>>,
+
+    /// Set, move or clear the closing time, set to now to close the poll
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub closes_at: Option<Option<UtcDateTime>>,
+
+    /// Optionally some displaying parameters
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub display: Option<Option<Display>>,
+}
+
+impl PollUpdateEventContent {
+    pub fn apply(&self, poll: &mut PollEventContent) -> Result<bool> {
+        let mut updated = false;
+        if let Some(question) = &self.question {
+            poll.question.clone_from(question);
+            updated = true;
+        }
+        if let Some(description) = &self.description {
+            poll.description.clone_from(description);
+            updated = true;
+        }
+        if let Some(closes_at) = &self.closes_at {
+            poll.closes_at.clone_from(closes_at);
+            updated = true;
+        }
+        if let Some(display) = &self.display {
+            poll.display.clone_from(display);
+            updated = true;
+        }
+
+        trace!(update = ?self, ?updated, ?poll, "Poll updated");
+
+        Ok(updated)
+    }
+}
+
+/// The Poll Vote Event
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.poll.vote", kind = MessageLike)]
+#[builder(name = "PollVoteBuilder", derive(Debug))]
+pub struct PollVoteEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub to: BelongsTo,
+
+    /// The ids of the chosen answers, none to retract the vote
+    #[builder(default)]
+    #[serde(default)]
+    pub answers: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_minimal_poll_parses() -> Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.poll",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"question":"Where to eat?","answers":[
+                {"id":"quarks","text":"Quark’s"},{"id":"replimat","text":"Replimat"}]},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<OriginalPollEvent>(json_raw)?;
+        assert_eq!(event.content.question, "Where to eat?");
+        assert_eq!(event.content.max_selections, 1);
+        assert!(event.content.allow_vote_change);
+        assert!(!event.content.anonymous);
+        assert!(!event.content.is_multiple_choice());
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
-use matrix_sdk_base::ruma::events::macros::EventContent;
+use matrix_sdk_base::ruma::{events::macros::EventContent, OwnedEventId};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::calendar::EventReminder;
 
 pub static USER_SETTINGS_KEY: &str = "global.acter.user_settings";
+pub static CALENDAR_REMINDERS_KEY: &str = "global.acter.calendar_reminders";
 
 struct BoolDefaults();
 
@@ -46,3 +50,12 @@ impl Default for UserSettingsEventContent {
         }
     }
 }
+
+/// The users own reminders for calendar events in this room, replacing the
+/// reminders set on the event
+#[derive(Debug, Default, Serialize, Deserialize, Clone, EventContent)]
+#[ruma_event(type = "global.acter.calendar_reminders", kind = RoomAccountData)]
+pub struct CalendarRemindersEventContent {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<OwnedEventId, Vec<EventReminder>>,
+}
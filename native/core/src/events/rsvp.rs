@@ -6,6 +6,9 @@ use strum::{Display, ParseError};
 
 use super::BelongsTo;
 
+/// The most guests we count for a single response
+pub const MAX_GUESTS: u32 = 100;
+
 /// RSVP status
 // previously accepted only PascalCase
 // now accept will serialize to kebab-case but also deserialize the previously
@@ -46,4 +49,26 @@ pub struct RsvpEventContent {
 
     /// The status responded by this user
     pub status: RsvpStatus,
+
+    /// How many additional guests this user brings along
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guests: Option<u32>,
+
+    /// A short note to the organizers, e.g. "bringing snacks"
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl RsvpEventContent {
+    /// The guests this user brings, at most [`MAX_GUESTS`]
+    pub fn guest_count(&self) -> u32 {
+        self.guests.unwrap_or_default().min(MAX_GUESTS)
+    }
+
+    /// This user and their guests
+    pub fn party_size(&self) -> u32 {
+        self.guest_count() + 1
+    }
 }
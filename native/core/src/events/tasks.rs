@@ -2,21 +2,30 @@ use chrono_tz::Tz;
 use core::result::Result as CoreResult;
 use derive_builder::Builder;
 use derive_getters::Getters;
-use matrix_sdk_base::ruma::events::{macros::EventContent, room::message::TextMessageEventContent};
+use matrix_sdk_base::ruma::{
+    events::{macros::EventContent, room::message::TextMessageEventContent, Mentions},
+    OwnedEventId, OwnedUserId,
+};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use tracing::trace;
+use tracing::{trace, warn};
 
 /// ToDo Lists and Task Items management
 /// modeled after [JMAP Tasks](https://jmap.io/spec-tasks.html), extensions to
 /// [ietf rfc8984](https://www.rfc-editor.org/rfc/rfc8984.html#name-task).
 ///
-use super::{BelongsTo, Date, Display, Update, UtcDateTime};
-use crate::{util::deserialize_some, Result as ActerResult};
+use super::{BelongsTo, Date, Display, Recurrence, Update, UtcDateTime};
+use crate::{
+    util::{deserialize_some, is_false},
+    Result as ActerResult,
+};
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+/// Special purpose a tasklist can have within its space
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum SpecialTaskListRole {
+    /// where quickly captured tasks land
     Inbox,
+    /// where deleted tasks go, until they are restored
     Trash,
 }
 
@@ -45,12 +54,202 @@ impl Priority {
     }
 }
 
+/// A single checkbox within a task
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChecklistItem {
+    /// Unique within the task, picked by the client adding it
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl ChecklistItem {
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+/// Granular changes to the checklist of a task. Items are addressed by
+/// their id, so concurrent edits of different items don't overwrite each
+/// other and applying the same operation twice doesn't change anything.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ChecklistOperation {
+    /// Add the item after the item with the id given, or at the end
+    Add {
+        #[serde(flatten)]
+        item: ChecklistItem,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<String>,
+    },
+    /// Set the done state of the item
+    Toggle {
+        id: String,
+        done: bool,
+    },
+    Remove {
+        id: String,
+    },
+}
+
+impl ChecklistOperation {
+    /// Apply to the checklist, returns whether it changed
+    pub fn apply(&self, checklist: &mut Vec<ChecklistItem>) -> bool {
+        match self {
+            ChecklistOperation::Add { item, after } => {
+                if checklist.iter().any(|i| i.id == item.id) {
+                    return false; // already added
+                }
+                let pos = after
+                    .as_ref()
+                    .and_then(|after| checklist.iter().position(|i| &i.id == after))
+                    .map(|pos| pos + 1)
+                    .unwrap_or(checklist.len());
+                checklist.insert(pos, item.clone());
+                true
+            }
+            ChecklistOperation::Toggle { id, done } => {
+                let Some(item) = checklist.iter_mut().find(|i| &i.id == id) else {
+                    return false; // removed in the meantime
+                };
+                let changed = item.done != *done;
+                item.done = *done;
+                changed
+            }
+            ChecklistOperation::Remove { id } => {
+                let before = checklist.len();
+                checklist.retain(|i| &i.id != id);
+                before != checklist.len()
+            }
+        }
+    }
+
+    /// The operations turning the `current` checklist into `target`
+    pub fn reverting(current: &[ChecklistItem], target: &[ChecklistItem]) -> Vec<Self> {
+        // items with the same text can stay, only their done state may differ
+        let mut kept = current
+            .iter()
+            .filter(|c| target.iter().any(|t| t.id == c.id && t.text == c.text))
+            .map(|c| c.id.as_str())
+            .collect::<Vec<_>>();
+        let kept_in_target = target
+            .iter()
+            .filter(|t| kept.contains(&t.id.as_str()))
+            .map(|t| t.id.as_str())
+            .collect::<Vec<_>>();
+        // adding always goes after another item or to the end, so if the
+        // order can’t be reached that way, we rebuild the whole list
+        let starts_with_new = target
+            .first()
+            .is_some_and(|t| !kept.contains(&t.id.as_str()));
+        if kept != kept_in_target || (starts_with_new && !kept.is_empty()) {
+            kept.clear();
+        }
+
+        let mut operations = current
+            .iter()
+            .filter(|c| !kept.contains(&c.id.as_str()))
+            .map(|c| ChecklistOperation::Remove { id: c.id.clone() })
+            .collect::<Vec<_>>();
+        let mut previous: Option<String> = None;
+        for item in target {
+            if kept.contains(&item.id.as_str()) {
+                if current
+                    .iter()
+                    .any(|c| c.id == item.id && c.done != item.done)
+                {
+                    operations.push(ChecklistOperation::Toggle {
+                        id: item.id.clone(),
+                        done: item.done,
+                    });
+                }
+            } else {
+                operations.push(ChecklistOperation::Add {
+                    item: item.clone(),
+                    after: previous.clone(),
+                });
+            }
+            previous = Some(item.id.clone());
+        }
+        operations
+    }
+}
+
+/// A step of the workflow of a tasklist, e.g. a column on a kanban board
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TaskState {
+    /// Unique within the tasklist
+    pub id: String,
+    pub name: String,
+    /// Tasks in this state are done, exactly one state of a workflow is
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub done: bool,
+}
+
+impl TaskState {
+    pub fn new(id: String, name: String, done: bool) -> Self {
+        TaskState { id, name, done }
+    }
+
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// The workflow of tasklists not defining their own
+    pub fn defaults() -> Vec<TaskState> {
+        vec![
+            TaskState::new("open".to_owned(), "Open".to_owned(), false),
+            TaskState::new("done".to_owned(), "Done".to_owned(), true),
+        ]
+    }
+
+    /// A workflow needs unique ids, open states and exactly one done state
+    pub fn validate_workflow(states: &[TaskState]) -> CoreResult<(), String> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        if states.iter().filter(|s| s.done).count() != 1 {
+            return Err("Exactly one state has to be marked as done".to_owned());
+        }
+        if states.iter().all(|s| s.done) {
+            return Err("At least one state has to be open".to_owned());
+        }
+        for (idx, state) in states.iter().enumerate() {
+            if states[..idx].iter().any(|s| s.id == state.id) {
+                return Err(format!("State id {} is used twice", state.id));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The TaskList Event
 ///
 /// modeled after [JMAP TaskList](https://jmap.io/spec-tasks.html#tasklists)
 #[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
 #[ruma_event(type = "global.acter.dev.tasklist", kind = MessageLike)]
-#[builder(name = "TaskListBuilder", derive(Debug))]
+#[builder(
+    name = "TaskListBuilder",
+    build_fn(validate = "Self::validate"),
+    derive(Debug)
+)]
 pub struct TaskListEventContent {
     pub name: String,
 
@@ -82,6 +281,20 @@ pub struct TaskListEventContent {
     #[builder(setter(into), default)]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub categories: Vec<String>,
+
+    /// The ordered states tasks of this list go through, if customized
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<TaskState>,
+}
+
+impl TaskListBuilder {
+    fn validate(&self) -> CoreResult<(), String> {
+        if let Some(states) = &self.states {
+            TaskState::validate_workflow(states)?;
+        }
+        Ok(())
+    }
 }
 
 /// The TaskList Event
@@ -159,6 +372,15 @@ pub struct TaskListUpdateEventContent {
         deserialize_with = "deserialize_some"
     )]
     pub categories: Option<Vec<String>>,
+
+    /// Replace the workflow states, an empty list goes back to the default
+    #[builder(setter(into), default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub states: Option<Vec<TaskState>>,
 }
 
 impl TaskListUpdateEventContent {
@@ -196,6 +418,14 @@ impl TaskListUpdateEventContent {
             task_list.categories.clone_from(categories);
             updated = true;
         }
+        if let Some(states) = &self.states {
+            if let Err(error) = TaskState::validate_workflow(states) {
+                warn!(?error, "Invalid workflow states. Ignoring.");
+            } else {
+                task_list.states.clone_from(states);
+                updated = true;
+            }
+        }
 
         trace!(update = ?self, ?updated, ?task_list, "TaskList updated");
 
@@ -224,6 +454,34 @@ pub struct TaskEventContent {
     #[serde(rename = "m.relates_to")]
     pub task_list_id: BelongsTo,
 
+    /// The task this is a subtask of, living in the same tasklist
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_task: Option<OwnedEventId>,
+
+    /// The tasks that need to be done before this one can be worked on,
+    /// from any tasklist of the same space
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<OwnedEventId>,
+
+    /// Repeat this task: once it is done, the next instance is created
+    /// with the next due date according to this rule. Rules we don’t
+    /// support are kept as they are, but don’t create further instances.
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+
+    /// For instances of a recurring task: the task the series started with
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_task: Option<OwnedEventId>,
+
+    /// For instances of a recurring task: the instance this one follows
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_instance: Option<OwnedEventId>,
+
     /// Further information describing the task
     #[builder(setter(into), default)]
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -250,6 +508,22 @@ pub struct TaskEventContent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub progress_percent: Option<u8>,
 
+    /// Ordered checkboxes within this task, if any, the progress is
+    /// derived from them
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+
+    /// How long this task is expected to take, in minutes
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+
+    /// The id of the workflow state of the tasklist this task is in
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
     /// Sort order within the TaskList
     #[builder(default)]
     #[serde(default)]
@@ -275,6 +549,17 @@ pub struct TaskEventContent {
     pub categories: Vec<String>,
 }
 
+impl TaskEventContent {
+    /// The share of done checklist items in percent, if there is a checklist
+    pub fn checklist_progress(&self) -> Option<u8> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+        let done = self.checklist.iter().filter(|i| i.done).count();
+        Some((done * 100 / self.checklist.len()) as u8)
+    }
+}
+
 impl TaskBuilder {
     fn validate(&self) -> CoreResult<(), String> {
         if let Some(Some(percent)) = &self.progress_percent {
@@ -308,6 +593,42 @@ pub struct TaskUpdateEventContent {
     )]
     pub title: Option<String>,
 
+    /// Move the task to another tasklist of the same space
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub task_list_id: Option<OwnedEventId>,
+
+    /// Move the task under another parent task or make it a top level task
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub parent_task: Option<Option<OwnedEventId>>,
+
+    /// Replace the tasks this task is blocked by
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub blocked_by: Option<Vec<OwnedEventId>>,
+
+    /// Change or remove the recurrence rule
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub recurrence: Option<Option<Recurrence>>,
+
     /// Every tasks belongs to a tasklist
     /// Further information describing the task
     #[builder(default)]
@@ -354,6 +675,29 @@ pub struct TaskUpdateEventContent {
     )]
     pub progress_percent: Option<Option<u8>>,
 
+    /// Changes to the checklist, applied in order
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistOperation>,
+
+    /// How long this task is expected to take, in minutes
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub estimated_minutes: Option<Option<u32>>,
+
+    /// Move the task to another workflow state of its tasklist
+    #[builder(default)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub state: Option<Option<String>>,
+
     /// Sort order within the TaskList
     #[builder(default)]
     #[serde(
@@ -406,6 +750,37 @@ impl TaskUpdateEventContent {
             task.title.clone_from(title);
             updated = true;
         }
+        if let Some(task_list_id) = &self.task_list_id {
+            if task.task_list_id.event_id != *task_list_id {
+                task.task_list_id = BelongsTo::from(task_list_id.clone());
+                if self.parent_task.is_none() {
+                    // the parent lives in the previous tasklist
+                    task.parent_task = None;
+                }
+                updated = true;
+            }
+        }
+        if let Some(parent_task) = &self.parent_task {
+            if parent_task.as_ref() == Some(&self.task.event_id) {
+                warn!(task = ?self.task.event_id, "A task can’t be its own parent. Ignoring.");
+            } else {
+                task.parent_task.clone_from(parent_task);
+                updated = true;
+            }
+        }
+        if let Some(blocked_by) = &self.blocked_by {
+            // a task can’t block itself
+            task.blocked_by = blocked_by
+                .iter()
+                .filter(|t| *t != &self.task.event_id)
+                .cloned()
+                .collect();
+            updated = true;
+        }
+        if let Some(recurrence) = &self.recurrence {
+            task.recurrence.clone_from(recurrence);
+            updated = true;
+        }
         if let Some(description) = &self.description {
             task.description.clone_from(description);
             updated = true;
@@ -426,6 +801,24 @@ impl TaskUpdateEventContent {
             task.progress_percent = *progress_percent;
             updated = true;
         }
+        let mut checklist_changed = false;
+        for operation in &self.checklist {
+            checklist_changed |= operation.apply(&mut task.checklist);
+        }
+        if checklist_changed {
+            if let Some(progress) = task.checklist_progress() {
+                task.progress_percent = Some(progress);
+            }
+            updated = true;
+        }
+        if let Some(estimated_minutes) = &self.estimated_minutes {
+            task.estimated_minutes = *estimated_minutes;
+            updated = true;
+        }
+        if let Some(state) = &self.state {
+            task.state.clone_from(state);
+            updated = true;
+        }
         if let Some(sort_order) = &self.sort_order {
             task.sort_order = *sort_order;
             updated = true;
@@ -472,3 +865,217 @@ pub struct TaskSelfUnassignEventContent {
     #[serde(rename = "m.relates_to")]
     pub task: BelongsTo,
 }
+
+/// TaskAssign Event
+///
+/// Assigns another member of the room to the task. Modelled after a mention to
+/// use the existing notification setup for the assignee.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.task.assign", kind = MessageLike)]
+#[builder(name = "TaskAssignBuilder", derive(Debug))]
+pub struct TaskAssignEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub task: BelongsTo,
+
+    /// The user being assigned
+    #[builder(setter(into))]
+    #[serde(rename = "m.mentions")]
+    pub assignee: Mentions,
+}
+
+impl TaskAssignEventContent {
+    pub fn new(task_id: OwnedEventId, user_id: OwnedUserId) -> TaskAssignEventContent {
+        TaskAssignEventContent {
+            task: BelongsTo::from(task_id),
+            assignee: Mentions::with_user_ids([user_id]),
+        }
+    }
+}
+
+/// TaskUnassign Event
+///
+/// Removes another member of the room from the task.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.task.unassign", kind = MessageLike)]
+#[builder(name = "TaskUnassignBuilder", derive(Debug))]
+pub struct TaskUnassignEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub task: BelongsTo,
+
+    /// The user being unassigned
+    #[builder(setter(into))]
+    #[serde(rename = "m.mentions")]
+    pub assignee: Mentions,
+}
+
+impl TaskUnassignEventContent {
+    pub fn new(task_id: OwnedEventId, user_id: OwnedUserId) -> TaskUnassignEventContent {
+        TaskUnassignEventContent {
+            task: BelongsTo::from(task_id),
+            assignee: Mentions::with_user_ids([user_id]),
+        }
+    }
+}
+
+/// TaskTimeLog Event
+///
+/// Time the sender spent working on the task
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent, Builder, Getters)]
+#[ruma_event(type = "global.acter.dev.task.time_log", kind = MessageLike)]
+#[builder(name = "TaskTimeLogBuilder", derive(Debug))]
+pub struct TaskTimeLogEventContent {
+    #[builder(setter(into))]
+    #[serde(rename = "m.relates_to")]
+    pub task: BelongsTo,
+
+    /// How long was worked on the task, in minutes
+    pub minutes: u32,
+
+    /// The day the work was done on, if not the day it is logged
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<Date>,
+
+    /// What was done, e.g. for reporting
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(id: &str, after: Option<&str>) -> ChecklistOperation {
+        ChecklistOperation::Add {
+            item: ChecklistItem {
+                id: id.to_owned(),
+                text: format!("pack {id}"),
+                done: false,
+            },
+            after: after.map(ToOwned::to_owned),
+        }
+    }
+
+    fn ids(checklist: &[ChecklistItem]) -> Vec<&str> {
+        checklist.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn checklist_operations_merge() {
+        let mut checklist = vec![];
+        assert!(add("tent", None).apply(&mut checklist));
+        assert!(add("stove", None).apply(&mut checklist));
+        assert!(add("mat", Some("tent")).apply(&mut checklist));
+        assert_eq!(ids(&checklist), ["tent", "mat", "stove"]);
+
+        // replaying is a no-op
+        assert!(!add("mat", Some("tent")).apply(&mut checklist));
+
+        // one removes the item the other one toggles
+        let remove = ChecklistOperation::Remove {
+            id: "stove".to_owned(),
+        };
+        let toggle = ChecklistOperation::Toggle {
+            id: "stove".to_owned(),
+            done: true,
+        };
+        assert!(remove.apply(&mut checklist));
+        assert!(!toggle.apply(&mut checklist));
+        assert!(!remove.apply(&mut checklist));
+
+        // adding after a removed item lands at the end
+        assert!(add("lamp", Some("stove")).apply(&mut checklist));
+        assert_eq!(ids(&checklist), ["tent", "mat", "lamp"]);
+    }
+
+    #[test]
+    fn checklist_reverting_reaches_target() {
+        let build = |ops: &[ChecklistOperation]| {
+            let mut checklist = vec![];
+            for op in ops {
+                op.apply(&mut checklist);
+            }
+            checklist
+        };
+        let target = build(&[add("tent", None), add("mat", None), add("stove", None)]);
+
+        // reordered, one removed, one added, one done
+        let mut current = build(&[add("stove", None), add("tent", None), add("lamp", None)]);
+        ChecklistOperation::Toggle {
+            id: "tent".to_owned(),
+            done: true,
+        }
+        .apply(&mut current);
+
+        for op in ChecklistOperation::reverting(&current, &target) {
+            op.apply(&mut current);
+        }
+        assert_eq!(current, target);
+
+        // only the done state differs
+        let mut done = target.clone();
+        done[1].done = true;
+        let operations = ChecklistOperation::reverting(&done, &target);
+        assert_eq!(
+            operations,
+            [ChecklistOperation::Toggle {
+                id: "mat".to_owned(),
+                done: false
+            }]
+        );
+    }
+
+    #[test]
+    fn checklist_operations_serialize() -> anyhow::Result<()> {
+        let json = serde_json::to_value(add("tent", None))?;
+        assert_eq!(
+            json,
+            serde_json::json!({"op": "add", "id": "tent", "text": "pack tent", "done": false})
+        );
+        let toggle: ChecklistOperation =
+            serde_json::from_str(r#"{"op":"toggle","id":"tent","done":true}"#)?;
+        assert_eq!(
+            toggle,
+            ChecklistOperation::Toggle {
+                id: "tent".to_owned(),
+                done: true
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn workflow_needs_one_done_state() {
+        let state = |id: &str, done| TaskState::new(id.to_owned(), id.to_owned(), done);
+        assert!(TaskState::validate_workflow(&[]).is_ok());
+        assert!(TaskState::validate_workflow(&TaskState::defaults()).is_ok());
+        assert!(TaskState::validate_workflow(&[
+            state("backlog", false),
+            state("doing", false),
+            state("review", false),
+            state("done", true),
+        ])
+        .is_ok());
+
+        assert!(TaskState::validate_workflow(&[state("backlog", false)]).is_err());
+        assert!(TaskState::validate_workflow(&[state("done", true)]).is_err());
+        assert!(
+            TaskState::validate_workflow(&[state("done", true), state("shipped", true)]).is_err()
+        );
+        assert!(TaskState::validate_workflow(&[
+            state("doing", false),
+            state("doing", false),
+            state("done", true),
+        ])
+        .is_err());
+
+        let builder = TaskListBuilder::default()
+            .name("Board".to_owned())
+            .states(vec![state("doing", false)])
+            .build();
+        assert!(builder.is_err());
+    }
+}
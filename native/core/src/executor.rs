@@ -9,6 +9,7 @@ use tokio::sync::broadcast::{channel, Receiver, Sender};
 use tracing::{error, info, trace, trace_span, warn};
 
 use crate::{
+    history,
     models::{ActerModel, AnyActerModel, EventMeta, RedactedActerModel},
     referencing::ExecuteReference,
     store::Store,
@@ -96,8 +97,12 @@ impl Executor {
     }
 
     pub async fn handle(&self, model: AnyActerModel) -> Result<()> {
-        let event_id = model.event_id().to_string();
+        let event_id = model.event_id().to_owned();
         trace!(?event_id, ?model, "handle");
+        if let Err(error) = history::record_original(&self.store, &model).await {
+            warn!(?event_id, ?error, "recording the original failed");
+        }
+        let updated_objects = history::updated_objects(&model);
         match model.execute(&self.store).await {
             Err(error) => {
                 error!(?event_id, ?error, "handling failed");
@@ -105,6 +110,13 @@ impl Executor {
             }
             Ok(keys) => {
                 trace!(?event_id, "handling done");
+                if let Some(object_ids) = updated_objects {
+                    if let Err(error) =
+                        history::record_applied(&self.store, &event_id, &object_ids).await
+                    {
+                        warn!(?event_id, ?error, "recording the applied update failed");
+                    }
+                }
                 info!("******************** executor handled: {:?}", &keys);
                 self.notify(keys);
                 Ok(())
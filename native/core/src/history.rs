@@ -0,0 +1,477 @@
+use derive_getters::Getters;
+use matrix_sdk::ruma::{EventId, OwnedEventId};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    client::CoreClient,
+    models::{ActerModel, AnyActerModel, EventMeta},
+    referencing::{ExecuteReference, ModelParam},
+    store::Store,
+    Error, Result,
+};
+
+type Fields = serde_json::Map<String, Value>;
+
+/// A single field touched by an update
+#[derive(Clone, Debug, Getters)]
+pub struct FieldChange {
+    /// the name of the field as it is found in the event content
+    field: String,
+    /// the value before the update, `None` if it wasn’t set
+    before: Option<Value>,
+    /// the value after the update, `None` if it was unset
+    after: Option<Value>,
+}
+
+/// An update event and the fields it changed
+#[derive(Clone, Debug, Getters)]
+pub struct HistoryEntry {
+    meta: EventMeta,
+    changes: Vec<FieldChange>,
+    /// all fields after this update
+    #[getter(skip)]
+    state: Fields,
+}
+
+/// The changes made to an object since its creation, oldest first
+#[derive(Clone, Debug, Getters)]
+pub struct ObjectHistory {
+    /// the creation event of the object
+    meta: EventMeta,
+    entries: Vec<HistoryEntry>,
+    /// all fields as the object was created
+    #[getter(skip)]
+    original: Fields,
+}
+
+fn as_fields<C: Serialize>(content: &C) -> Result<Fields> {
+    match serde_json::to_value(content)? {
+        Value::Object(fields) => Ok(fields),
+        _ => Err(Error::Custom("Content isn’t a map of fields".to_owned())),
+    }
+}
+
+fn diff(mut before: Fields, mut after: Fields) -> Vec<FieldChange> {
+    let mut fields = before.keys().cloned().collect::<Vec<_>>();
+    fields.extend(after.keys().filter(|k| !before.contains_key(*k)).cloned());
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let before = before.remove(&field);
+            let after = after.remove(&field);
+            (before != after).then_some(FieldChange {
+                field,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+fn original_key(object_id: &EventId) -> String {
+    ExecuteReference::ModelParam(object_id.to_owned(), ModelParam::OriginalContent).as_storage_key()
+}
+
+fn applied_updates_key(object_id: &EventId) -> String {
+    ExecuteReference::ModelParam(object_id.to_owned(), ModelParam::AppliedUpdates).as_storage_key()
+}
+
+/// Whether we keep a change history for this kind of object
+fn has_history(model: &AnyActerModel) -> bool {
+    matches!(
+        model,
+        AnyActerModel::Pin(_)
+            | AnyActerModel::Task(_)
+            | AnyActerModel::TaskList(_)
+            | AnyActerModel::CalendarEvent(_)
+            | AnyActerModel::NewsEntry(_)
+    )
+}
+
+/// The objects this update changes, if it is one we keep the history of
+pub(crate) fn updated_objects(model: &AnyActerModel) -> Option<Vec<OwnedEventId>> {
+    match model {
+        AnyActerModel::PinUpdate(_)
+        | AnyActerModel::TaskUpdate(_)
+        | AnyActerModel::TaskListUpdate(_)
+        | AnyActerModel::CalendarEventUpdate(_)
+        | AnyActerModel::NewsEntryUpdate(_) => model.belongs_to(),
+        _ => None,
+    }
+}
+
+/// The store only keeps the merged state of an object, so we keep a copy of
+/// it as it was created. Called before the object is executed the first time.
+pub(crate) async fn record_original(store: &Store, model: &AnyActerModel) -> Result<()> {
+    if !has_history(model) {
+        return Ok(());
+    }
+    let key = original_key(model.event_id());
+    if store.get_raw::<AnyActerModel>(&key).await.is_ok() {
+        // seen before, the object might already be updated
+        return Ok(());
+    }
+    store.set_raw(&key, model).await
+}
+
+/// Remember `update_id` was applied to `object_ids`, after the ones before
+pub(crate) async fn record_applied(
+    store: &Store,
+    update_id: &EventId,
+    object_ids: &[OwnedEventId],
+) -> Result<()> {
+    for object_id in object_ids {
+        let key = applied_updates_key(object_id);
+        let mut applied = store
+            .get_raw::<Vec<OwnedEventId>>(&key)
+            .await
+            .unwrap_or_default();
+        if applied.iter().any(|id| id == update_id) {
+            continue;
+        }
+        applied.push(update_id.to_owned());
+        store.set_raw(&key, &applied).await?;
+    }
+    Ok(())
+}
+
+/// Apply each update the same way the model does and record what changed
+fn replay<C, F>(
+    mut content: C,
+    updates: &[AnyActerModel],
+    apply: F,
+) -> Result<(Fields, Vec<HistoryEntry>)>
+where
+    C: Serialize,
+    F: Fn(&AnyActerModel, &mut C) -> Option<Result<bool>>,
+{
+    let original = as_fields(&content)?;
+    let mut entries = vec![];
+    for update in updates {
+        let before = as_fields(&content)?;
+        let Some(changed) = apply(update, &mut content) else {
+            continue;
+        };
+        if !changed? {
+            continue;
+        }
+        let state = as_fields(&content)?;
+        let changes = diff(before, state.clone());
+        if !changes.is_empty() {
+            entries.push(HistoryEntry {
+                meta: update.event_meta().clone(),
+                changes,
+                state,
+            });
+        }
+    }
+    Ok((original, entries))
+}
+
+impl ObjectHistory {
+    /// Build the history from the object as it was created and its updates,
+    /// in the order they were applied. Only the `*Update` events are taken
+    /// into account.
+    pub fn from_updates(original: AnyActerModel, updates: Vec<AnyActerModel>) -> Result<Self> {
+        let meta = original.event_meta().clone();
+        let (original, entries) = match original {
+            AnyActerModel::Pin(pin) => replay(pin.inner, &updates, |m, c| match m {
+                AnyActerModel::PinUpdate(u) => Some(u.apply(c)),
+                _ => None,
+            })?,
+            AnyActerModel::Task(task) => replay(task.inner, &updates, |m, c| match m {
+                AnyActerModel::TaskUpdate(u) => Some(u.apply(c)),
+                _ => None,
+            })?,
+            AnyActerModel::TaskList(list) => replay(list.inner, &updates, |m, c| match m {
+                AnyActerModel::TaskListUpdate(u) => Some(u.apply(c)),
+                _ => None,
+            })?,
+            AnyActerModel::CalendarEvent(event) => replay(event.inner, &updates, |m, c| match m {
+                AnyActerModel::CalendarEventUpdate(u) => Some(u.apply(c)),
+                _ => None,
+            })?,
+            AnyActerModel::NewsEntry(news) => replay(news.inner, &updates, |m, c| match m {
+                AnyActerModel::NewsEntryUpdate(u) => Some(u.apply(c)),
+                _ => None,
+            })?,
+            other => {
+                return Err(Error::Custom(format!(
+                    "No change history for {}",
+                    other.model_type()
+                )))
+            }
+        };
+        Ok(ObjectHistory {
+            meta,
+            entries,
+            original,
+        })
+    }
+
+    /// The object content at the given version: 0 is the object as created,
+    /// `n` its state after the `n`-th entry
+    pub fn state_at<C: DeserializeOwned>(&self, version: usize) -> Result<C> {
+        let fields = match version {
+            0 => &self.original,
+            n => match self.entries.get(n - 1) {
+                Some(entry) => &entry.state,
+                None => {
+                    return Err(Error::Custom(format!(
+                        "Version {n} unknown, the latest is {}",
+                        self.entries.len()
+                    )))
+                }
+            },
+        };
+        Ok(serde_json::from_value(Value::Object(fields.clone()))?)
+    }
+
+    /// The history of `object_id` as recorded in the store, replaying its
+    /// updates in the order the executor applied them. Redacted updates are
+    /// left out.
+    pub async fn from_store(store: &Store, object_id: &EventId) -> Result<Self> {
+        let original = store
+            .get_raw::<AnyActerModel>(&original_key(object_id))
+            .await
+            .map_err(|_| Error::Custom(format!("No history recorded for {object_id}")))?;
+        let applied = store
+            .get_raw::<Vec<OwnedEventId>>(&applied_updates_key(object_id))
+            .await
+            .unwrap_or_default();
+        let updates = store
+            .get_many(applied)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+        ObjectHistory::from_updates(original, updates)
+    }
+}
+
+impl CoreClient {
+    /// The per-field change history of a pin, task, task list, calendar event
+    /// or news entry
+    pub async fn object_history(&self, object_id: &EventId) -> Result<ObjectHistory> {
+        ObjectHistory::from_store(&self.store, object_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{pins::PinEventContent, AnyActerEvent},
+        models::fixtures,
+    };
+
+    const PIN_ID: &str = "$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c";
+
+    fn model(
+        event_id: &str,
+        ts: u64,
+        event_type: &str,
+        content: &str,
+    ) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":{ts},
+            "event_id":"{event_id}",
+            "user_id":"@odo:ds9.acter.global"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    fn pin_update(event_id: &str, ts: u64, fields: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            event_id,
+            ts,
+            "global.acter.dev.pin.update",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.update","event_id":"{PIN_ID}"}},{fields}}}"#
+            ),
+        )
+    }
+
+    #[test]
+    fn pin_history_has_before_and_after() -> anyhow::Result<()> {
+        let pin = model(
+            PIN_ID,
+            1672407531453,
+            "global.acter.dev.pin",
+            r#"{"title":"Security codes","url":"https://acter.global"}"#,
+        )?;
+        // out of order on purpose
+        let updates = vec![
+            pin_update("$second", 1672407531500, r#""url":null"#)?,
+            pin_update("$first", 1672407531470, r#""title":"Security protocols""#)?,
+        ];
+
+        let history = ObjectHistory::from_updates(pin, updates)?;
+        assert_eq!(history.meta().event_id.as_str(), PIN_ID);
+        let [first, second] = history.entries().as_slice() else {
+            anyhow::bail!("expected two entries, got {:?}", history.entries());
+        };
+
+        assert_eq!(first.meta().event_id.as_str(), "$first");
+        let [title] = first.changes().as_slice() else {
+            anyhow::bail!("expected only the title to change");
+        };
+        assert_eq!(title.field(), "title");
+        assert_eq!(title.before(), &Some(Value::from("Security codes")));
+        assert_eq!(title.after(), &Some(Value::from("Security protocols")));
+
+        let [url] = second.changes().as_slice() else {
+            anyhow::bail!("expected only the url to change");
+        };
+        assert_eq!(url.field(), "url");
+        assert_eq!(url.before(), &Some(Value::from("https://acter.global")));
+        assert_eq!(url.after(), &None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn history_follows_the_applied_order() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        let store = executor.store();
+        executor
+            .handle(model(
+                PIN_ID,
+                1672407531453,
+                "global.acter.dev.pin",
+                r#"{"title":"Security codes","url":"https://acter.global"}"#,
+            )?)
+            .await?;
+        // the later update is applied first
+        executor
+            .handle(pin_update(
+                "$late",
+                1672407531500,
+                r#""title":"Security protocols""#,
+            )?)
+            .await?;
+        executor
+            .handle(pin_update(
+                "$early",
+                1672407531470,
+                r#""title":"Security briefing""#,
+            )?)
+            .await?;
+
+        let pin_id = EventId::parse(PIN_ID)?;
+        let history = ObjectHistory::from_store(store, &pin_id).await?;
+        let order: Vec<_> = history
+            .entries()
+            .iter()
+            .map(|e| e.meta().event_id.to_string())
+            .collect();
+        assert_eq!(order, ["$late", "$early"]);
+
+        // the original is kept, even though the model was changed since
+        let original: PinEventContent = history.state_at(0)?;
+        assert_eq!(original.title, "Security codes");
+        // and the latest version is what the model shows now
+        let AnyActerModel::Pin(pin) = store.get(&pin_id).await? else {
+            anyhow::bail!("Not a pin");
+        };
+        let latest: PinEventContent = history.state_at(2)?;
+        assert_eq!(latest.title, pin.title);
+        assert_eq!(latest.title, "Security briefing");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reverting_to_a_version_reached_out_of_order() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        let store = executor.store();
+        executor
+            .handle(model(
+                PIN_ID,
+                1672407531453,
+                "global.acter.dev.pin",
+                r#"{"title":"Security codes","url":"https://acter.global"}"#,
+            )?)
+            .await?;
+        // applied in the opposite order they were sent in
+        executor
+            .handle(pin_update("$late", 1672407531500, r#""url":null"#)?)
+            .await?;
+        executor
+            .handle(pin_update(
+                "$early",
+                1672407531470,
+                r#""title":"Security protocols""#,
+            )?)
+            .await?;
+
+        let pin_id = EventId::parse(PIN_ID)?;
+        let history = ObjectHistory::from_store(store, &pin_id).await?;
+        let AnyActerModel::Pin(pin) = store.get(&pin_id).await? else {
+            anyhow::bail!("Not a pin");
+        };
+        // version 1 is after `$late` only: url removed, title unchanged
+        let target: PinEventContent = history.state_at(1)?;
+        assert_eq!(target.title, "Security codes");
+        assert_eq!(target.url, None);
+
+        let revert = pin.reverter(&target).build()?;
+        assert_eq!(revert.title.as_deref(), Some("Security codes"));
+        assert!(revert.url.is_none());
+
+        // applying it brings the pin back to that version
+        let revert_update = model(
+            "$revert",
+            1672407531600,
+            "global.acter.dev.pin.update",
+            &serde_json::to_string(&revert)?,
+        )?;
+        executor.handle(revert_update).await?;
+        let AnyActerModel::Pin(pin) = store.get(&pin_id).await? else {
+            anyhow::bail!("Not a pin");
+        };
+        assert_eq!(pin.title, "Security codes");
+        assert_eq!(pin.url, None);
+        Ok(())
+    }
+
+    #[test]
+    fn reverting_pin_to_creation() -> anyhow::Result<()> {
+        let AnyActerModel::Pin(mut pin) = model(
+            PIN_ID,
+            1672407531453,
+            "global.acter.dev.pin",
+            r#"{"title":"Security codes","url":"https://acter.global"}"#,
+        )?
+        else {
+            anyhow::bail!("Not a pin");
+        };
+        let updates = vec![
+            pin_update("$first", 1672407531470, r#""title":"Security protocols""#)?,
+            pin_update("$second", 1672407531500, r#""url":null"#)?,
+        ];
+        let history = ObjectHistory::from_updates(pin.clone().into(), updates.clone())?;
+        for update in &updates {
+            pin.transition(update)?;
+        }
+
+        let after_first: PinEventContent = history.state_at(1)?;
+        assert_eq!(after_first.title, "Security protocols");
+        assert_eq!(after_first.url.as_deref(), Some("https://acter.global"));
+        assert!(history.state_at::<PinEventContent>(3).is_err());
+
+        let revert = pin.reverter(&history.state_at(0)?).build()?;
+        assert_eq!(revert.title.as_deref(), Some("Security codes"));
+        assert_eq!(revert.url, Some(Some("https://acter.global".to_owned())));
+        assert!(revert.content.is_none());
+        assert!(revert.deadline.is_none());
+
+        // nothing to do for the latest version
+        let noop = pin.reverter(&history.state_at(2)?).build()?;
+        assert!(noop.title.is_none() && noop.url.is_none());
+        Ok(())
+    }
+}
@@ -11,6 +11,7 @@ pub mod client;
 pub mod error;
 pub mod events;
 pub mod executor;
+pub mod history;
 pub mod models;
 pub mod push;
 pub mod referencing;
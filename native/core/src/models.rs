@@ -1,3 +1,4 @@
+mod agenda;
 mod any;
 mod attachments;
 mod calendar;
@@ -5,11 +6,13 @@ mod capabilities;
 mod comments;
 mod common;
 mod conversion;
+mod documents;
 mod execution;
 mod invites;
 mod meta;
 mod news;
 mod pins;
+mod polls;
 mod reactions;
 mod read_receipts;
 mod redaction;
@@ -21,29 +24,40 @@ mod tasks;
 #[cfg(any(test, feature = "testing"))]
 mod test;
 
+pub use agenda::{agenda_items, AgendaItem, AgendaSource};
 pub use any::{ActerModel, AnyActerModel};
 pub use attachments::{Attachment, AttachmentUpdate, AttachmentsManager, AttachmentsStats};
-pub use calendar::{CalendarEvent, CalendarEventUpdate};
+pub use calendar::{
+    draft_from_ical_event, drafts_from_ical, ical_document, next_reminder_after, reminders_due,
+    CalendarEvent, CalendarEventOccurrence, CalendarEventUpdate, DueReminder, ReminderScheduler,
+};
 pub use capabilities::Capability;
-pub use comments::{Comment, CommentUpdate, CommentsManager, CommentsStats};
+pub use comments::{Comment, CommentThread, CommentUpdate, CommentsManager, CommentsStats};
 pub use common::*;
 pub use core::fmt::Debug;
-pub(crate) use execution::default_model_execute;
+pub use documents::{Document, DocumentRevision};
+pub(crate) use execution::{default_model_execute, transition_tree};
 pub use invites::{InvitationsManager, InviteStats, MyInvitesManager};
 pub use meta::{can_redact, EventMeta};
 pub use news::{NewsEntry, NewsEntryUpdate};
 pub use pins::{Pin, PinUpdate};
+pub use polls::{Poll, PollResults, PollStats, PollUpdate, PollVote, PollsManager};
 pub use reactions::{Reaction, ReactionManager, ReactionStats};
 pub use read_receipts::{ReadReceipt, ReadReceiptStats, ReadReceiptsManager};
 pub use redaction::RedactedActerModel;
-pub use rsvp::{Rsvp, RsvpManager, RsvpStats};
+pub use rsvp::{Rsvp, RsvpManager, RsvpParticipation, RsvpStats};
 pub use status::{ActerSupportedRoomStatusEvents, RoomStatus};
 pub use stories::{Story, StoryUpdate};
 pub use tag::Tag;
 pub use tasks::{
-    Task, TaskList, TaskListUpdate, TaskSelfAssign, TaskSelfUnassign, TaskStats, TaskUpdate,
+    can_assign, next_due_lists_change, task_draft_from_ical_todo, task_drafts_from_ical,
+    task_list_ical_document, IcalTaskDraft, Task, TaskAssign, TaskDueWatcher, TaskList,
+    TaskListUpdate, TaskSelfAssign, TaskSelfUnassign, TaskStats, TaskTimeLog, TaskUnassign,
+    TaskUpdate, TimeTrackingManager, TimeTrackingStats, ASSIGN_OTHERS_POWER_LEVEL,
 };
 
+#[cfg(test)]
+pub(crate) use test::fixtures;
 #[cfg(any(test, feature = "testing"))]
 pub use test::{TestModel, TestModelBuilder, TestModelBuilderError};
 
@@ -53,7 +67,13 @@ pub use crate::store::Store;
 mod tests {
     use super::*;
     use crate::{
-        error::ModelRedactedDetails, events::AnyActerEvent, models::conversion::ParseError, Result,
+        error::ModelRedactedDetails,
+        events::{
+            calendar::{CalendarEventEventContent, EventStatus},
+            AnyActerEvent,
+        },
+        models::conversion::ParseError,
+        Result,
     };
 
     use anyhow::bail;
@@ -216,4 +236,151 @@ mod tests {
         // assert!(matches!(event, AnyCreation::TaskList(_)));
         Ok(())
     }
+
+    #[test]
+    fn ensure_recurring_calendar_event_expands() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Weekly sync",
+                "utc_start":"2024-03-05T10:00:00Z","utc_end":"2024-03-05T11:00:00Z",
+                "recurrence":"FREQ=WEEKLY;COUNT=5",
+                "exdates":["2024-03-12T10:00:00Z"],
+                "rdates":["2024-03-14T10:00:00Z"]},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(cal_event) = AnyActerModel::try_from(event)? else {
+            bail!("Not a calendar event");
+        };
+        assert!(cal_event.is_recurring());
+
+        let from = "2024-03-05T10:30:00Z".parse()?;
+        let to = "2024-03-26T10:00:00Z".parse()?;
+        let starts = cal_event
+            .occurrences_between(from, to)
+            .into_iter()
+            .map(|o| o.utc_start().to_rfc3339())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            [
+                // still ongoing at `from`
+                "2024-03-05T10:00:00+00:00",
+                "2024-03-14T10:00:00+00:00",
+                "2024-03-19T10:00:00+00:00",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_unsupported_recurrence_still_parses() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Last workday drinks",
+                "utc_start":"2024-03-29T17:00:00Z","utc_end":"2024-03-29T19:00:00Z",
+                "recurrence":"FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(cal_event) = AnyActerModel::try_from(event)? else {
+            bail!("Not a calendar event");
+        };
+        assert!(cal_event.is_recurring());
+        assert!(cal_event.has_unsupported_recurrence());
+        assert_eq!(
+            cal_event.recurrence.as_ref().map(ToString::to_string),
+            Some("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1".to_owned())
+        );
+
+        // only the first occurrence is known
+        let from = "2024-03-01T00:00:00Z".parse()?;
+        let to = "2024-06-01T00:00:00Z".parse()?;
+        let occurrences = cal_event.occurrences_between(from, to);
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(
+            occurrences[0].utc_start().to_rfc3339(),
+            "2024-03-29T17:00:00+00:00"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_recurring_calendar_event_keeps_local_time() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Weekly sync",
+                "utc_start":"2024-03-19T09:00:00Z","utc_end":"2024-03-19T10:00:00Z",
+                "time_zone":"Europe/Berlin",
+                "recurrence":"FREQ=WEEKLY;COUNT=3"},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(cal_event) = AnyActerModel::try_from(event)? else {
+            bail!("Not a calendar event");
+        };
+        assert_eq!(
+            cal_event.local_start().to_rfc3339(),
+            "2024-03-19T10:00:00+01:00"
+        );
+
+        let from = "2024-03-01T00:00:00Z".parse()?;
+        let to = "2024-04-30T00:00:00Z".parse()?;
+        let starts = cal_event
+            .occurrences_between(from, to)
+            .into_iter()
+            .map(|o| o.utc_start().to_rfc3339())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            [
+                "2024-03-19T09:00:00+00:00",
+                // summer time starts on March 31st
+                "2024-03-26T09:00:00+00:00",
+                "2024-04-02T08:00:00+00:00",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_calendar_event_status_round_trips() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Dabo night",
+                "utc_start":"2024-03-06T20:00:00Z","utc_end":"2024-03-06T23:00:00Z",
+                "status":"cancelled","status_reason":"Quark is out of latinum"},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(mut cal_event) = AnyActerModel::try_from(event)? else {
+            bail!("Not a calendar event");
+        };
+        assert_eq!(cal_event.status, EventStatus::Cancelled);
+        assert_eq!(
+            cal_event.status_reason.as_deref(),
+            Some("Quark is out of latinum")
+        );
+        let json = serde_json::to_value(&cal_event.inner)?;
+        assert_eq!(json["status"], "cancelled");
+        assert_eq!(json["status_reason"], "Quark is out of latinum");
+
+        // confirmed is the default and neither written nor required
+        cal_event.inner.status = EventStatus::Confirmed;
+        cal_event.inner.status_reason = None;
+        let json = serde_json::to_value(&cal_event.inner)?;
+        assert!(json.get("status").is_none());
+        assert!(json.get("status_reason").is_none());
+        let content = serde_json::from_value::<CalendarEventEventContent>(json)?;
+        assert_eq!(content.status, EventStatus::Confirmed);
+        Ok(())
+    }
 }
@@ -0,0 +1,394 @@
+use chrono::{Days, NaiveTime, TimeZone};
+use matrix_sdk_base::ruma::{EventId, RoomId, UserId};
+use serde::Serialize;
+
+use super::{
+    ActerModel, AnyActerModel, CalendarEvent, CalendarEventOccurrence, NewsEntry, Pin, RsvpManager,
+    Task,
+};
+use crate::{
+    events::{rsvp::RsvpStatus, UtcDateTime},
+    referencing::{IndexKey, SectionIndex, SpecialListsIndex},
+    store::Store,
+    util::local_to_utc,
+    Result,
+};
+
+/// An entry on the personal agenda of a user
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AgendaItem {
+    /// An occurrence of a calendar event the user said yes or maybe to
+    CalendarEvent {
+        event: CalendarEvent,
+        occurrence: CalendarEventOccurrence,
+        rsvp: RsvpStatus,
+    },
+    /// An open task assigned to the user, at its due time or at the start of
+    /// its due date in the local time zone
+    Task { task: Task, at: UtcDateTime },
+    /// A pin with a deadline
+    Pin(Pin),
+    /// A news entry with a deadline
+    NewsEntry(NewsEntry),
+}
+
+impl AgendaItem {
+    /// The occurrences of `event` in the range `from` to `to`, if the user
+    /// responded with yes or maybe
+    pub fn for_calendar_event(
+        event: &CalendarEvent,
+        rsvp: RsvpStatus,
+        from: UtcDateTime,
+        to: UtcDateTime,
+    ) -> Vec<AgendaItem> {
+        if rsvp == RsvpStatus::No {
+            return vec![];
+        }
+        event
+            .occurrences_between(from, to)
+            .into_iter()
+            .map(|occurrence| AgendaItem::CalendarEvent {
+                event: event.clone(),
+                occurrence,
+                rsvp: rsvp.clone(),
+            })
+            .collect()
+    }
+
+    /// The task, if it is open and due in the range `from` to `to`. Tasks
+    /// without time of day are due for the whole day in `tz`.
+    pub fn for_task<T: TimeZone>(
+        task: &Task,
+        from: UtcDateTime,
+        to: UtcDateTime,
+        tz: &T,
+    ) -> Option<AgendaItem> {
+        if task.is_done() {
+            return None;
+        }
+        let (in_range, at) = match task.utc_due() {
+            Some(due_at) => (due_at >= from && due_at < to, due_at),
+            None => {
+                let due_date = task.due_date?;
+                let start = local_to_utc(tz, due_date.and_time(NaiveTime::MIN))?;
+                let next_day = due_date.checked_add_days(Days::new(1))?;
+                let end = local_to_utc(tz, next_day.and_time(NaiveTime::MIN))?;
+                (start < to && end > from, start)
+            }
+        };
+        in_range.then(|| AgendaItem::Task {
+            task: task.clone(),
+            at,
+        })
+    }
+
+    /// The pin, if its deadline is in the range `from` to `to`
+    pub fn for_pin(pin: &Pin, from: UtcDateTime, to: UtcDateTime) -> Option<AgendaItem> {
+        let deadline = pin.deadline?;
+        (deadline >= from && deadline < to).then(|| AgendaItem::Pin(pin.clone()))
+    }
+
+    /// The news entry, if its deadline is in the range `from` to `to`
+    pub fn for_news_entry(
+        news_entry: &NewsEntry,
+        from: UtcDateTime,
+        to: UtcDateTime,
+    ) -> Option<AgendaItem> {
+        let deadline = news_entry.deadline?;
+        (deadline >= from && deadline < to).then(|| AgendaItem::NewsEntry(news_entry.clone()))
+    }
+
+    pub fn item_type(&self) -> &'static str {
+        match self {
+            AgendaItem::CalendarEvent { .. } => "calendar-event",
+            AgendaItem::Task { .. } => "task",
+            AgendaItem::Pin(_) => "pin",
+            AgendaItem::NewsEntry(_) => "news-entry",
+        }
+    }
+
+    /// When this item is on the agenda: the start of the occurrence, the
+    /// due time or the deadline. Tasks without time of day are at the start
+    /// of their due date in the time zone the agenda was made for.
+    pub fn utc_at(&self) -> UtcDateTime {
+        match self {
+            AgendaItem::CalendarEvent { occurrence, .. } => occurrence.utc_start,
+            AgendaItem::Task { at, .. } => *at,
+            AgendaItem::Pin(pin) => pin.deadline.unwrap_or_default(),
+            AgendaItem::NewsEntry(news_entry) => news_entry.deadline.unwrap_or_default(),
+        }
+    }
+
+    /// Whether this item takes the whole day rather than a specific time
+    pub fn is_all_day(&self) -> bool {
+        match self {
+            AgendaItem::CalendarEvent { event, .. } => event.show_without_time(),
+            AgendaItem::Task { task, .. } => task.utc_due_time_of_day.is_none(),
+            AgendaItem::Pin(_) | AgendaItem::NewsEntry(_) => false,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            AgendaItem::CalendarEvent { event, .. } => event.title(),
+            AgendaItem::Task { task, .. } => task.title(),
+            AgendaItem::Pin(pin) => pin.title(),
+            AgendaItem::NewsEntry(news_entry) => news_entry
+                .slides
+                .first()
+                .map(|slide| slide.content.text_str())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn event_id(&self) -> &EventId {
+        match self {
+            AgendaItem::CalendarEvent { event, .. } => event.event_id(),
+            AgendaItem::Task { task, .. } => task.event_id(),
+            AgendaItem::Pin(pin) => pin.event_id(),
+            AgendaItem::NewsEntry(news_entry) => news_entry.event_id(),
+        }
+    }
+
+    pub fn room_id(&self) -> &RoomId {
+        match self {
+            AgendaItem::CalendarEvent { event, .. } => event.room_id(),
+            AgendaItem::Task { task, .. } => task.room_id(),
+            AgendaItem::Pin(pin) => pin.room_id(),
+            AgendaItem::NewsEntry(news_entry) => news_entry.room_id(),
+        }
+    }
+
+    /// Identifies this item on the agenda: recurring events are on it once
+    /// per occurrence
+    pub fn key(&self) -> String {
+        match self {
+            AgendaItem::CalendarEvent { occurrence, .. } => {
+                format!("{}:{}", self.event_id(), occurrence.utc_start.to_rfc3339())
+            }
+            _ => self.event_id().to_string(),
+        }
+    }
+}
+
+/// Where the items on the agenda come from, so each can be refreshed on its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AgendaSource {
+    CalendarEvents,
+    Tasks,
+    Pins,
+    NewsEntries,
+}
+
+impl AgendaSource {
+    pub const ALL: [AgendaSource; 4] = [
+        AgendaSource::CalendarEvents,
+        AgendaSource::Tasks,
+        AgendaSource::Pins,
+        AgendaSource::NewsEntries,
+    ];
+
+    /// The index listing the objects of this source
+    fn objects_index(&self) -> IndexKey {
+        match self {
+            AgendaSource::CalendarEvents => IndexKey::Section(SectionIndex::Calendar),
+            AgendaSource::Tasks => IndexKey::Special(SpecialListsIndex::MyOpenTasks),
+            AgendaSource::Pins => IndexKey::Section(SectionIndex::Pins),
+            AgendaSource::NewsEntries => IndexKey::Section(SectionIndex::Boosts),
+        }
+    }
+
+    /// The indizes changing when objects are added to or removed from this
+    /// source: for calendar events that includes the responses of the user
+    pub fn indizes(&self) -> Vec<IndexKey> {
+        match self {
+            AgendaSource::CalendarEvents => vec![
+                self.objects_index(),
+                IndexKey::Special(SpecialListsIndex::MyRsvps),
+            ],
+            _ => vec![self.objects_index()],
+        }
+    }
+
+    /// All objects of this source, whether they are on the agenda or not. Any
+    /// of them might move onto the agenda when updated.
+    pub async fn models(&self, store: &Store) -> Result<Vec<AnyActerModel>> {
+        Ok(store.get_list(&self.objects_index()).await?.collect())
+    }
+
+    /// The items of this source on the agenda of `user_id` in the range
+    /// `from` to `to`, unsorted
+    pub async fn items<T: TimeZone>(
+        &self,
+        store: &Store,
+        user_id: &UserId,
+        from: UtcDateTime,
+        to: UtcDateTime,
+        tz: &T,
+    ) -> Result<Vec<AgendaItem>> {
+        let mut items = vec![];
+        for mdl in self.models(store).await? {
+            match mdl {
+                AnyActerModel::CalendarEvent(event) => {
+                    let rsvps = RsvpManager::from_store_and_event_id(store, event.event_id())
+                        .await
+                        .rsvp_entries()
+                        .await?;
+                    if let Some(rsvp) = rsvps.get(user_id) {
+                        items.extend(AgendaItem::for_calendar_event(
+                            &event,
+                            rsvp.status.clone(),
+                            from,
+                            to,
+                        ));
+                    }
+                }
+                AnyActerModel::Task(task) => {
+                    items.extend(AgendaItem::for_task(&task, from, to, tz));
+                }
+                AnyActerModel::Pin(pin) => items.extend(AgendaItem::for_pin(&pin, from, to)),
+                AnyActerModel::NewsEntry(news_entry) => {
+                    items.extend(AgendaItem::for_news_entry(&news_entry, from, to));
+                }
+                _ => {}
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// All items on the agenda of `user_id` in the range `from` to `to`, sorted
+/// chronologically: occurrences of calendar events they responded yes or maybe
+/// to, their open tasks due in the range and pins and news with a deadline in
+/// the range. Due dates without time are local dates in `tz`.
+pub async fn agenda_items<T: TimeZone>(
+    store: &Store,
+    user_id: &UserId,
+    from: UtcDateTime,
+    to: UtcDateTime,
+    tz: &T,
+) -> Result<Vec<AgendaItem>> {
+    let mut items = vec![];
+    for source in AgendaSource::ALL {
+        items.extend(source.items(store, user_id, from, to, tz).await?);
+    }
+    items.sort_by_key(|item| (item.utc_at(), item.key()));
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AnyActerEvent;
+    use chrono::Utc;
+
+    fn model(event_type: &str, content: &str) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":1672407531453,
+            "unsigned":{{"age":11523850}},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    #[test]
+    fn tasks_and_deadlines_in_range() -> anyhow::Result<()> {
+        let from: UtcDateTime = "2024-03-06T00:00:00Z".parse()?;
+        let to: UtcDateTime = "2024-03-07T00:00:00Z".parse()?;
+
+        let AnyActerModel::Task(task) = model(
+            "global.acter.dev.task",
+            r#"{"title":"Fix the replicator","due_date":"2024-03-06",
+                "m.relates_to":{"event_id":"$tasklist:ds9.acter.global"}}"#,
+        )?
+        else {
+            anyhow::bail!("Not a task");
+        };
+        let item = AgendaItem::for_task(&task, from, to, &Utc).expect("task is due");
+        assert!(item.is_all_day());
+        assert_eq!(item.utc_at(), from);
+        assert!(AgendaItem::for_task(&task, to, to + Days::new(1), &Utc).is_none());
+
+        let AnyActerModel::Pin(pin) = model(
+            "global.acter.dev.pin",
+            r#"{"title":"Submit reports","deadline":"2024-03-06T17:00:00Z"}"#,
+        )?
+        else {
+            anyhow::bail!("Not a pin");
+        };
+        let item = AgendaItem::for_pin(&pin, from, to).expect("deadline in range");
+        assert_eq!(
+            item.utc_at(),
+            "2024-03-06T17:00:00Z".parse::<UtcDateTime>()?
+        );
+        assert!(!item.is_all_day());
+        assert!(AgendaItem::for_pin(&pin, to, to + Days::new(1)).is_none());
+
+        let AnyActerModel::Pin(no_deadline) =
+            model("global.acter.dev.pin", r#"{"title":"Seat arrangement"}"#)?
+        else {
+            anyhow::bail!("Not a pin");
+        };
+        assert!(AgendaItem::for_pin(&no_deadline, from, to).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn due_dates_are_local_dates() -> anyhow::Result<()> {
+        let AnyActerModel::Task(task) = model(
+            "global.acter.dev.task",
+            r#"{"title":"Fix the replicator","due_date":"2024-03-06",
+                "m.relates_to":{"event_id":"$tasklist:ds9.acter.global"}}"#,
+        )?
+        else {
+            anyhow::bail!("Not a task");
+        };
+        let berlin = chrono_tz::Europe::Berlin;
+        // the sixth of march starts an hour earlier in Berlin
+        let local_midnight: UtcDateTime = "2024-03-05T23:00:00Z".parse()?;
+        let evening: UtcDateTime = "2024-03-05T22:00:00Z".parse()?;
+        let item = AgendaItem::for_task(&task, evening, local_midnight + Days::new(1), &berlin)
+            .expect("task is due");
+        assert_eq!(item.utc_at(), local_midnight);
+        // still the sixth in Berlin
+        let late: UtcDateTime = "2024-03-06T22:30:00Z".parse()?;
+        assert!(AgendaItem::for_task(&task, late, late + Days::new(1), &berlin).is_some());
+        // but not before it started there
+        assert!(AgendaItem::for_task(&task, evening, local_midnight, &berlin).is_none());
+        // while it is already the seventh there, it still is the sixth in UTC
+        let next_day: UtcDateTime = "2024-03-06T23:30:00Z".parse()?;
+        let hour_later = next_day + chrono::Duration::hours(1);
+        assert!(AgendaItem::for_task(&task, next_day, hour_later, &berlin).is_none());
+        assert!(AgendaItem::for_task(&task, next_day, hour_later, &Utc).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn declined_events_are_not_on_the_agenda() -> anyhow::Result<()> {
+        let from: UtcDateTime = "2024-03-01T00:00:00Z".parse()?;
+        let to: UtcDateTime = "2024-03-31T00:00:00Z".parse()?;
+        let AnyActerModel::CalendarEvent(event) = model(
+            "global.acter.dev.calendar_event",
+            r#"{"title":"Staff meeting",
+                "utc_start":"2024-03-06T09:00:00Z","utc_end":"2024-03-06T10:00:00Z"}"#,
+        )?
+        else {
+            anyhow::bail!("Not a calendar event");
+        };
+        let items = AgendaItem::for_calendar_event(&event, RsvpStatus::Maybe, from, to);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type(), "calendar-event");
+        assert_eq!(
+            items[0].key(),
+            "$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c:2024-03-06T09:00:00+00:00"
+        );
+        assert!(AgendaItem::for_calendar_event(&event, RsvpStatus::No, from, to).is_empty());
+        Ok(())
+    }
+}
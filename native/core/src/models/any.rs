@@ -3,17 +3,22 @@ use super::calendar::{CalendarEvent, CalendarEventUpdate};
 use super::capabilities::Capability;
 use super::comments::{Comment, CommentUpdate};
 use super::conversion::ParseError;
+use super::documents::{Document, DocumentRevision};
 pub(crate) use super::execution::transition_tree;
 use super::invites::ExplicitInvite;
 use super::meta::EventMeta;
 use super::news::{NewsEntry, NewsEntryUpdate};
 use super::pins::{Pin, PinUpdate};
+use super::polls::{Poll, PollUpdate, PollVote};
 use super::reactions::Reaction;
 use super::read_receipts::ReadReceipt;
 use super::redaction::RedactedActerModel;
 use super::rsvp::Rsvp;
 use super::stories::{Story, StoryUpdate};
-use super::tasks::{Task, TaskList, TaskListUpdate, TaskSelfAssign, TaskSelfUnassign, TaskUpdate};
+use super::tasks::{
+    Task, TaskAssign, TaskList, TaskListUpdate, TaskSelfAssign, TaskSelfUnassign, TaskTimeLog,
+    TaskUnassign, TaskUpdate,
+};
 use super::RoomStatus;
 use core::fmt::Debug;
 use enum_dispatch::enum_dispatch;
@@ -34,15 +39,18 @@ use crate::{
         attachments::{AttachmentEventContent, AttachmentUpdateEventContent},
         calendar::{CalendarEventEventContent, CalendarEventUpdateEventContent},
         comments::{CommentEventContent, CommentUpdateEventContent},
+        documents::{DocumentEventContent, DocumentRevisionEventContent},
         explicit_invites::ExplicitInviteEventContent,
         news::{NewsEntryEventContent, NewsEntryUpdateEventContent},
         pins::{PinEventContent, PinUpdateEventContent},
+        polls::{PollEventContent, PollUpdateEventContent, PollVoteEventContent},
         read_receipt::ReadReceiptEventContent,
         rsvp::RsvpEventContent,
         stories::{StoryEventContent, StoryUpdateEventContent},
         tasks::{
-            TaskEventContent, TaskListEventContent, TaskListUpdateEventContent,
-            TaskSelfAssignEventContent, TaskSelfUnassignEventContent, TaskUpdateEventContent,
+            TaskAssignEventContent, TaskEventContent, TaskListEventContent,
+            TaskListUpdateEventContent, TaskSelfAssignEventContent, TaskSelfUnassignEventContent,
+            TaskTimeLogEventContent, TaskUnassignEventContent, TaskUpdateEventContent,
         },
         AnyActerEvent,
     },
@@ -120,11 +128,23 @@ pub enum AnyActerModel {
     TaskUpdate(TaskUpdate),
     TaskSelfAssign(TaskSelfAssign),
     TaskSelfUnassign(TaskSelfUnassign),
+    TaskAssign(TaskAssign),
+    TaskUnassign(TaskUnassign),
+    TaskTimeLog(TaskTimeLog),
 
     // -- Pins
     Pin(Pin),
     PinUpdate(PinUpdate),
 
+    // -- Polls
+    Poll(Poll),
+    PollUpdate(PollUpdate),
+    PollVote(PollVote),
+
+    // -- Documents
+    Document(Document),
+    DocumentRevision(DocumentRevision),
+
     // -- News
     NewsEntry(NewsEntry),
     NewsEntryUpdate(NewsEntryUpdate),
@@ -162,8 +182,16 @@ impl AnyActerModel {
             AnyActerModel::TaskUpdate(_) => TaskUpdateEventContent::TYPE,
             AnyActerModel::TaskSelfAssign(_) => TaskSelfAssignEventContent::TYPE,
             AnyActerModel::TaskSelfUnassign(_) => TaskSelfUnassignEventContent::TYPE,
+            AnyActerModel::TaskAssign(_) => TaskAssignEventContent::TYPE,
+            AnyActerModel::TaskUnassign(_) => TaskUnassignEventContent::TYPE,
+            AnyActerModel::TaskTimeLog(_) => TaskTimeLogEventContent::TYPE,
             AnyActerModel::Pin(_) => PinEventContent::TYPE,
             AnyActerModel::PinUpdate(_) => PinUpdateEventContent::TYPE,
+            AnyActerModel::Poll(_) => PollEventContent::TYPE,
+            AnyActerModel::PollUpdate(_) => PollUpdateEventContent::TYPE,
+            AnyActerModel::PollVote(_) => PollVoteEventContent::TYPE,
+            AnyActerModel::Document(_) => DocumentEventContent::TYPE,
+            AnyActerModel::DocumentRevision(_) => DocumentRevisionEventContent::TYPE,
             AnyActerModel::NewsEntry(_) => NewsEntryEventContent::TYPE,
             AnyActerModel::NewsEntryUpdate(_) => NewsEntryUpdateEventContent::TYPE,
             AnyActerModel::Story(_) => StoryEventContent::TYPE,
@@ -1,3 +1,11 @@
 mod event;
+mod ical;
+mod reminders;
 
-pub use event::{CalendarEvent, CalendarEventUpdate};
+pub use event::{CalendarEvent, CalendarEventOccurrence, CalendarEventUpdate};
+pub use ical::{draft_from_ical_event, drafts_from_ical, ical_document};
+pub(crate) use ical::{
+    escape_text, ical_cal_address, parse_error, start_of_day, text_property, to_utc, unescape_text,
+    user_id_from_cal_address,
+};
+pub use reminders::{next_reminder_after, reminders_due, DueReminder, ReminderScheduler};
@@ -1,21 +1,31 @@
-use icalendar::{Component, Event as iCalEvent, EventLike};
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use icalendar::{CalendarDateTime, Component, Event as iCalEvent, EventLike};
 use matrix_sdk::ruma::OwnedEventId;
-use matrix_sdk_base::ruma::{events::OriginalMessageLikeEvent, RoomId, UserId};
+use matrix_sdk_base::ruma::{events::OriginalMessageLikeEvent, OwnedUserId, RoomId, UserId};
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
+use std::{collections::HashMap, ops::Deref};
 
-use super::super::{
-    default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, Store,
-    TextMessageContent,
+use super::{
+    super::{
+        default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, Rsvp, RsvpManager,
+        Store, TextMessageContent,
+    },
+    ical::{
+        ical_alarm, ical_attendee, ical_cal_address, ical_location_values, ical_status,
+        MAX_VTIMEZONE_YEARS,
+    },
 };
 use crate::{
     events::{
         calendar::{
             CalendarEventEventContent, CalendarEventUpdateBuilder, CalendarEventUpdateEventContent,
+            EventReminder,
         },
-        UtcDateTime,
+        Recurrence, UtcDateTime,
     },
     referencing::{ExecuteReference, IndexKey, SectionIndex},
+    util::{local_to_utc, serialized_differ},
     Result,
 };
 
@@ -54,6 +64,73 @@ impl CalendarEvent {
             .to_owned()
     }
 
+    /// Update setting all fields that differ back to the given earlier state
+    pub fn reverter(&self, target: &CalendarEventEventContent) -> CalendarEventUpdateBuilder {
+        let current = &self.inner;
+        let mut builder = self.updater();
+        if serialized_differ(&current.title, &target.title) {
+            builder.title(Some(target.title.clone()));
+        }
+        if serialized_differ(&current.description, &target.description) {
+            builder.description(Some(target.description.clone()));
+        }
+        if serialized_differ(&current.display, &target.display) {
+            builder.display(Some(target.display.clone()));
+        }
+        if serialized_differ(&current.utc_start, &target.utc_start) {
+            builder.utc_start(Some(target.utc_start));
+        }
+        if serialized_differ(&current.utc_end, &target.utc_end) {
+            builder.utc_end(Some(target.utc_end));
+        }
+        if serialized_differ(&current.show_without_time, &target.show_without_time) {
+            builder.show_without_time(Some(target.show_without_time));
+        }
+        if serialized_differ(&current.time_zone, &target.time_zone) {
+            builder.time_zone(Some(target.time_zone));
+        }
+        if serialized_differ(&current.locations, &target.locations) {
+            builder.locations(Some(target.locations.clone()));
+        }
+        if serialized_differ(&current.recurrence, &target.recurrence) {
+            builder.recurrence(Some(target.recurrence.clone()));
+        }
+        if serialized_differ(&current.exdates, &target.exdates) {
+            builder.exdates(Some(target.exdates.clone()));
+        }
+        if serialized_differ(&current.rdates, &target.rdates) {
+            builder.rdates(Some(target.rdates.clone()));
+        }
+        if serialized_differ(&current.reminders, &target.reminders) {
+            builder.reminders(Some(target.reminders.clone()));
+        }
+        if serialized_differ(&current.capacity, &target.capacity) {
+            builder.capacity(Some(target.capacity));
+        }
+        if serialized_differ(&current.status, &target.status) {
+            builder.status(Some(target.status));
+        }
+        if serialized_differ(&current.status_reason, &target.status_reason) {
+            builder.status_reason(Some(target.status_reason.clone()));
+        }
+        if serialized_differ(&current.keywords, &target.keywords) {
+            builder.keywords(Some(target.keywords.clone()));
+        }
+        if serialized_differ(&current.categories, &target.categories) {
+            builder.categories(Some(target.categories.clone()));
+        }
+        builder
+    }
+
+    /// Update builder excluding the occurrence starting at `start` from this series
+    pub fn exclude_occurrence_updater(&self, start: UtcDateTime) -> CalendarEventUpdateBuilder {
+        let mut exdates = self.inner.exdates.clone();
+        if !exdates.contains(&start) {
+            exdates.push(start);
+        }
+        self.updater().exdates(Some(exdates)).to_owned()
+    }
+
     pub fn utc_end(&self) -> UtcDateTime {
         self.inner.utc_end
     }
@@ -66,14 +143,145 @@ impl CalendarEvent {
         self.inner.show_without_time
     }
 
+    /// The time zone of this event, UTC if none was given
+    pub fn tz(&self) -> Tz {
+        self.inner.time_zone.unwrap_or(Tz::UTC)
+    }
+
+    /// When the event starts in its own time zone
+    pub fn local_start(&self) -> DateTime<Tz> {
+        self.inner.utc_start.with_timezone(&self.tz())
+    }
+
+    /// When the event ends in its own time zone
+    pub fn local_end(&self) -> DateTime<Tz> {
+        self.inner.utc_end.with_timezone(&self.tz())
+    }
+
+    /// Whether this event has more than one occurrence
+    pub fn is_recurring(&self) -> bool {
+        self.inner.recurrence.is_some() || !self.inner.rdates.is_empty()
+    }
+
+    /// Whether this event repeats by a rule we can't compute the occurrences
+    /// of: only the first occurrence and the additional dates are known
+    pub fn has_unsupported_recurrence(&self) -> bool {
+        self.inner
+            .recurrence
+            .as_ref()
+            .is_some_and(|recurrence| !recurrence.is_supported())
+    }
+
+    /// All occurrences of this event overlapping with the range `from` to `to`,
+    /// ordered by their start. For non-recurring events this is at most the
+    /// event itself.
+    pub fn occurrences_between(
+        &self,
+        from: UtcDateTime,
+        to: UtcDateTime,
+    ) -> Vec<CalendarEventOccurrence> {
+        let duration = self.inner.utc_end - self.inner.utc_start;
+        let rule = self.inner.recurrence.as_ref().and_then(Recurrence::rule);
+        let mut starts: Vec<UtcDateTime> = match (rule, &self.inner.time_zone) {
+            // repeat in local time, so the series keeps its wall clock time over DST changes
+            (Some(rule), Some(tz)) => rule
+                .iter_from(
+                    self.inner.utc_start.with_timezone(tz).naive_local(),
+                    rule.until.map(|u| u.with_timezone(tz).naive_local()),
+                )
+                .filter_map(|local| local_to_utc(tz, local))
+                .take_while(|start| *start < to)
+                .collect(),
+            (Some(rule), None) => rule
+                .iter_utc(self.inner.utc_start)
+                .take_while(|start| *start < to)
+                .collect(),
+            (None, _) => vec![self.inner.utc_start],
+        };
+        starts.extend(self.inner.rdates.iter().copied());
+        starts.retain(|start| {
+            *start < to
+                && (*start >= from || *start + duration > from)
+                && !self.inner.exdates.contains(start)
+        });
+        starts.sort();
+        starts.dedup();
+        starts
+            .into_iter()
+            .map(|utc_start| CalendarEventOccurrence {
+                utc_start,
+                utc_end: utc_start + duration,
+            })
+            .collect()
+    }
+
     pub fn as_ical_event(&self) -> iCalEvent {
+        self.as_ical_event_with_reminders(&self.inner.reminders)
+    }
+
+    /// iCal event with the given reminders as `VALARM`s rather than the
+    /// defaults of the event
+    pub fn as_ical_event_with_reminders(&self, reminders: &[EventReminder]) -> iCalEvent {
         let mut cal_e_builder = iCalEvent::new();
 
         cal_e_builder
+            .uid(self.meta.event_id.as_str())
             .summary(&self.inner.title)
-            .starts(self.inner.utc_start)
-            .ends(self.inner.utc_end)
-            .class(icalendar::Class::Private);
+            .class(icalendar::Class::Private)
+            .status(ical_status(&self.inner.status))
+            .add_property("ORGANIZER", ical_cal_address(&self.meta.sender));
+        if let Some(reason) = &self.inner.status_reason {
+            // there is no property for the reason, the closest is a comment
+            cal_e_builder.add_property("COMMENT", reason);
+        }
+
+        if self.inner.show_without_time {
+            // all-day events end exclusively on the day after
+            let (start, end) = (self.local_start(), self.local_end());
+            let mut end_date = end.date_naive();
+            if end_date == start.date_naive() || end.time() != chrono::NaiveTime::MIN {
+                end_date = end_date.succ_opt().unwrap_or(end_date);
+            }
+            cal_e_builder.starts(start.date_naive()).ends(end_date);
+        } else if let Some(tz) = &self.inner.time_zone {
+            let with_tz = |date_time: NaiveDateTime| CalendarDateTime::WithTimezone {
+                date_time,
+                tzid: tz.name().to_owned(),
+            };
+            cal_e_builder
+                .starts(with_tz(self.local_start().naive_local()))
+                .ends(with_tz(self.local_end().naive_local()));
+        } else {
+            cal_e_builder
+                .starts(self.inner.utc_start)
+                .ends(self.inner.utc_end);
+        }
+
+        if let Some(recurrence) = &self.inner.recurrence {
+            cal_e_builder.add_property("RRULE", recurrence.to_string());
+        }
+        if !self.inner.exdates.is_empty() {
+            cal_e_builder.add_property("EXDATE", ical_date_list(&self.inner.exdates));
+        }
+        if !self.inner.rdates.is_empty() {
+            cal_e_builder.add_property("RDATE", ical_date_list(&self.inner.rdates));
+        }
+
+        let (location, geo, url) = ical_location_values(&self.inner.locations);
+        if let Some(location) = location {
+            cal_e_builder.location(&location);
+        }
+        if let Some(geo) = geo {
+            cal_e_builder.add_property("GEO", geo);
+        }
+        if let Some(url) = url {
+            cal_e_builder.add_property("URL", url);
+        }
+
+        for reminder in reminders {
+            cal_e_builder.alarm(ical_alarm(&self.inner.title, reminder));
+        }
+
         if let Some(msg) = &self.inner.description {
             if let Some(formatted) = &msg.formatted {
                 return cal_e_builder.description(&formatted.body).done();
@@ -83,6 +291,90 @@ impl CalendarEvent {
         }
         cal_e_builder.done()
     }
+
+    /// The time zone of this event with the range of years its `VTIMEZONE`
+    /// has to cover
+    pub(super) fn ical_time_zone_years(&self) -> Option<(Tz, i32, i32)> {
+        let tz = self.inner.time_zone?;
+        if self.inner.show_without_time {
+            // exported as plain dates
+            return None;
+        }
+        let from = self.local_start().year();
+        let mut to = self.local_end().year();
+        if let Some(recurrence) = &self.inner.recurrence {
+            // we can't tell when unsupported rules end, treat them as open ended
+            let last = recurrence
+                .rule()
+                .and_then(|rule| match (rule.until, rule.count) {
+                    (Some(until), _) => Some(until),
+                    // a COUNT can be huge, we only walk it as far as we can cover
+                    (None, Some(_)) => rule
+                        .iter_utc(self.inner.utc_start)
+                        .take_while(|start| {
+                            start.with_timezone(&tz).year() < from + MAX_VTIMEZONE_YEARS
+                        })
+                        .last(),
+                    (None, None) => None,
+                });
+            to = match last {
+                Some(last) => last.with_timezone(&tz).year(),
+                // open ended series, cover the next few years
+                None => to.max(Utc::now().year()) + 5,
+            };
+        }
+        for rdate in &self.inner.rdates {
+            to = to.max(rdate.with_timezone(&tz).year());
+        }
+        Some((tz, from, to.max(from)))
+    }
+
+    /// iCal event listing the given RSVP responses as `ATTENDEE`s. If `me` is
+    /// given but hasn’t responded yet, they are added as `NEEDS-ACTION`.
+    pub fn as_ical_event_with_rsvps(
+        &self,
+        rsvps: &HashMap<OwnedUserId, Rsvp>,
+        me: Option<&UserId>,
+        reminders: &[EventReminder],
+    ) -> iCalEvent {
+        let mut cal_event = self.as_ical_event_with_reminders(reminders);
+        let mut responses = rsvps.iter().collect::<Vec<_>>();
+        responses.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (user_id, rsvp) in responses {
+            cal_event.append_multi_property(ical_attendee(user_id, Some(&rsvp.status)));
+        }
+        if let Some(me) = me {
+            if !rsvps.contains_key(me) {
+                cal_event.append_multi_property(ical_attendee(me, None));
+            }
+        }
+        cal_event
+    }
+}
+
+fn ical_date_list(dates: &[UtcDateTime]) -> String {
+    dates
+        .iter()
+        .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A single occurrence of a (possibly recurring) calendar event
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CalendarEventOccurrence {
+    pub utc_start: UtcDateTime,
+    pub utc_end: UtcDateTime,
+}
+
+impl CalendarEventOccurrence {
+    pub fn utc_start(&self) -> UtcDateTime {
+        self.utc_start
+    }
+
+    pub fn utc_end(&self) -> UtcDateTime {
+        self.utc_end
+    }
 }
 
 impl ActerModel for CalendarEvent {
@@ -172,7 +464,21 @@ impl ActerModel for CalendarEventUpdate {
     }
 
     async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
-        default_model_execute(store, self.into()).await
+        let capacity_changed = self.inner.capacity.is_some();
+        let event_id = self.inner.calendar_event.event_id.clone();
+        if capacity_changed {
+            // to tell who got in through this change later on
+            RsvpManager::remember_capacity(store, &self).await?;
+        }
+        let mut updates = default_model_execute(store, self.into()).await?;
+        if capacity_changed {
+            // spots opened up or went away, recount who is in
+            let mut manager = RsvpManager::from_store_and_event_id(store, &event_id).await;
+            let participation = manager.participation().await?;
+            manager.set_participation(&participation);
+            updates.push(manager.save().await?);
+        }
+        Ok(updates)
     }
 
     fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
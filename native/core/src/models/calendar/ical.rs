@@ -0,0 +1,762 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use icalendar::{
+    Alarm, Calendar as iCalendar, CalendarComponent, CalendarDateTime, Component, DatePerhapsTime,
+    Event as iCalEvent, EventLike, EventStatus as iCalEventStatus, Property, Trigger,
+};
+use matrix_sdk_base::ruma::{events::room::message::TextMessageEventContent, OwnedUserId, UserId};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+use super::CalendarEvent;
+
+use crate::{
+    events::{
+        calendar::{CalendarEventBuilder, EventLocation, EventReminder, EventStatus},
+        rsvp::RsvpStatus,
+        Recurrence, UtcDateTime,
+    },
+    util::local_to_utc,
+    Error, Result,
+};
+
+pub(crate) fn parse_error(msg: impl Into<String>) -> Error {
+    Error::FailedToParse {
+        model_type: "iCalendar".to_owned(),
+        msg: msg.into(),
+    }
+}
+
+/// RFC 5545 TEXT escaping, for values that are part of a list
+pub(crate) fn escape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n' => result.push_str("\\n"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// undo the RFC 5545 TEXT escaping
+pub(crate) fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+pub(crate) fn text_property(component: &impl Component, key: &str) -> Option<String> {
+    component
+        .property_value(key)
+        .map(unescape_text)
+        .filter(|v| !v.trim().is_empty())
+}
+
+/// local times in `tz`, floating times without one are read as UTC
+fn naive_to_utc(naive: NaiveDateTime, tz: Option<&Tz>) -> Option<UtcDateTime> {
+    match tz {
+        Some(tz) => local_to_utc(tz, naive),
+        None => Some(naive.and_utc()),
+    }
+}
+
+/// convert a DTSTART/DTEND value to UTC, returns whether this was a date only
+pub(crate) fn to_utc(value: DatePerhapsTime) -> Result<(UtcDateTime, bool, Option<Tz>)> {
+    Ok(match value {
+        DatePerhapsTime::Date(date) => (start_of_day(date)?, true, None),
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(dt)) => (dt, false, None),
+        // floating times have no zone attached, we read them as UTC
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive)) => {
+            (naive.and_utc(), false, None)
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+            let tz = tzid
+                .parse::<Tz>()
+                .map_err(|e| parse_error(format!("unknown TZID {tzid}: {e}")))?;
+            let utc = naive_to_utc(date_time, Some(&tz))
+                .ok_or_else(|| parse_error(format!("{date_time} doesn’t exist in {tzid}")))?;
+            (utc, false, Some(tz))
+        }
+    })
+}
+
+pub(crate) fn start_of_day(date: NaiveDate) -> Result<UtcDateTime> {
+    date.and_hms_opt(0, 0, 0)
+        .map(|d| d.and_utc())
+        .ok_or_else(|| parse_error(format!("invalid date {date}")))
+}
+
+/// parse the comma separated EXDATE/RDATE values in the time zone of the event
+fn date_list(event: &iCalEvent, key: &str, tz: Option<&Tz>) -> Vec<UtcDateTime> {
+    let Some(value) = event.property_value(key) else {
+        return vec![];
+    };
+    value
+        .split(',')
+        .filter_map(|v| {
+            let v = v.trim();
+            let parsed = if v.ends_with('Z') {
+                NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%SZ")
+                    .ok()
+                    .map(|d| d.and_utc())
+            } else if let Ok(naive) = NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S") {
+                naive_to_utc(naive, tz)
+            } else {
+                NaiveDate::parse_from_str(v, "%Y%m%d")
+                    .ok()
+                    .and_then(|d| start_of_day(d).ok())
+            };
+            if parsed.is_none() {
+                warn!(?key, value = v, "Skipping unparsable date in iCal import");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// `GEO` is `lat;lon`, we keep coordinates as RFC 5870 `geo:` URI
+fn geo_uri(event: &iCalEvent) -> Option<String> {
+    let (lat, lon) = event.property_value("GEO")?.split_once(';')?;
+    let lat = lat.trim().parse::<f64>().ok()?;
+    let lon = lon.trim().parse::<f64>().ok()?;
+    Some(format!("geo:{lat},{lon}"))
+}
+
+fn is_uri(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+/// Build a calendar event draft from a single iCal `VEVENT`
+pub fn draft_from_ical_event(event: &iCalEvent) -> Result<CalendarEventBuilder> {
+    let title =
+        text_property(event, "SUMMARY").ok_or_else(|| Error::MissingField("SUMMARY".to_owned()))?;
+    let start = event
+        .get_start()
+        .ok_or_else(|| Error::MissingField("DTSTART".to_owned()))?;
+    let (utc_start, all_day, tz) = to_utc(start)?;
+    let utc_end = match event.get_end() {
+        Some(end) => to_utc(end)?.0,
+        // an all-day event without end lasts that day
+        None if all_day => utc_start + Duration::days(1),
+        None => utc_start,
+    };
+    if utc_end < utc_start {
+        return Err(parse_error(format!("{title} ends before it starts")));
+    }
+
+    let mut builder = CalendarEventBuilder::default();
+    builder
+        .title(title)
+        .utc_start(utc_start)
+        .utc_end(utc_end)
+        .show_without_time(all_day)
+        .time_zone(tz);
+
+    if let Some(body) = text_property(event, "DESCRIPTION") {
+        let description = match event.property_value("X-ALT-DESC") {
+            Some(html) => TextMessageEventContent::html(body, unescape_text(html)),
+            None => TextMessageEventContent::plain(body),
+        };
+        builder.description(Some(description));
+    }
+
+    let geo = geo_uri(event);
+    match text_property(event, "LOCATION") {
+        Some(location) if is_uri(&location) => {
+            builder.add_virtual_location(None, None, location, None);
+        }
+        Some(location) => {
+            builder.add_physical_location(Some(location), None, geo, None, None, None);
+        }
+        None if geo.is_some() => {
+            builder.add_physical_location(None, None, geo, None, None, None);
+        }
+        None => {}
+    }
+    if let Some(url) = text_property(event, "URL") {
+        builder.add_virtual_location(None, None, url, None);
+    }
+
+    if let Some(rrule) = event.property_value("RRULE") {
+        let recurrence = Recurrence::from_raw(rrule);
+        if !recurrence.is_supported() {
+            warn!(?rrule, "Unsupported RRULE in iCal import, kept as is");
+        }
+        builder.recurrence(Some(recurrence));
+        builder.exdates(date_list(event, "EXDATE", tz.as_ref()));
+    }
+    let rdates = date_list(event, "RDATE", tz.as_ref());
+    if !rdates.is_empty() {
+        builder.rdates(rdates);
+    }
+
+    match event.property_value("STATUS") {
+        Some("CANCELLED") => {
+            builder.status(EventStatus::Cancelled);
+        }
+        Some("TENTATIVE") => {
+            builder.status(EventStatus::Tentative);
+        }
+        _ => {}
+    }
+
+    Ok(builder)
+}
+
+/// Parse an iCalendar document into one calendar event draft per `VEVENT`.
+/// Events we can’t read are skipped, so one broken entry doesn’t prevent
+/// importing the rest.
+pub fn drafts_from_ical(ics: &str) -> Result<Vec<CalendarEventBuilder>> {
+    let calendar = ics
+        .parse::<iCalendar>()
+        .map_err(|e| parse_error(e.to_string()))?;
+    Ok(calendar
+        .components
+        .iter()
+        .filter_map(|component| match component {
+            CalendarComponent::Event(event) => match draft_from_ical_event(event) {
+                Ok(draft) => Some(draft),
+                Err(error) => {
+                    warn!(uid = ?event.get_uid(), ?error, "Skipping VEVENT in iCal import");
+                    None
+                }
+            },
+            _ => None,
+        })
+        .collect())
+}
+
+/// `geo:lat,lon[,alt][;params]` to the iCal `lat;lon`
+fn ical_geo(coordinates: &str) -> Option<String> {
+    let coords = coordinates.strip_prefix("geo:")?.split(';').next()?;
+    let mut parts = coords.split(',');
+    let lat = parts.next()?.trim().parse::<f64>().ok()?;
+    let lon = parts.next()?.trim().parse::<f64>().ok()?;
+    Some(format!("{lat};{lon}"))
+}
+
+/// The `LOCATION`, `GEO` and `URL` values for the given locations. iCal only
+/// supports one of each, so the first physical location is used for `LOCATION`
+/// and `GEO` and the first virtual one for `URL`. Online-only events get their
+/// link as `LOCATION`, too.
+pub(super) fn ical_location_values(
+    locations: &[EventLocation],
+) -> (Option<String>, Option<String>, Option<String>) {
+    let mut location = None;
+    let mut geo = None;
+    let mut physical_uri = None;
+    let mut virtual_uri = None;
+
+    for loc in locations {
+        match loc {
+            EventLocation::Physical {
+                name,
+                address,
+                coordinates,
+                uri,
+                ..
+            } => {
+                if location.is_none() {
+                    let text = [name, address]
+                        .into_iter()
+                        .flatten()
+                        .filter(|s| !s.is_empty())
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    if !text.is_empty() {
+                        location = Some(text);
+                    }
+                }
+                if geo.is_none() {
+                    geo = coordinates.as_deref().and_then(ical_geo);
+                }
+                if physical_uri.is_none() {
+                    physical_uri.clone_from(uri);
+                }
+            }
+            EventLocation::Virtual { uri, .. } => {
+                if virtual_uri.is_none() {
+                    virtual_uri = Some(uri.clone());
+                }
+            }
+        }
+    }
+
+    let location = location.or_else(|| virtual_uri.clone());
+    (location, geo, virtual_uri.or(physical_uri))
+}
+
+/// `matrix:` URI (MSC2312) as calendar user address
+pub(crate) fn ical_cal_address(user_id: &UserId) -> String {
+    format!("matrix:u/{}", user_id.as_str().trim_start_matches('@'))
+}
+
+/// The user of a `matrix:u/` calendar user address, other addresses are ignored
+pub(crate) fn user_id_from_cal_address(address: &str) -> Option<OwnedUserId> {
+    let localpart = address.trim().strip_prefix("matrix:u/")?;
+    UserId::parse(format!("@{localpart}")).ok()
+}
+
+/// `ATTENDEE` for the given user with the `PARTSTAT` of their RSVP
+pub(super) fn ical_attendee(user_id: &UserId, status: Option<&RsvpStatus>) -> Property {
+    let partstat = match status {
+        Some(RsvpStatus::Yes) => "ACCEPTED",
+        Some(RsvpStatus::Maybe) => "TENTATIVE",
+        Some(RsvpStatus::No) => "DECLINED",
+        None => "NEEDS-ACTION",
+    };
+    Property::new("ATTENDEE", ical_cal_address(user_id))
+        .add_parameter("CUTYPE", "INDIVIDUAL")
+        .add_parameter("PARTSTAT", partstat)
+        .done()
+}
+
+pub(super) fn ical_status(status: &EventStatus) -> iCalEventStatus {
+    match status {
+        EventStatus::Confirmed => iCalEventStatus::Confirmed,
+        EventStatus::Tentative => iCalEventStatus::Tentative,
+        EventStatus::Cancelled => iCalEventStatus::Cancelled,
+    }
+}
+
+/// `VALARM` showing the title of the event ahead of its start
+pub(super) fn ical_alarm(title: &str, reminder: &EventReminder) -> Alarm {
+    Alarm::display(title, Trigger::before_start(reminder.offset()))
+}
+
+fn ical_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    format!("{sign}{:02}{:02}", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// the most years a `VTIMEZONE` covers
+pub(super) const MAX_VTIMEZONE_YEARS: i32 = 30;
+/// the steps we scan time zones in for transitions
+const SCAN_DAYS: i64 = 7;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Observance {
+    utc_offset: i32,
+    is_dst: bool,
+    name: Option<String>,
+}
+
+impl Observance {
+    fn at(tz: &Tz, utc: &NaiveDateTime) -> Self {
+        let offset = tz.offset_from_utc_datetime(utc);
+        Observance {
+            utc_offset: offset.fix().local_minus_utc(),
+            is_dst: offset.dst_offset().num_seconds() != 0,
+            name: offset.abbreviation().map(ToOwned::to_owned),
+        }
+    }
+
+    fn differs(&self, other: &Observance) -> bool {
+        self.utc_offset != other.utc_offset || self.is_dst != other.is_dst
+    }
+
+    fn write(&self, from: &Observance, utc: NaiveDateTime, lines: &mut Vec<String>) {
+        let kind = if self.is_dst { "DAYLIGHT" } else { "STANDARD" };
+        let local = utc + Duration::seconds(from.utc_offset as i64);
+        lines.push(format!("BEGIN:{kind}"));
+        lines.push(format!("DTSTART:{}", local.format("%Y%m%dT%H%M%S")));
+        lines.push(format!("TZOFFSETFROM:{}", ical_offset(from.utc_offset)));
+        lines.push(format!("TZOFFSETTO:{}", ical_offset(self.utc_offset)));
+        if let Some(name) = &self.name {
+            lines.push(format!("TZNAME:{name}"));
+        }
+        lines.push(format!("END:{kind}"));
+    }
+}
+
+/// `VTIMEZONE` definition of `tz` listing all its transitions between
+/// `from_year` and `to_year` (inclusive) as explicit observances. Long ranges
+/// are cut to the last [`MAX_VTIMEZONE_YEARS`], earlier dates fall back to the
+/// first observance.
+pub(super) fn ical_vtimezone(tz: &Tz, from_year: i32, to_year: i32) -> String {
+    let year_start = |year: i32| {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap_or_default()
+    };
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_owned(), format!("TZID:{}", tz.name())];
+
+    let from_year = from_year.max(to_year.saturating_sub(MAX_VTIMEZONE_YEARS - 1));
+    let mut day = year_start(from_year);
+    let end = year_start(to_year.saturating_add(1));
+    let mut current = Observance::at(tz, &day);
+    current.write(&current, day, &mut lines);
+
+    // chrono-tz doesn’t expose its transitions, so we look for them. They are
+    // months apart, checking once a week is enough to find each.
+    while day < end {
+        let next_day = day + Duration::days(SCAN_DAYS);
+        let next = Observance::at(tz, &next_day);
+        if next.differs(&current) {
+            // find the exact second of the transition
+            let (mut lo, mut hi) = (day, next_day);
+            while hi - lo > Duration::seconds(1) {
+                let mid = lo + (hi - lo) / 2;
+                if Observance::at(tz, &mid).differs(&current) {
+                    hi = mid;
+                } else {
+                    lo = mid;
+                }
+            }
+            next.write(&current, hi, &mut lines);
+            current = next;
+        }
+        day = next_day;
+    }
+
+    lines.push("END:VTIMEZONE".to_owned());
+    lines.join("\r\n")
+}
+
+/// Render the given events as one iCalendar document, including a `VTIMEZONE`
+/// for every time zone the events are in
+pub fn ical_document<'a>(
+    name: Option<&str>,
+    events: impl IntoIterator<Item = (&'a CalendarEvent, iCalEvent)>,
+) -> String {
+    let mut calendar = iCalendar::new();
+    if let Some(name) = name {
+        calendar.name(name);
+    }
+    let mut zones: BTreeMap<&'static str, (Tz, i32, i32)> = BTreeMap::new();
+    for (model, cal_event) in events {
+        if let Some((tz, from, to)) = model.ical_time_zone_years() {
+            zones
+                .entry(tz.name())
+                .and_modify(|(_, f, t)| {
+                    *f = (*f).min(from);
+                    *t = (*t).max(to);
+                })
+                .or_insert((tz, from, to));
+        }
+        calendar.push(cal_event);
+    }
+
+    let mut ics = calendar.done().to_string();
+    if zones.is_empty() {
+        return ics;
+    }
+    // the `icalendar` crate has no support for VTIMEZONE, we add them in
+    // front of the first event
+    let timezones = zones
+        .values()
+        .map(|(tz, from, to)| ical_vtimezone(tz, *from, *to) + "\r\n")
+        .collect::<String>();
+    let pos = ics
+        .find("BEGIN:VEVENT")
+        .or_else(|| ics.find("END:VCALENDAR"))
+        .unwrap_or(ics.len());
+    ics.insert_str(pos, &timezones);
+    ics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::AnyActerEvent,
+        models::{AnyActerModel, Rsvp},
+    };
+    use std::collections::HashMap;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Other Tool//EN\r
+BEGIN:VEVENT\r
+UID:1@example.org\r
+DTSTAMP:20240301T120000Z\r
+SUMMARY:Team meeting\r
+DESCRIPTION:Weekly sync\\, bring notes\\nand coffee\r
+DTSTART;TZID=Europe/Berlin:20240305T100000\r
+DTEND;TZID=Europe/Berlin:20240305T110000\r
+LOCATION:Room 101\r
+GEO:52.52;13.405\r
+URL:https://meet.example.org/team\r
+RRULE:FREQ=WEEKLY;BYDAY=TU\r
+EXDATE;TZID=Europe/Berlin:20240312T100000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2@example.org\r
+DTSTAMP:20240301T120000Z\r
+SUMMARY:Summer fest\r
+DTSTART;VALUE=DATE:20240621\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn imports_multiple_events() -> anyhow::Result<()> {
+        let drafts = drafts_from_ical(ICS)?;
+        assert_eq!(drafts.len(), 2);
+
+        let meeting = drafts[0].build()?;
+        assert_eq!(meeting.title, "Team meeting");
+        assert_eq!(
+            meeting.description.map(|d| d.body),
+            Some("Weekly sync, bring notes\nand coffee".to_owned())
+        );
+        assert_eq!(meeting.utc_start.to_rfc3339(), "2024-03-05T09:00:00+00:00");
+        assert_eq!(meeting.utc_end.to_rfc3339(), "2024-03-05T10:00:00+00:00");
+        assert!(!meeting.show_without_time);
+        assert_eq!(meeting.locations.len(), 2);
+        assert!(matches!(
+            &meeting.locations[0],
+            EventLocation::Physical { name: Some(name), coordinates: Some(geo), .. }
+                if name == "Room 101" && geo == "geo:52.52,13.405"
+        ));
+        assert!(matches!(
+            &meeting.locations[1],
+            EventLocation::Virtual { uri, .. } if uri == "https://meet.example.org/team"
+        ));
+        assert_eq!(
+            meeting.recurrence.map(|r| r.to_string()),
+            Some("FREQ=WEEKLY;BYDAY=TU".to_owned())
+        );
+        assert_eq!(
+            meeting
+                .exdates
+                .iter()
+                .map(|d| d.to_rfc3339())
+                .collect::<Vec<_>>(),
+            ["2024-03-12T09:00:00+00:00"]
+        );
+
+        let fest = drafts[1].build()?;
+        assert_eq!(fest.title, "Summer fest");
+        assert!(fest.show_without_time);
+        assert_eq!(fest.utc_end - fest.utc_start, Duration::days(1));
+        Ok(())
+    }
+
+    #[test]
+    fn location_values_for_export() {
+        let locations = vec![
+            EventLocation::Virtual {
+                name: None,
+                description: None,
+                icon: None,
+                uri: "https://meet.example.org/team".to_owned(),
+                notes: None,
+            },
+            EventLocation::Physical {
+                name: Some("Room 101".to_owned()),
+                description: None,
+                icon: None,
+                coordinates: Some("geo:52.52,13.405;u=10".to_owned()),
+                uri: Some("https://example.org/rooms".to_owned()),
+                address: Some("Main St 1".to_owned()),
+                notes: None,
+            },
+        ];
+        let (location, geo, url) = ical_location_values(&locations);
+        assert_eq!(location.as_deref(), Some("Room 101, Main St 1"));
+        assert_eq!(geo.as_deref(), Some("52.52;13.405"));
+        assert_eq!(url.as_deref(), Some("https://meet.example.org/team"));
+
+        let (location, geo, url) = ical_location_values(&locations[..1]);
+        assert_eq!(location.as_deref(), Some("https://meet.example.org/team"));
+        assert_eq!(geo, None);
+        assert_eq!(url.as_deref(), Some("https://meet.example.org/team"));
+    }
+
+    #[test]
+    fn vtimezone_lists_transitions() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let vtimezone = ical_vtimezone(&tz, 2024, 2024);
+        assert!(vtimezone.starts_with("BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\n"));
+        assert!(vtimezone.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:20240331T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200"
+        ));
+        assert!(vtimezone.contains(
+            "BEGIN:STANDARD\r\nDTSTART:20241027T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100"
+        ));
+        assert!(vtimezone.ends_with("END:VTIMEZONE"));
+
+        // open ended ranges only cover the last years
+        let vtimezone = ical_vtimezone(&tz, 1900, 2030);
+        assert_eq!(vtimezone.matches("BEGIN:DAYLIGHT").count(), 30);
+        assert!(vtimezone.contains("DTSTART:20010325T020000"));
+        assert!(vtimezone.contains("DTSTART:20300331T020000"));
+        assert!(!vtimezone.contains("DTSTART:2000"));
+
+        let tz: Tz = "Asia/Kolkata".parse().unwrap();
+        let vtimezone = ical_vtimezone(&tz, 2024, 2025);
+        assert_eq!(vtimezone.matches("BEGIN:STANDARD").count(), 1);
+        assert!(vtimezone.contains("TZOFFSETTO:+0530"));
+    }
+
+    #[test]
+    fn huge_counts_only_cover_the_vtimezone_years() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Daily standup",
+                "utc_start":"2024-03-05T09:00:00Z","utc_end":"2024-03-05T09:15:00Z",
+                "time_zone":"Europe/Berlin",
+                "recurrence":"FREQ=DAILY;COUNT=4294967295"},
+            "origin_server_ts":1672407531453,
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global"}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(standup) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a calendar event");
+        };
+        let (tz, from, to) = standup
+            .ical_time_zone_years()
+            .expect("event has a time zone");
+        assert_eq!(tz, chrono_tz::Europe::Berlin);
+        assert_eq!((from, to), (2024, 2024 + MAX_VTIMEZONE_YEARS - 1));
+        Ok(())
+    }
+
+    #[test]
+    fn feed_lists_attendees_with_their_rsvp() -> anyhow::Result<()> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Promenade briefing",
+                "utc_start":"2024-03-05T09:00:00Z","utc_end":"2024-03-05T10:00:00Z"},
+            "origin_server_ts":1672407531453,
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global"}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(briefing) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a calendar event");
+        };
+
+        let mut rsvps: HashMap<OwnedUserId, Rsvp> = HashMap::new();
+        for (idx, (name, status)) in [("kira", "yes"), ("quark", "no"), ("sisko", "maybe")]
+            .into_iter()
+            .enumerate()
+        {
+            let sender = format!("@{name}:ds9.acter.global");
+            let json_raw = format!(
+                r#"{{"type":"global.acter.dev.rsvp",
+                "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"{sender}",
+                "content":{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                    "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c"}},
+                    "status":{{"type":"{status}"}}}},
+                "origin_server_ts":{},
+                "event_id":"$rsvp{idx}",
+                "user_id":"{sender}"}}"#,
+                1672407531454 + idx as u64
+            );
+            let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+            let AnyActerModel::Rsvp(rsvp) = AnyActerModel::try_from(event)? else {
+                anyhow::bail!("Not an rsvp");
+            };
+            rsvps.insert(OwnedUserId::try_from(sender)?, rsvp);
+        }
+
+        // odo hasn't answered yet
+        let odo: OwnedUserId = "@odo:ds9.acter.global".try_into()?;
+        let cal_event = briefing.as_ical_event_with_rsvps(&rsvps, Some(&odo), &[]);
+        let ics = ical_document(None, [(&briefing, cal_event)]).replace("\r\n ", "");
+        let attendees = ics
+            .lines()
+            .filter(|line| line.starts_with("ATTENDEE"))
+            .collect::<Vec<_>>();
+        assert_eq!(attendees.len(), 4);
+        for (name, partstat) in [
+            ("kira", "ACCEPTED"),
+            ("quark", "DECLINED"),
+            ("sisko", "TENTATIVE"),
+            ("odo", "NEEDS-ACTION"),
+        ] {
+            let address = format!(":matrix:u/{name}:ds9.acter.global");
+            let line = attendees
+                .iter()
+                .find(|line| line.ends_with(&address))
+                .unwrap_or_else(|| panic!("{name} is missing from the attendees"));
+            assert!(line.contains(&format!(";PARTSTAT={partstat}")), "{line}");
+            assert!(line.contains(";CUTYPE=INDIVIDUAL"), "{line}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn skips_broken_events() -> anyhow::Result<()> {
+        let ics = ICS.replace("SUMMARY:Summer fest\r\n", "");
+        let drafts = drafts_from_ical(&ics)?;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].build()?.title, "Team meeting");
+
+        let ics = ICS.replace("TZID=Europe/Berlin", "TZID=W. Europe Standard Time");
+        let drafts = drafts_from_ical(&ics)?;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].build()?.title, "Summer fest");
+        Ok(())
+    }
+
+    #[test]
+    fn series_keep_their_time_zone_over_dst() -> anyhow::Result<()> {
+        let ics = ICS
+            .replace("20240305T100000", "20240319T100000")
+            .replace("20240305T110000", "20240319T110000")
+            .replace("BYDAY=TU", "COUNT=3")
+            .replace("20240312T100000", "20240326T100000");
+        let content = drafts_from_ical(&ics)?[0].build()?;
+        assert_eq!(content.time_zone, Some(chrono_tz::Europe::Berlin));
+
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{},
+            "origin_server_ts":1672407531453,
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global"}}"#,
+            serde_json::to_string(&content)?
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::CalendarEvent(meeting) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a calendar event");
+        };
+        let starts = meeting
+            .occurrences_between(
+                "2024-03-01T00:00:00Z".parse()?,
+                "2024-04-30T00:00:00Z".parse()?,
+            )
+            .into_iter()
+            .map(|o| o.utc_start().to_rfc3339())
+            .collect::<Vec<_>>();
+        // still at ten in Berlin after summer time started on March 31st
+        assert_eq!(
+            starts,
+            ["2024-03-19T09:00:00+00:00", "2024-04-02T08:00:00+00:00"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn local_times_in_dst_gap_move_forward() -> anyhow::Result<()> {
+        // 02:30 doesn’t exist in Berlin that night, it is 03:30 CEST instead
+        let ics = ICS
+            .replace("20240305T100000", "20240331T023000")
+            .replace("20240305T110000", "20240331T040000");
+        let meeting = drafts_from_ical(&ics)?[0].build()?;
+        assert_eq!(meeting.utc_start.to_rfc3339(), "2024-03-31T01:30:00+00:00");
+        assert_eq!(meeting.utc_end.to_rfc3339(), "2024-03-31T02:00:00+00:00");
+        Ok(())
+    }
+}
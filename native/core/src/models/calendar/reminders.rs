@@ -0,0 +1,392 @@
+use chrono::{Duration, Utc};
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::{trace, warn};
+
+use super::CalendarEvent;
+use crate::{
+    events::{calendar::EventReminder, room::CalendarRemindersEventContent, UtcDateTime},
+    executor::Executor,
+    models::{ActerModel, AnyActerModel},
+    referencing::{ExecuteReference, IndexKey, SectionIndex, SpecialListsIndex},
+    Result,
+};
+
+/// How far ahead we look for the next reminder
+const LOOK_AHEAD_DAYS: i64 = 366;
+
+/// How far back we still fire reminders missed while we weren’t running
+const CATCH_UP_HOURS: i64 = 24;
+
+/// How many due reminders we keep for the app to take, older ones are dropped
+const MAX_PENDING_REMINDERS: usize = 100;
+
+/// Where we keep until when we checked the reminders across restarts
+static CHECKED_UNTIL_KEY: &str = "calendar_reminders_checked_until";
+
+/// A reminder of an upcoming calendar event occurrence that is due
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DueReminder {
+    pub event_id: OwnedEventId,
+    pub room_id: OwnedRoomId,
+    pub title: String,
+    pub occurrence_start: UtcDateTime,
+    pub minutes_before: u32,
+    pub due_at: UtcDateTime,
+}
+
+impl DueReminder {
+    pub fn event_id(&self) -> OwnedEventId {
+        self.event_id.clone()
+    }
+
+    pub fn room_id(&self) -> OwnedRoomId {
+        self.room_id.clone()
+    }
+
+    pub fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    pub fn occurrence_start(&self) -> UtcDateTime {
+        self.occurrence_start
+    }
+
+    pub fn minutes_before(&self) -> u32 {
+        self.minutes_before
+    }
+
+    pub fn due_at(&self) -> UtcDateTime {
+        self.due_at
+    }
+}
+
+/// All reminders of `event` due after `from` until (and including) `to`
+pub fn reminders_due(
+    event: &CalendarEvent,
+    reminders: &[EventReminder],
+    from: UtcDateTime,
+    to: UtcDateTime,
+) -> Vec<DueReminder> {
+    let mut due = Vec::new();
+    for reminder in reminders {
+        let offset = reminder.offset();
+        for occurrence in
+            event.occurrences_between(from + offset, to + offset + Duration::seconds(1))
+        {
+            let due_at = occurrence.utc_start() - offset;
+            if due_at <= from || due_at > to {
+                // only overlapping, not starting in our range
+                continue;
+            }
+            due.push(DueReminder {
+                event_id: event.event_id().to_owned(),
+                room_id: event.room_id().to_owned(),
+                title: event.title(),
+                occurrence_start: occurrence.utc_start(),
+                minutes_before: reminder.minutes_before,
+                due_at,
+            });
+        }
+    }
+    due.sort_by_key(|r| r.due_at);
+    due
+}
+
+/// When the next reminder of `event` after `after` is due
+pub fn next_reminder_after(
+    event: &CalendarEvent,
+    reminders: &[EventReminder],
+    after: UtcDateTime,
+) -> Option<UtcDateTime> {
+    reminders
+        .iter()
+        .filter_map(|reminder| {
+            let offset = reminder.offset();
+            event
+                .occurrences_between(
+                    after + offset,
+                    after + offset + Duration::days(LOOK_AHEAD_DAYS),
+                )
+                .into_iter()
+                .map(|occurrence| occurrence.utc_start() - offset)
+                .find(|due_at| *due_at > after)
+        })
+        .min()
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    checked_until: Option<UtcDateTime>,
+    due: Vec<DueReminder>,
+}
+
+impl SchedulerState {
+    /// Add newly due reminders, dropping the oldest ones nobody took
+    fn queue(&mut self, due: Vec<DueReminder>) {
+        self.due.extend(due);
+        let overflow = self.due.len().saturating_sub(MAX_PENDING_REMINDERS);
+        self.due.drain(..overflow);
+    }
+}
+
+/// Finds the calendar reminders coming due and lets the subscribers know
+/// through the executor, so the app can show them as local notifications.
+///
+/// [`run`](Self::run) checks whenever the next reminder is due. Until when it
+/// checked is kept in the store, so reminders that came due while we weren’t
+/// running fire on the next start.
+#[derive(Clone, Debug)]
+pub struct ReminderScheduler {
+    executor: Executor,
+    state: Arc<Mutex<SchedulerState>>,
+    running: Arc<AtomicBool>,
+}
+
+impl ReminderScheduler {
+    pub fn new(executor: Executor) -> Self {
+        ReminderScheduler {
+            executor,
+            state: Default::default(),
+            running: Default::default(),
+        }
+    }
+
+    pub fn key() -> ExecuteReference {
+        ExecuteReference::Index(IndexKey::Special(SpecialListsIndex::DueReminders))
+    }
+
+    pub fn subscribe(&self) -> Receiver<()> {
+        self.executor.subscribe(Self::key())
+    }
+
+    /// The reminders of the user for this event, falling back to the ones of the event
+    pub async fn reminders_for(&self, event: &CalendarEvent) -> Result<Vec<EventReminder>> {
+        let Some(room) = self.executor.store().client.get_room(event.room_id()) else {
+            return Ok(event.reminders.clone());
+        };
+        let own = match room
+            .account_data_static::<CalendarRemindersEventContent>()
+            .await?
+            .map(|raw| raw.deserialize())
+        {
+            Some(Ok(data)) => data.content.events.get(event.event_id()).cloned(),
+            Some(Err(error)) => {
+                warn!(?error, room_id = ?room.room_id(), "Deserializing calendar reminders failed");
+                None
+            }
+            None => None,
+        };
+        Ok(own.unwrap_or_else(|| event.reminders.clone()))
+    }
+
+    async fn calendar_events(&self) -> Result<Vec<CalendarEvent>> {
+        Ok(self
+            .executor
+            .store()
+            .get_list(&IndexKey::Section(SectionIndex::Calendar))
+            .await?
+            .filter_map(|mdl| match mdl {
+                AnyActerModel::CalendarEvent(event) => Some(event),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Collect all reminders that came due since the last check, even before
+    /// a restart, and notify the subscribers if there are any. The very first
+    /// check only starts the clock. Returns the number of newly due reminders.
+    pub async fn check(&self, now: UtcDateTime) -> Result<usize> {
+        let checked_until = self.state.lock()?.checked_until;
+        let checked_until = match checked_until {
+            Some(checked_until) => Some(checked_until),
+            None => self
+                .executor
+                .store()
+                .get_raw::<UtcDateTime>(CHECKED_UNTIL_KEY)
+                .await
+                .ok(),
+        };
+        let from = checked_until
+            .unwrap_or(now)
+            .max(now - Duration::hours(CATCH_UP_HOURS));
+
+        let mut due = Vec::new();
+        if from < now {
+            for event in self.calendar_events().await? {
+                let reminders = self.reminders_for(&event).await?;
+                due.extend(reminders_due(&event, &reminders, from, now));
+            }
+        }
+        let count = due.len();
+        trace!(?from, ?now, count, "checked calendar reminders");
+        if count > 0 {
+            self.state.lock()?.queue(due);
+            self.executor.notify(vec![Self::key()]);
+        }
+
+        // only once they are handed out, so a failed check is tried again
+        self.executor
+            .store()
+            .set_raw(CHECKED_UNTIL_KEY, &now)
+            .await?;
+        self.state.lock()?.checked_until = Some(now);
+        Ok(count)
+    }
+
+    /// When the next reminder after `after` is due, if any
+    pub async fn next_due(&self, after: UtcDateTime) -> Result<Option<UtcDateTime>> {
+        let mut next = None;
+        for event in self.calendar_events().await? {
+            let reminders = self.reminders_for(&event).await?;
+            next = match (next, next_reminder_after(&event, &reminders, after)) {
+                (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                (a, b) => a.or(b),
+            };
+        }
+        Ok(next)
+    }
+
+    /// Take all due reminders not yet taken
+    pub fn take_due(&self) -> Result<Vec<DueReminder>> {
+        Ok(std::mem::take(&mut self.state.lock()?.due))
+    }
+
+    /// Keep checking for due reminders whenever the next one is due or the
+    /// calendar events change. Only one loop runs at a time, further calls
+    /// return right away.
+    pub async fn run(&self) -> Result<()> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let mut calendar = self
+            .executor
+            .subscribe(IndexKey::Section(SectionIndex::Calendar));
+        let result = loop {
+            let now = Utc::now();
+            if let Err(error) = self.check(now).await {
+                warn!(?error, "Checking calendar reminders failed");
+            }
+            let wait = match self.next_due(now).await {
+                Ok(Some(next)) => (next - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+                    .min(std::time::Duration::from_secs(3600)),
+                Ok(None) => std::time::Duration::from_secs(3600),
+                Err(error) => break Err(error),
+            };
+            tokio::select! {
+                received = calendar.recv() => {
+                    if matches!(received, Err(RecvError::Closed)) {
+                        break Ok(());
+                    }
+                }
+                _ = tokio::time::sleep(wait) => {}
+            }
+        };
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AnyActerEvent;
+    use crate::models::fixtures;
+
+    fn weekly_event() -> anyhow::Result<CalendarEvent> {
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Weekly sync",
+                "utc_start":"2024-03-05T10:00:00Z","utc_end":"2024-03-05T11:00:00Z",
+                "recurrence":"FREQ=WEEKLY;COUNT=3",
+                "reminders":[{"minutes_before":1440},{"minutes_before":15}]},
+            "origin_server_ts":1672407531453,
+            "unsigned":{"age":11523850},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        let AnyActerModel::CalendarEvent(event) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a calendar event");
+        };
+        Ok(event)
+    }
+
+    #[test]
+    fn finds_due_reminders() -> anyhow::Result<()> {
+        let event = weekly_event()?;
+        let from = "2024-03-11T09:00:00Z".parse()?;
+        let to = "2024-03-12T09:50:00Z".parse()?;
+        let due = reminders_due(&event, &event.reminders, from, to)
+            .into_iter()
+            .map(|r| (r.due_at.to_rfc3339(), r.minutes_before))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            due,
+            [
+                ("2024-03-11T10:00:00+00:00".to_owned(), 1440),
+                ("2024-03-12T09:45:00+00:00".to_owned(), 15),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn next_reminder_skips_past_ones() -> anyhow::Result<()> {
+        let event = weekly_event()?;
+        let after = "2024-03-12T09:45:00Z".parse()?;
+        let next = next_reminder_after(&event, &event.reminders, after);
+        assert_eq!(next, Some("2024-03-18T10:00:00Z".parse()?));
+
+        let after = "2024-03-19T09:45:00Z".parse()?;
+        assert_eq!(next_reminder_after(&event, &event.reminders, after), None);
+        Ok(())
+    }
+
+    #[test]
+    fn pending_reminders_are_bounded() -> anyhow::Result<()> {
+        let event = weekly_event()?;
+        let from = "2024-03-11T09:00:00Z".parse()?;
+        let to = "2024-03-12T09:50:00Z".parse()?;
+        let due = reminders_due(&event, &event.reminders, from, to);
+
+        let mut state = SchedulerState::default();
+        for _ in 0..MAX_PENDING_REMINDERS {
+            state.queue(due.clone());
+        }
+        assert_eq!(state.due.len(), MAX_PENDING_REMINDERS);
+        // the oldest ones are dropped first
+        assert_eq!(state.due.last(), due.last());
+        assert_eq!(state.due[0], due[0]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fires_reminders_missed_while_not_running() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        executor.handle(weekly_event()?.into()).await?;
+
+        let scheduler = ReminderScheduler::new(executor.clone());
+        // the very first check only starts the clock
+        assert_eq!(scheduler.check("2024-03-11T09:00:00Z".parse()?).await?, 0);
+
+        // restarted after the reminder a day before was due
+        let restarted = ReminderScheduler::new(executor);
+        assert_eq!(restarted.check("2024-03-11T12:00:00Z".parse()?).await?, 1);
+        let due = restarted.take_due()?;
+        assert_eq!(
+            due[0].due_at,
+            "2024-03-11T10:00:00Z".parse::<UtcDateTime>()?
+        );
+        assert_eq!(due[0].minutes_before, 1440);
+
+        // nothing fires twice
+        assert_eq!(restarted.check("2024-03-11T12:05:00Z".parse()?).await?, 0);
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 use derive_getters::Getters;
-use matrix_sdk_base::ruma::{events::OriginalMessageLikeEvent, EventId, OwnedEventId, UserId};
+use matrix_sdk_base::ruma::{
+    events::{Mentions, OriginalMessageLikeEvent},
+    EventId, OwnedEventId, UserId,
+};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use tracing::{error, info, trace};
@@ -72,6 +75,36 @@ impl CommentsManager {
         Ok(comments)
     }
 
+    /// The comments that aren’t replies, each with its first replies
+    pub async fn threads(&self, replies_limit: usize) -> Result<Vec<CommentThread>> {
+        let mut threads = vec![];
+        for comment in self.comments().await? {
+            if comment.is_reply() {
+                continue;
+            }
+            let replies =
+                CommentsManager::from_store_and_event_id(&self.store, comment.event_id()).await;
+            threads.push(CommentThread {
+                reply_count: replies.total_comments_count,
+                replies: replies.replies(0, replies_limit).await?,
+                comment,
+            });
+        }
+        Ok(threads)
+    }
+
+    /// The direct replies to the comment this manager is for, oldest first
+    pub async fn replies(&self, offset: usize, limit: usize) -> Result<Vec<Comment>> {
+        let mut replies = self
+            .comments()
+            .await?
+            .into_iter()
+            .filter(|c| c.reply_to_id().is_some_and(|id| *id == self.event_id))
+            .collect::<Vec<_>>();
+        replies.sort_by_key(|c| c.meta.origin_server_ts);
+        Ok(replies.into_iter().skip(offset).take(limit).collect())
+    }
+
     pub(crate) async fn add_comment(&mut self, _comment: &Comment) -> Result<bool> {
         self.stats.has_comments = true;
         self.stats.total_comments_count += 1;
@@ -112,6 +145,22 @@ impl Deref for CommentsManager {
     }
 }
 
+/// A comment with the number of replies and the first of them
+#[derive(Clone, Debug, Getters)]
+pub struct CommentThread {
+    comment: Comment,
+    reply_count: u32,
+    replies: Vec<Comment>,
+}
+
+impl CommentThread {
+    /// How many replies aren’t loaded yet
+    pub fn more_replies_count(&self) -> u32 {
+        self.reply_count
+            .saturating_sub(self.replies.len().try_into().unwrap_or(u32::MAX))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Comment {
     pub(crate) inner: CommentEventContent,
@@ -136,12 +185,30 @@ impl Comment {
             .to_owned()
     }
 
-    pub fn reply_builder(&self) -> CommentBuilder {
+    /// Replies mention the author of this comment, so they get notified,
+    /// unless it is the `replier` themselves
+    pub fn reply_builder(&self, replier: &UserId) -> CommentBuilder {
         let event_id = self.meta.event_id.clone();
-        CommentBuilder::default()
+        let mut builder = CommentBuilder::default();
+        builder
             .on(self.on.event_id.clone())
-            .reply_to(Some(event_id.into()))
-            .to_owned()
+            .reply_to(Some(event_id.into()));
+        if *self.meta.sender != *replier {
+            builder.mentions(Some(Mentions::with_user_ids([self.meta.sender.clone()])));
+        }
+        builder
+    }
+
+    /// The comment this one replies to, if any
+    pub fn reply_to_id(&self) -> Option<&OwnedEventId> {
+        self.inner
+            .reply_to
+            .as_ref()
+            .and_then(|r| r.event_ids.first())
+    }
+
+    pub fn is_reply(&self) -> bool {
+        self.reply_to_id().is_some()
     }
 
     fn belongs_to_inner(&self) -> Vec<OwnedEventId> {
@@ -0,0 +1,434 @@
+use matrix_sdk_base::ruma::{
+    events::OriginalMessageLikeEvent, EventId, OwnedEventId, RoomId, UserId,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use tracing::trace;
+
+use super::{
+    default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, RedactedActerModel,
+};
+use crate::{
+    events::documents::{
+        DocumentEventContent, DocumentRevisionBuilder, DocumentRevisionEventContent,
+    },
+    referencing::{ExecuteReference, IndexKey, ObjectListIndex, SectionIndex},
+    store::Store,
+    Result,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Document {
+    pub(crate) inner: DocumentEventContent,
+    meta: EventMeta,
+
+    /// the state the document was created with, kept once it was revised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    original: Option<DocumentEventContent>,
+
+    /// the latest revision applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    head: Option<OwnedEventId>,
+
+    #[serde(default)]
+    revision_count: u32,
+
+    /// the revisions since the last clean one that weren’t based on the
+    /// revision before them and might have overwritten it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    conflicts: Vec<OwnedEventId>,
+}
+
+impl Deref for Document {
+    type Target = DocumentEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Document {
+    pub fn title(&self) -> String {
+        self.inner.title.clone()
+    }
+
+    pub fn room_id(&self) -> &RoomId {
+        &self.meta.room_id
+    }
+
+    pub fn sender(&self) -> &UserId {
+        &self.meta.sender
+    }
+
+    /// The revision the current state is from, the document itself if it
+    /// was never revised
+    pub fn current_revision(&self) -> &EventId {
+        self.head.as_deref().unwrap_or(&self.meta.event_id)
+    }
+
+    pub fn revision_count(&self) -> u32 {
+        self.revision_count
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    pub fn conflicts(&self) -> &[OwnedEventId] {
+        &self.conflicts
+    }
+
+    /// The state the document was created with
+    pub fn original(&self) -> &DocumentEventContent {
+        self.original.as_ref().unwrap_or(&self.inner)
+    }
+
+    /// Start a new revision based on the current one
+    pub fn reviser(&self) -> DocumentRevisionBuilder {
+        DocumentRevisionBuilder::default()
+            .document(self.meta.event_id.clone())
+            .parent_revision(self.current_revision().to_owned())
+            .title(self.inner.title.clone())
+            .body(self.inner.body.clone())
+            .to_owned()
+    }
+
+    /// A new revision bringing back the state of the given older revision or
+    /// of the document as it was created
+    pub async fn restorer(
+        &self,
+        store: &Store,
+        revision_id: &EventId,
+    ) -> Result<DocumentRevisionBuilder> {
+        let (title, body) = if *revision_id == *self.meta.event_id {
+            let original = self.original();
+            (original.title.clone(), original.body.clone())
+        } else {
+            let AnyActerModel::DocumentRevision(revision) =
+                store.get(&revision_id.to_owned()).await?
+            else {
+                return Err(crate::Error::Custom(format!(
+                    "{revision_id} is not a document revision"
+                )));
+            };
+            if revision.inner.document.event_id != self.meta.event_id {
+                return Err(crate::Error::Custom(format!(
+                    "{revision_id} is a revision of another document"
+                )));
+            }
+            (revision.inner.title, revision.inner.body)
+        };
+        Ok(self
+            .reviser()
+            .title(title)
+            .body(body)
+            .restored_from(Some(revision_id.to_owned()))
+            .to_owned())
+    }
+
+    fn apply_revision(&mut self, revision: &DocumentRevision) {
+        if *revision.inner.parent_revision != *self.current_revision() {
+            trace!(
+                revision = ?revision.meta.event_id,
+                parent = ?revision.inner.parent_revision,
+                head = ?self.current_revision(),
+                "revision not based on the latest one, conflict"
+            );
+            self.conflicts.push(revision.meta.event_id.clone());
+        } else {
+            self.conflicts.clear();
+        }
+        if self.original.is_none() {
+            self.original = Some(self.inner.clone());
+        }
+        revision.inner.apply(&mut self.inner);
+        self.head = Some(revision.meta.event_id.clone());
+        self.revision_count += 1;
+    }
+
+    /// Start over from the original and apply the given revisions in the
+    /// order they were sent, e.g. once one of them got redacted
+    fn replay(&mut self, mut revisions: Vec<DocumentRevision>) {
+        revisions.sort_by(|a, b| {
+            (a.meta.origin_server_ts, &a.meta.event_id)
+                .cmp(&(b.meta.origin_server_ts, &b.meta.event_id))
+        });
+        if let Some(original) = self.original.take() {
+            self.inner = original;
+        }
+        self.head = None;
+        self.revision_count = 0;
+        self.conflicts.clear();
+        for revision in &revisions {
+            self.apply_revision(revision);
+        }
+    }
+}
+
+impl ActerModel for Document {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            IndexKey::RoomSection(self.meta.room_id.clone(), SectionIndex::Documents),
+            IndexKey::Section(SectionIndex::Documents),
+            IndexKey::ObjectHistory(self.meta.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &[Capability] {
+        &[
+            Capability::Commentable,
+            Capability::Attachmentable,
+            Capability::Reactable,
+        ]
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        default_model_execute(store, self.into()).await
+    }
+
+    fn transition(&mut self, model: &AnyActerModel) -> Result<bool> {
+        let AnyActerModel::DocumentRevision(revision) = model else {
+            return Ok(false);
+        };
+        self.apply_revision(revision);
+        Ok(true)
+    }
+}
+
+impl From<OriginalMessageLikeEvent<DocumentEventContent>> for Document {
+    fn from(outer: OriginalMessageLikeEvent<DocumentEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        Document {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+            original: None,
+            head: None,
+            revision_count: 0,
+            conflicts: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DocumentRevision {
+    pub(crate) inner: DocumentRevisionEventContent,
+    pub meta: EventMeta,
+}
+
+impl Deref for DocumentRevision {
+    type Target = DocumentRevisionEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DocumentRevision {
+    pub fn index_for(document_id: OwnedEventId) -> IndexKey {
+        IndexKey::ObjectList(document_id, ObjectListIndex::Revisions)
+    }
+
+    /// All revisions of the document, latest first
+    pub async fn all_for(store: &Store, document_id: &EventId) -> Result<Vec<DocumentRevision>> {
+        Ok(store
+            .get_list(&Self::index_for(document_id.to_owned()))
+            .await?
+            .filter_map(|mdl| match mdl {
+                AnyActerModel::DocumentRevision(r) => Some(r),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl ActerModel for DocumentRevision {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            DocumentRevision::index_for(self.inner.document.event_id.clone()),
+            IndexKey::ObjectHistory(self.inner.document.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        default_model_execute(store, self.into()).await
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        Some(vec![self.inner.document.event_id.clone()])
+    }
+
+    async fn redact(
+        &self,
+        store: &Store,
+        redaction_model: RedactedActerModel,
+    ) -> Result<Vec<ExecuteReference>> {
+        let document_id = &self.inner.document.event_id;
+        let mut models = vec![];
+        // what it changed has to go, recompute from the remaining revisions
+        if let Ok(AnyActerModel::Document(mut document)) = store.get(document_id).await {
+            let mut remaining = DocumentRevision::all_for(store, document_id).await?;
+            remaining.retain(|r| r.meta.event_id != self.meta.event_id);
+            trace!(?document_id, revision = ?self.meta.event_id, "replaying remaining revisions");
+            document.replay(remaining);
+            models.push(document.into());
+        }
+        models.push(redaction_model.into());
+        store.save_many(models).await
+    }
+}
+
+impl From<OriginalMessageLikeEvent<DocumentRevisionEventContent>> for DocumentRevision {
+    fn from(outer: OriginalMessageLikeEvent<DocumentRevisionEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        DocumentRevision {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events::AnyActerEvent, executor::Executor, models::fixtures};
+
+    const DOC_ID: &str = "$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c";
+
+    fn model(event_id: &str, event_type: &str, content: &str) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":1672407531453,
+            "event_id":"{event_id}",
+            "user_id":"@odo:ds9.acter.global"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    fn revision(event_id: &str, parent: &str, title: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            event_id,
+            "global.acter.dev.document.revision",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.update","event_id":"{DOC_ID}"}},
+                "parent_revision":"{parent}","title":"{title}","body":{{"body":"{title}"}}}}"#
+            ),
+        )
+    }
+
+    #[test]
+    fn concurrent_revisions_conflict() -> anyhow::Result<()> {
+        let AnyActerModel::Document(mut doc) = model(
+            DOC_ID,
+            "global.acter.dev.document",
+            r#"{"title":"Station rules","body":{"body":"No weapons on the promenade"}}"#,
+        )?
+        else {
+            anyhow::bail!("Not a document");
+        };
+        assert_eq!(doc.current_revision().as_str(), DOC_ID);
+
+        assert!(doc.transition(&revision("$rev1", DOC_ID, "Rules v1")?)?);
+        assert_eq!(doc.current_revision().as_str(), "$rev1");
+        assert!(!doc.has_conflict());
+
+        // kira and odo both edit from the first revision
+        doc.transition(&revision("$rev2", "$rev1", "Rules v2")?)?;
+        doc.transition(&revision("$rev3", "$rev1", "Rules v2b")?)?;
+        assert!(doc.has_conflict());
+        assert_eq!(doc.conflicts().len(), 1);
+        assert_eq!(doc.conflicts()[0].as_str(), "$rev3");
+        assert_eq!(doc.title(), "Rules v2b");
+        assert_eq!(doc.revision_count(), 3);
+
+        // building on the latest one resolves it
+        doc.transition(&revision("$rev4", "$rev3", "Rules v3")?)?;
+        assert!(!doc.has_conflict());
+        assert_eq!(doc.original().title, "Station rules");
+
+        let reviser = doc.reviser().build()?;
+        assert_eq!(reviser.parent_revision.as_str(), "$rev4");
+        assert_eq!(reviser.title, "Rules v3");
+        Ok(())
+    }
+
+    async fn stored_document(executor: &Executor) -> anyhow::Result<Document> {
+        let AnyActerModel::Document(doc) = executor.store().get(&EventId::parse(DOC_ID)?).await?
+        else {
+            anyhow::bail!("Not a document");
+        };
+        Ok(doc)
+    }
+
+    #[tokio::test]
+    async fn redacted_revisions_are_undone() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        executor
+            .handle(model(
+                DOC_ID,
+                "global.acter.dev.document",
+                r#"{"title":"Station rules","body":{"body":"No weapons on the promenade"}}"#,
+            )?)
+            .await?;
+        executor
+            .handle(revision("$rev1", DOC_ID, "Rules v1")?)
+            .await?;
+        executor
+            .handle(revision("$rev2", "$rev1", "Rules v2")?)
+            .await?;
+        assert_eq!(stored_document(&executor).await?.title(), "Rules v2");
+
+        // the latest one is gone, we are back at the first revision
+        fixtures::redact(&executor, "$rev2").await?;
+        let doc = stored_document(&executor).await?;
+        assert_eq!(doc.title(), "Rules v1");
+        assert_eq!(doc.current_revision().as_str(), "$rev1");
+        assert_eq!(doc.revision_count(), 1);
+
+        // and with that one gone, too, at the original
+        fixtures::redact(&executor, "$rev1").await?;
+        let doc = stored_document(&executor).await?;
+        assert_eq!(doc.title(), "Station rules");
+        assert_eq!(doc.current_revision().as_str(), DOC_ID);
+        assert_eq!(doc.revision_count(), 0);
+        Ok(())
+    }
+}
@@ -136,6 +136,18 @@ impl InvitationsManager {
         was_invited
     }
 
+    /// Being assigned to the object counts as having accepted
+    pub(crate) fn mark_as_assigned(&mut self, entry: OwnedUserId) -> bool {
+        self.stats.invited.remove(&entry);
+        self.stats.declined.remove(&entry);
+        self.stats.accepted.insert(entry)
+    }
+
+    /// Being unassigned by someone else isn't declining, they are just not part of it anymore
+    pub(crate) fn mark_as_unassigned(&mut self, entry: &UserId) -> bool {
+        self.stats.accepted.remove(entry)
+    }
+
     pub fn stats(&self) -> &InviteStats {
         &self.stats
     }
@@ -13,7 +13,7 @@ use crate::{
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct NewsEntry {
-    inner: NewsEntryEventContent,
+    pub(crate) inner: NewsEntryEventContent,
     pub meta: EventMeta,
 }
 
@@ -8,12 +8,13 @@ use crate::{
     events::pins::{PinEventContent, PinUpdateBuilder, PinUpdateEventContent},
     referencing::{ExecuteReference, IndexKey, SectionIndex},
     store::Store,
+    util::serialized_differ,
     Result,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Pin {
-    inner: PinEventContent,
+    pub(crate) inner: PinEventContent,
     meta: EventMeta,
 }
 impl Deref for Pin {
@@ -44,6 +45,28 @@ impl Pin {
             .pin(self.meta.event_id.clone())
             .to_owned()
     }
+
+    /// Update setting all fields that differ back to the given earlier state
+    pub fn reverter(&self, target: &PinEventContent) -> PinUpdateBuilder {
+        let current = &self.inner;
+        let mut builder = self.updater();
+        if serialized_differ(&current.title, &target.title) {
+            builder.title(Some(target.title.clone()));
+        }
+        if serialized_differ(&current.content, &target.content) {
+            builder.content(Some(target.content.clone()));
+        }
+        if serialized_differ(&current.url, &target.url) {
+            builder.url(Some(target.url.clone()));
+        }
+        if serialized_differ(&current.display, &target.display) {
+            builder.display(Some(target.display.clone()));
+        }
+        if serialized_differ(&current.deadline, &target.deadline) {
+            builder.deadline(Some(target.deadline));
+        }
+        builder
+    }
 }
 
 impl ActerModel for Pin {
@@ -0,0 +1,585 @@
+use chrono::DateTime;
+use derive_getters::Getters;
+use matrix_sdk_base::ruma::{
+    events::OriginalMessageLikeEvent, EventId, OwnedEventId, OwnedUserId, RoomId, UserId,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ops::Deref};
+use tracing::{error, trace};
+
+use super::{
+    default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, RedactedActerModel,
+};
+use crate::{
+    events::polls::{
+        PollEventContent, PollUpdateBuilder, PollUpdateEventContent, PollVoteBuilder,
+        PollVoteEventContent,
+    },
+    referencing::{ExecuteReference, IndexKey, ModelParam, ObjectListIndex, SectionIndex},
+    store::Store,
+    Result,
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Poll {
+    inner: PollEventContent,
+    meta: EventMeta,
+}
+
+impl Deref for Poll {
+    type Target = PollEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Poll {
+    pub fn question(&self) -> String {
+        self.inner.question.clone()
+    }
+
+    pub fn room_id(&self) -> &RoomId {
+        &self.meta.room_id
+    }
+
+    pub fn sender(&self) -> &UserId {
+        &self.meta.sender
+    }
+
+    pub fn updater(&self) -> PollUpdateBuilder {
+        PollUpdateBuilder::default()
+            .poll(self.meta.event_id.clone())
+            .to_owned()
+    }
+}
+
+impl ActerModel for Poll {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            IndexKey::RoomSection(self.meta.room_id.clone(), SectionIndex::Polls),
+            IndexKey::Section(SectionIndex::Polls),
+            IndexKey::ObjectHistory(self.meta.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    fn capabilities(&self) -> &[Capability] {
+        &[
+            Capability::Commentable,
+            Capability::Attachmentable,
+            Capability::Reactable,
+        ]
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        default_model_execute(store, self.into()).await
+    }
+
+    fn transition(&mut self, model: &AnyActerModel) -> Result<bool> {
+        let AnyActerModel::PollUpdate(update) = model else {
+            return Ok(false);
+        };
+
+        update.apply(&mut self.inner)
+    }
+}
+
+impl From<OriginalMessageLikeEvent<PollEventContent>> for Poll {
+    fn from(outer: OriginalMessageLikeEvent<PollEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        Poll {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PollUpdate {
+    pub(crate) inner: PollUpdateEventContent,
+    meta: EventMeta,
+}
+
+impl ActerModel for PollUpdate {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            IndexKey::ObjectHistory(self.inner.poll.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let poll_id = self.inner.poll.event_id.clone();
+        let mut updates = default_model_execute(store, self.into()).await?;
+        // a changed closing time changes which votes count
+        let mut manager = PollsManager::from_store_and_event_id(store, &poll_id).await;
+        if manager.recount().await? {
+            updates.push(manager.save().await?);
+        }
+        Ok(updates)
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        Some(vec![self.inner.poll.event_id.clone()])
+    }
+}
+
+impl Deref for PollUpdate {
+    type Target = PollUpdateEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl From<OriginalMessageLikeEvent<PollUpdateEventContent>> for PollUpdate {
+    fn from(outer: OriginalMessageLikeEvent<PollUpdateEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        PollUpdate {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PollVote {
+    pub(crate) inner: PollVoteEventContent,
+    pub meta: EventMeta,
+}
+
+impl Deref for PollVote {
+    type Target = PollVoteEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl PollVote {
+    pub fn index_for(parent: OwnedEventId) -> IndexKey {
+        IndexKey::ObjectList(parent, ObjectListIndex::PollVotes)
+    }
+
+    pub fn is_retraction(&self) -> bool {
+        self.inner.answers.is_empty()
+    }
+
+    async fn recount(&self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let poll_id = self.inner.to.event_id.clone();
+        let mut manager = PollsManager::from_store_and_event_id(store, &poll_id).await;
+        if !manager.recount().await? {
+            return Ok(vec![]);
+        }
+        Ok(vec![manager.save().await?])
+    }
+}
+
+impl ActerModel for PollVote {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            PollVote::index_for(self.inner.to.event_id.clone()),
+            IndexKey::ObjectHistory(self.inner.to.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let poll_id = self.inner.to.event_id.clone();
+        trace!(event_id=?self.event_id(), ?poll_id, "applying poll vote");
+        if !matches!(store.get(&poll_id).await?, AnyActerModel::Poll(_)) {
+            error!(?poll_id, vote = ?self, "votes can only be cast on polls. can’t apply");
+            return store.save(self.into()).await;
+        }
+        let vote = self.clone();
+        let mut updates = store.save(self.into()).await?;
+        updates.extend(vote.recount(store).await?);
+        Ok(updates)
+    }
+
+    async fn redact(
+        &self,
+        store: &Store,
+        redaction_model: RedactedActerModel,
+    ) -> Result<Vec<ExecuteReference>> {
+        // the redacted vote doesn’t count anymore
+        let mut updates = store.save(redaction_model.into()).await?;
+        updates.extend(self.recount(store).await?);
+        Ok(updates)
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        // we have a manager keeping the counts
+        None
+    }
+}
+
+impl From<OriginalMessageLikeEvent<PollVoteEventContent>> for PollVote {
+    fn from(outer: OriginalMessageLikeEvent<PollVoteEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        PollVote {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+/// The counted votes of a poll
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters)]
+pub struct PollResults {
+    /// the answers counted for each voter
+    votes: BTreeMap<OwnedUserId, Vec<String>>,
+    /// the number of voters per answer id
+    answer_counts: BTreeMap<String, u32>,
+}
+
+impl PollResults {
+    /// Count the `votes` by the rules of the `poll`: votes after it closed,
+    /// with unknown answers or too many of them are ignored. The latest vote
+    /// of each user counts, or their first if they may not change it. Votes
+    /// without answers retract the vote.
+    pub fn from_votes(poll: &PollEventContent, mut votes: Vec<PollVote>) -> Self {
+        votes.sort_by(|a, b| {
+            (a.meta.origin_server_ts, &a.meta.event_id)
+                .cmp(&(b.meta.origin_server_ts, &b.meta.event_id))
+        });
+        let mut counted: BTreeMap<OwnedUserId, Vec<String>> = BTreeMap::new();
+        let mut has_voted: Vec<&UserId> = vec![];
+        for vote in &votes {
+            let sender = vote.meta.sender.as_ref();
+            let sent_at = DateTime::from_timestamp_millis(vote.meta.origin_server_ts.get().into());
+            if sent_at.is_some_and(|at| poll.is_closed_at(at)) {
+                continue;
+            }
+            if !poll.allow_vote_change && has_voted.contains(&sender) {
+                continue;
+            }
+            if vote.answers.len() > poll.max_selections as usize
+                || vote.answers.iter().any(|id| poll.answer(id).is_none())
+            {
+                trace!(event_id = ?vote.meta.event_id, "ignoring invalid vote");
+                continue;
+            }
+            has_voted.push(sender);
+            if vote.is_retraction() {
+                counted.remove(sender);
+            } else {
+                let mut answers = vote.answers.clone();
+                answers.sort();
+                answers.dedup();
+                counted.insert(sender.to_owned(), answers);
+            }
+        }
+
+        let mut answer_counts = BTreeMap::new();
+        for answers in counted.values() {
+            for id in answers {
+                *answer_counts.entry(id.clone()).or_default() += 1;
+            }
+        }
+        PollResults {
+            votes: counted,
+            answer_counts,
+        }
+    }
+
+    pub fn voter_count(&self) -> u32 {
+        self.votes.len() as u32
+    }
+
+    pub fn count_for(&self, answer_id: &str) -> u32 {
+        self.answer_counts
+            .get(answer_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// the answers counted for the given user
+    pub fn vote_of(&self, user_id: &UserId) -> Option<&Vec<String>> {
+        self.votes.get(user_id)
+    }
+
+    /// the users that chose the given answer
+    pub fn voters_for(&self, answer_id: &str) -> Vec<OwnedUserId> {
+        self.votes
+            .iter()
+            .filter(|(_, answers)| answers.iter().any(|a| a == answer_id))
+            .map(|(user_id, _)| user_id.clone())
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Getters)]
+pub struct PollStats {
+    has_votes: bool,
+    total_voter_count: u32,
+    /// the number of voters per answer id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    answer_counts: BTreeMap<String, u32>,
+}
+
+impl PollStats {
+    pub fn count_for(&self, answer_id: &str) -> u32 {
+        self.answer_counts
+            .get(answer_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl From<&PollResults> for PollStats {
+    fn from(results: &PollResults) -> Self {
+        PollStats {
+            has_votes: !results.votes.is_empty(),
+            total_voter_count: results.voter_count(),
+            answer_counts: results.answer_counts.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PollsManager {
+    stats: PollStats,
+    event_id: OwnedEventId,
+    store: Store,
+}
+
+impl PollsManager {
+    fn stats_field_for(parent: OwnedEventId) -> ExecuteReference {
+        ExecuteReference::ModelParam(parent, ModelParam::PollStats)
+    }
+
+    pub async fn from_store_and_event_id(store: &Store, event_id: &EventId) -> PollsManager {
+        let store = store.clone();
+        let stats = store
+            .get_raw(&Self::stats_field_for(event_id.to_owned()).as_storage_key())
+            .await
+            .unwrap_or_default();
+        PollsManager {
+            store,
+            stats,
+            event_id: event_id.to_owned(),
+        }
+    }
+
+    pub fn event_id(&self) -> OwnedEventId {
+        self.event_id.clone()
+    }
+
+    /// All votes ever cast on this poll, latest first
+    pub async fn votes(&self) -> Result<Vec<PollVote>> {
+        Ok(self
+            .store
+            .get_list(&PollVote::index_for(self.event_id.clone()))
+            .await?
+            .filter_map(|mdl| match mdl {
+                AnyActerModel::PollVote(v) => Some(v),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// The votes counted by the current rules of the poll
+    pub async fn results(&self) -> Result<PollResults> {
+        let AnyActerModel::Poll(poll) = self.store.get(&self.event_id).await? else {
+            return Ok(PollResults::default());
+        };
+        Ok(PollResults::from_votes(&poll.inner, self.votes().await?))
+    }
+
+    /// Count the votes again, returns whether the poll exists
+    pub(crate) async fn recount(&mut self) -> Result<bool> {
+        if !matches!(
+            self.store.get(&self.event_id).await,
+            Ok(AnyActerModel::Poll(_))
+        ) {
+            return Ok(false);
+        }
+        self.stats = PollStats::from(&self.results().await?);
+        Ok(true)
+    }
+
+    pub fn stats(&self) -> &PollStats {
+        &self.stats
+    }
+
+    pub fn draft_builder(&self) -> PollVoteBuilder {
+        PollVoteBuilder::default()
+            .to(self.event_id.clone())
+            .to_owned()
+    }
+
+    pub fn update_key(&self) -> ExecuteReference {
+        Self::stats_field_for(self.event_id.clone())
+    }
+
+    pub async fn save(&self) -> Result<ExecuteReference> {
+        let update_key = self.update_key();
+        self.store
+            .set_raw(&update_key.as_storage_key(), &self.stats)
+            .await?;
+        Ok(update_key)
+    }
+}
+
+impl Deref for PollsManager {
+    type Target = PollStats;
+    fn deref(&self) -> &Self::Target {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AnyActerEvent;
+
+    fn poll(content: &str) -> anyhow::Result<Poll> {
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.poll",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":1672407531000,
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::Poll(poll) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a poll");
+        };
+        Ok(poll)
+    }
+
+    fn vote(idx: u64, sender: &str, answers: &[&str]) -> anyhow::Result<PollVote> {
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.poll.vote",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"{sender}",
+            "content":{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c"}},
+                "answers":{}}},
+            "origin_server_ts":{},
+            "event_id":"$vote{idx}",
+            "user_id":"{sender}"}}"#,
+            serde_json::to_string(answers)?,
+            1672407531000 + idx * 1000
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::PollVote(vote) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a vote");
+        };
+        Ok(vote)
+    }
+
+    const ANSWERS: &str = r#"[{"id":"quarks","text":"Quark’s"},
+        {"id":"replimat","text":"Replimat"},{"id":"klingon","text":"Klingon restaurant"}]"#;
+
+    #[test]
+    fn latest_valid_vote_counts() -> anyhow::Result<()> {
+        let poll = poll(&format!(
+            r#"{{"question":"Where to eat?","answers":{ANSWERS}}}"#
+        ))?;
+        let votes = vec![
+            vote(1, "@odo:ds9.acter.global", &["quarks"])?,
+            vote(2, "@kira:ds9.acter.global", &["replimat"])?,
+            vote(3, "@quark:ds9.acter.global", &["quarks"])?,
+            // changed their mind
+            vote(4, "@odo:ds9.acter.global", &["replimat"])?,
+            // single choice only, ignored
+            vote(5, "@kira:ds9.acter.global", &["quarks", "klingon"])?,
+            // unknown answer, ignored
+            vote(6, "@quark:ds9.acter.global", &["holosuite"])?,
+            vote(7, "@worf:ds9.acter.global", &["klingon"])?,
+            // retracted
+            vote(8, "@worf:ds9.acter.global", &[])?,
+        ];
+        let results = PollResults::from_votes(&poll, votes);
+        assert_eq!(results.voter_count(), 3);
+        assert_eq!(results.count_for("replimat"), 2);
+        assert_eq!(results.count_for("quarks"), 1);
+        assert_eq!(results.count_for("klingon"), 0);
+        let kira: OwnedUserId = "@kira:ds9.acter.global".try_into()?;
+        assert_eq!(results.vote_of(&kira), Some(&vec!["replimat".to_owned()]));
+
+        let stats = PollStats::from(&results);
+        assert!(stats.has_votes());
+        assert_eq!(stats.count_for("replimat"), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn closing_and_fixed_votes() -> anyhow::Result<()> {
+        // closes 5 seconds after creation
+        let poll = poll(&format!(
+            r#"{{"question":"Where to eat?","answers":{ANSWERS},"max_selections":2,
+                "allow_vote_change":false,"closes_at":"2022-12-30T13:38:56Z"}}"#
+        ))?;
+        assert!(poll.is_multiple_choice());
+        let votes = vec![
+            vote(1, "@odo:ds9.acter.global", &["quarks", "klingon"])?,
+            // no changes allowed
+            vote(2, "@odo:ds9.acter.global", &["replimat"])?,
+            vote(3, "@kira:ds9.acter.global", &["klingon"])?,
+            // too late
+            vote(5, "@quark:ds9.acter.global", &["quarks"])?,
+        ];
+        let results = PollResults::from_votes(&poll, votes);
+        assert_eq!(results.voter_count(), 2);
+        assert_eq!(results.count_for("klingon"), 2);
+        assert_eq!(results.count_for("quarks"), 1);
+        assert_eq!(results.count_for("replimat"), 0);
+        assert_eq!(results.voters_for("quarks").len(), 1);
+        Ok(())
+    }
+}
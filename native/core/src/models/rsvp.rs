@@ -6,10 +6,10 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, ops::Deref};
 use tracing::{error, trace};
 
-use super::{ActerModel, AnyActerModel, Capability, EventMeta};
+use super::{ActerModel, AnyActerModel, CalendarEventUpdate, Capability, EventMeta};
 use crate::{
-    events::rsvp::{RsvpBuilder, RsvpEventContent},
-    referencing::{ExecuteReference, IndexKey, ModelParam, ObjectListIndex},
+    events::rsvp::{RsvpBuilder, RsvpEventContent, RsvpStatus},
+    referencing::{ExecuteReference, IndexKey, ModelParam, ObjectListIndex, SpecialListsIndex},
     store::Store,
     Result,
 };
@@ -18,6 +18,100 @@ use crate::{
 pub struct RsvpStats {
     has_rsvp_entries: bool,
     total_rsvp_count: u32,
+    #[serde(default)]
+    confirmed_count: u32,
+    #[serde(default)]
+    waitlist_count: u32,
+    /// confirmed users and the guests they bring
+    #[serde(default)]
+    attendee_count: u32,
+    /// guests brought by the confirmed users
+    #[serde(default)]
+    guest_count: u32,
+    /// whether the counts above were computed, stats stored before they
+    /// existed need a recount
+    #[serde(default)]
+    #[getter(skip)]
+    participation_counted: bool,
+}
+
+/// Who is in and who is waiting for a free spot, if the event has a capacity
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters)]
+pub struct RsvpParticipation {
+    /// users attending, in order of their response
+    confirmed: Vec<OwnedUserId>,
+    /// users waiting for a spot, in order of their response
+    waitlisted: Vec<OwnedUserId>,
+    /// guests brought by the confirmed users
+    guest_count: u32,
+}
+
+impl RsvpParticipation {
+    /// Order everyone that responded with `yes` by the time since they
+    /// did so without interruption and fill up to `capacity` spots. Guests
+    /// take up spots, too, a party not fitting in goes on the waitlist while
+    /// smaller ones after it may still get in.
+    pub fn from_entries(mut entries: Vec<Rsvp>, capacity: Option<u32>) -> Self {
+        entries.sort_by(|a, b| {
+            (a.meta.origin_server_ts, &a.meta.event_id)
+                .cmp(&(b.meta.origin_server_ts, &b.meta.event_id))
+        });
+        // user -> (position of their first `yes`, latest party size)
+        let mut yes_since: HashMap<OwnedUserId, (usize, u32)> = HashMap::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            if entry.status == RsvpStatus::Yes {
+                yes_since
+                    .entry(entry.meta.sender.clone())
+                    .or_insert((idx, 0))
+                    .1 = entry.party_size();
+            } else {
+                yes_since.remove(&entry.meta.sender);
+            }
+        }
+        let mut attending = yes_since.into_iter().collect::<Vec<_>>();
+        attending.sort_by_key(|(_, (idx, _))| *idx);
+
+        let mut participation = RsvpParticipation::default();
+        let mut seats_taken = 0u32;
+        for (user_id, (_, party_size)) in attending {
+            let fits = capacity.map_or(true, |c| seats_taken.saturating_add(party_size) <= c);
+            if fits {
+                seats_taken = seats_taken.saturating_add(party_size);
+                participation.guest_count =
+                    participation.guest_count.saturating_add(party_size - 1);
+                participation.confirmed.push(user_id);
+            } else {
+                participation.waitlisted.push(user_id);
+            }
+        }
+        participation
+    }
+
+    /// confirmed users and the guests they bring
+    pub fn attendee_count(&self) -> u32 {
+        (self.confirmed.len() as u32).saturating_add(self.guest_count)
+    }
+
+    pub fn is_confirmed(&self, user_id: &UserId) -> bool {
+        self.confirmed.iter().any(|u| u == user_id)
+    }
+
+    /// 1-based position of the user on the waitlist
+    pub fn waitlist_position(&self, user_id: &UserId) -> Option<u32> {
+        self.waitlisted
+            .iter()
+            .position(|u| u == user_id)
+            .map(|idx| idx as u32 + 1)
+    }
+
+    /// users that are confirmed now but were waiting before
+    pub fn promoted_since(&self, before: &RsvpParticipation) -> Vec<OwnedUserId> {
+        self.confirmed
+            .iter()
+            .filter(|u| before.waitlisted.contains(u))
+            .cloned()
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -38,11 +132,19 @@ impl RsvpManager {
             .get_raw(&Self::stats_field_for(event_id.to_owned()).as_storage_key())
             .await
             .unwrap_or_default();
-        RsvpManager {
+        let mut manager = RsvpManager {
             store,
             stats,
             event_id: event_id.to_owned(),
+        };
+        if manager.stats.has_rsvp_entries && !manager.stats.participation_counted {
+            // stored before we kept track of who is in, recount
+            match manager.participation().await {
+                Ok(participation) => manager.set_participation(&participation),
+                Err(error) => error!(?error, ?event_id, "recounting rsvp participation failed"),
+            }
         }
+        manager
     }
 
     pub fn event_id(&self) -> OwnedEventId {
@@ -64,12 +166,138 @@ impl RsvpManager {
         Ok(entries)
     }
 
+    /// Everyone planning to come, i.e. whose latest response is `yes` or
+    /// `maybe`, but `except` themselves
+    pub async fn attending_users(&self, except: &UserId) -> Result<Vec<OwnedUserId>> {
+        let mut users = self
+            .rsvp_entries()
+            .await?
+            .into_iter()
+            .filter(|(user_id, rsvp)| {
+                user_id != except && matches!(rsvp.status, RsvpStatus::Yes | RsvpStatus::Maybe)
+            })
+            .map(|(user_id, _)| user_id)
+            .collect::<Vec<_>>();
+        users.sort();
+        Ok(users)
+    }
+
+    async fn all_entries(&self) -> Result<Vec<Rsvp>> {
+        Self::entries_of(&self.store, &self.event_id).await
+    }
+
+    async fn entries_of(store: &Store, event_id: &EventId) -> Result<Vec<Rsvp>> {
+        Ok(store
+            .get_list(&Rsvp::index_for(event_id.to_owned()))
+            .await?
+            .filter_map(|mdl| match mdl {
+                AnyActerModel::Rsvp(c) => Some(c),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// the entries that came in before the given event
+    async fn entries_before(
+        store: &Store,
+        event_id: &EventId,
+        meta: &EventMeta,
+    ) -> Result<Vec<Rsvp>> {
+        let mut entries = Self::entries_of(store, event_id).await?;
+        entries.retain(|e| {
+            (e.meta.origin_server_ts, &e.meta.event_id) < (meta.origin_server_ts, &meta.event_id)
+        });
+        Ok(entries)
+    }
+
+    async fn capacity_of(store: &Store, event_id: &EventId) -> Result<Option<u32>> {
+        Ok(match store.get(event_id).await? {
+            AnyActerModel::CalendarEvent(event) => event.capacity,
+            _ => None,
+        })
+    }
+
+    /// The participation for the current capacity of the event
+    pub async fn participation(&self) -> Result<RsvpParticipation> {
+        Ok(RsvpParticipation::from_entries(
+            self.all_entries().await?,
+            Self::capacity_of(&self.store, &self.event_id).await?,
+        ))
+    }
+
     pub(crate) fn add_rsvp_entry(&mut self, _entry: &Rsvp) -> Result<bool> {
         self.stats.has_rsvp_entries = true;
         self.stats.total_rsvp_count += 1;
         Ok(true)
     }
 
+    pub(crate) fn set_participation(&mut self, participation: &RsvpParticipation) {
+        self.stats.confirmed_count = participation.confirmed.len() as u32;
+        self.stats.waitlist_count = participation.waitlisted.len() as u32;
+        self.stats.attendee_count = participation.attendee_count();
+        self.stats.guest_count = participation.guest_count;
+        self.stats.participation_counted = true;
+    }
+
+    fn previous_capacity_key(update_id: OwnedEventId) -> ExecuteReference {
+        ExecuteReference::ModelParam(update_id, ModelParam::PreviousCapacity)
+    }
+
+    /// Keep the capacity the event had before the given update changes it
+    pub(crate) async fn remember_capacity(
+        store: &Store,
+        update: &CalendarEventUpdate,
+    ) -> Result<()> {
+        let key = Self::previous_capacity_key(update.event_id().to_owned()).as_storage_key();
+        if store.get_raw::<Option<u32>>(&key).await.is_ok() {
+            // applied before, the event already has the new capacity
+            return Ok(());
+        }
+        let capacity = Self::capacity_of(store, &update.calendar_event.event_id).await?;
+        store.set_raw(&key, &capacity).await
+    }
+
+    /// The users that got a spot off the waitlist through the given RSVP.
+    ///
+    /// Derived from the entries that came in before it, so it is the same
+    /// whether or not the RSVP has been applied yet.
+    pub async fn waitlist_promotions(store: &Store, rsvp: &Rsvp) -> Result<Vec<OwnedUserId>> {
+        let event_id = &rsvp.inner.to.event_id;
+        let capacity = Self::capacity_of(store, event_id).await?;
+        if capacity.is_none() {
+            return Ok(vec![]);
+        }
+        let mut entries = Self::entries_before(store, event_id, &rsvp.meta).await?;
+        let before = RsvpParticipation::from_entries(entries.clone(), capacity);
+        entries.push(rsvp.clone());
+        let after = RsvpParticipation::from_entries(entries, capacity);
+        Ok(after.promoted_since(&before))
+    }
+
+    /// The users that got a spot off the waitlist through the given change
+    /// of the capacity
+    pub async fn capacity_promotions(
+        store: &Store,
+        update: &CalendarEventUpdate,
+    ) -> Result<Vec<OwnedUserId>> {
+        let Some(new_capacity) = update.capacity else {
+            return Ok(vec![]);
+        };
+        let event_id = &update.calendar_event.event_id;
+        let previous_capacity = match store
+            .get_raw(&Self::previous_capacity_key(update.event_id().to_owned()).as_storage_key())
+            .await
+        {
+            Ok(capacity) => capacity,
+            // not applied yet, the event still has the old one
+            Err(_) => Self::capacity_of(store, event_id).await?,
+        };
+        let entries = Self::entries_before(store, event_id, update.event_meta()).await?;
+        let before = RsvpParticipation::from_entries(entries.clone(), previous_capacity);
+        let after = RsvpParticipation::from_entries(entries, new_capacity);
+        Ok(after.promoted_since(&before))
+    }
+
     pub fn stats(&self) -> &RsvpStats {
         &self.stats
     }
@@ -118,13 +346,17 @@ impl Rsvp {
 }
 
 impl ActerModel for Rsvp {
-    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
-        vec![
+    fn indizes(&self, user_id: &UserId) -> Vec<IndexKey> {
+        let mut indizes = vec![
             Rsvp::index_for(self.inner.to.event_id.clone()),
             IndexKey::ObjectHistory(self.inner.to.event_id.clone()),
             IndexKey::RoomHistory(self.meta.room_id.clone()),
             IndexKey::AllHistory,
-        ]
+        ];
+        if self.meta.sender == user_id {
+            indizes.push(IndexKey::Special(SpecialListsIndex::MyRsvps));
+        }
+        indizes
     }
 
     fn event_meta(&self) -> &EventMeta {
@@ -159,7 +391,9 @@ impl ActerModel for Rsvp {
 
         let mut updates = store.save(self.clone().into()).await?;
         trace!(event_id=?self.event_id(), "saved rsvp entry");
-        if let Some(manager) = manager {
+        if let Some(mut manager) = manager {
+            let participation = manager.participation().await?;
+            manager.set_participation(&participation);
             updates.push(manager.save().await?);
         }
         Ok(updates)
@@ -193,3 +427,150 @@ impl From<OriginalMessageLikeEvent<RsvpEventContent>> for Rsvp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{rsvp::MAX_GUESTS, AnyActerEvent},
+        models::fixtures,
+    };
+
+    fn rsvp(idx: u64, sender: &str, status: &str) -> anyhow::Result<Rsvp> {
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.rsvp",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"{sender}",
+            "content":{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c"}},
+                "status":{{"type":"{status}"}}}},
+            "origin_server_ts":{},
+            "event_id":"$rsvp{idx}",
+            "user_id":"{sender}"}}"#,
+            1672407531453 + idx
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::Rsvp(rsvp) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not an rsvp");
+        };
+        Ok(rsvp)
+    }
+
+    #[test]
+    fn waitlist_follows_response_order() -> anyhow::Result<()> {
+        let odo: OwnedUserId = "@odo:ds9.acter.global".try_into()?;
+        let kira: OwnedUserId = "@kira:ds9.acter.global".try_into()?;
+        let quark: OwnedUserId = "@quark:ds9.acter.global".try_into()?;
+        let mut entries = vec![
+            rsvp(1, "@odo:ds9.acter.global", "yes")?,
+            rsvp(2, "@kira:ds9.acter.global", "yes")?,
+            rsvp(3, "@quark:ds9.acter.global", "yes")?,
+            // saying yes again doesn’t lose the spot
+            rsvp(4, "@odo:ds9.acter.global", "yes")?,
+        ];
+
+        let before = RsvpParticipation::from_entries(entries.clone(), Some(2));
+        assert_eq!(before.confirmed(), &[odo.clone(), kira.clone()]);
+        assert_eq!(before.waitlist_position(&quark), Some(1));
+        assert_eq!(before.waitlist_position(&odo), None);
+
+        entries.push(rsvp(5, "@odo:ds9.acter.global", "no")?);
+        let after = RsvpParticipation::from_entries(entries.clone(), Some(2));
+        assert_eq!(after.confirmed(), &[kira.clone(), quark.clone()]);
+        assert!(after.waitlisted().is_empty());
+        assert_eq!(after.promoted_since(&before), [quark.clone()]);
+
+        // coming back puts odo at the end of the line
+        entries.push(rsvp(6, "@odo:ds9.acter.global", "yes")?);
+        let last = RsvpParticipation::from_entries(entries, Some(2));
+        assert_eq!(last.waitlist_position(&odo), Some(1));
+
+        let unlimited = RsvpParticipation::from_entries(vec![], None);
+        assert!(unlimited.confirmed().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn guests_take_up_spots() -> anyhow::Result<()> {
+        let kira: OwnedUserId = "@kira:ds9.acter.global".try_into()?;
+        let quark: OwnedUserId = "@quark:ds9.acter.global".try_into()?;
+        let mut with_guests = rsvp(2, "@kira:ds9.acter.global", "yes")?;
+        with_guests.inner.guests = Some(2);
+        with_guests.inner.note = Some("bringing snacks".to_owned());
+        let entries = vec![
+            rsvp(1, "@odo:ds9.acter.global", "yes")?,
+            with_guests,
+            rsvp(3, "@quark:ds9.acter.global", "yes")?,
+        ];
+
+        let unlimited = RsvpParticipation::from_entries(entries.clone(), None);
+        assert_eq!(unlimited.attendee_count(), 5);
+        assert_eq!(*unlimited.guest_count(), 2);
+
+        let limited = RsvpParticipation::from_entries(entries.clone(), Some(4));
+        assert_eq!(limited.attendee_count(), 4);
+        assert!(limited.is_confirmed(&kira));
+        assert_eq!(limited.waitlist_position(&quark), Some(1));
+
+        // a party too large for the spots left doesn’t hold up the others
+        let tight = RsvpParticipation::from_entries(entries, Some(3));
+        assert_eq!(tight.attendee_count(), 2);
+        assert!(tight.is_confirmed(&quark));
+        assert_eq!(tight.waitlist_position(&kira), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn huge_parties_are_capped() -> anyhow::Result<()> {
+        let mut entries = vec![];
+        for (idx, sender) in ["@odo:ds9.acter.global", "@kira:ds9.acter.global"]
+            .into_iter()
+            .enumerate()
+        {
+            let mut entry = rsvp(idx as u64, sender, "yes")?;
+            entry.inner.guests = Some(u32::MAX);
+            entries.push(entry);
+        }
+        let participation = RsvpParticipation::from_entries(entries, None);
+        assert_eq!(*participation.guest_count(), 2 * MAX_GUESTS);
+        assert_eq!(participation.attendee_count(), 2 * (MAX_GUESTS + 1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stats_from_before_the_capacity_get_recounted() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        let json_raw = r#"{"type":"global.acter.dev.calendar_event",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{"title":"Dabo night","capacity":1,
+                "utc_start":"2024-03-06T20:00:00Z","utc_end":"2024-03-06T23:00:00Z"},
+            "origin_server_ts":1672407531453,
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global"}"#;
+        let event = serde_json::from_str::<AnyActerEvent>(json_raw)?;
+        executor.handle(AnyActerModel::try_from(event)?).await?;
+        for (idx, sender) in ["@odo:ds9.acter.global", "@kira:ds9.acter.global"]
+            .into_iter()
+            .enumerate()
+        {
+            executor
+                .handle(rsvp(idx as u64, sender, "yes")?.into())
+                .await?;
+        }
+        let event_id = OwnedEventId::try_from("$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c")?;
+        // what we stored before counting who is in
+        executor
+            .store()
+            .set_raw(
+                &RsvpManager::stats_field_for(event_id.clone()).as_storage_key(),
+                &serde_json::json!({"has_rsvp_entries": true, "total_rsvp_count": 2}),
+            )
+            .await?;
+
+        let manager = RsvpManager::from_store_and_event_id(executor.store(), &event_id).await;
+        assert_eq!(*manager.total_rsvp_count(), 2);
+        assert_eq!(*manager.confirmed_count(), 1);
+        assert_eq!(*manager.waitlist_count(), 1);
+        assert_eq!(*manager.attendee_count(), 1);
+        Ok(())
+    }
+}
@@ -1,5 +1,16 @@
+mod due;
+mod ical;
 mod task;
 mod task_list;
+mod time_tracking;
 
-pub use task::{Task, TaskSelfAssign, TaskSelfUnassign, TaskUpdate};
+pub use due::{next_due_lists_change, TaskDueWatcher};
+pub use ical::{
+    task_draft_from_ical_todo, task_drafts_from_ical, task_list_ical_document, IcalTaskDraft,
+};
+pub use task::{
+    can_assign, Task, TaskAssign, TaskSelfAssign, TaskSelfUnassign, TaskUnassign, TaskUpdate,
+    ASSIGN_OTHERS_POWER_LEVEL,
+};
 pub use task_list::{TaskList, TaskListUpdate, TaskStats};
+pub use time_tracking::{TaskTimeLog, TimeTrackingManager, TimeTrackingStats};
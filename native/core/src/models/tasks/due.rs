@@ -0,0 +1,322 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, TimeZone, Utc};
+use matrix_sdk::ruma::OwnedEventId;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::{trace, warn};
+
+use super::Task;
+use crate::{
+    events::UtcDateTime,
+    executor::Executor,
+    models::{ActerModel, AnyActerModel},
+    referencing::{ExecuteReference, IndexKey, SpecialListsIndex},
+    Result,
+};
+
+/// The special lists computed from the due date of my open tasks
+const DUE_LISTS: [SpecialListsIndex; 3] = [
+    SpecialListsIndex::MyOverdueTasks,
+    SpecialListsIndex::MyTasksDueToday,
+    SpecialListsIndex::MyTasksDueThisWeek,
+];
+
+impl Task {
+    /// The moment this task is due, if it is due at a specific time of day
+    pub fn utc_due(&self) -> Option<UtcDateTime> {
+        let due_date = self.inner.due_date?;
+        let seconds = self.inner.utc_due_time_of_day?;
+        let midnight = due_date.and_time(NaiveTime::MIN).and_utc();
+        Some(midnight + chrono::Duration::seconds(seconds.into()))
+    }
+
+    /// The due lists this task is on at `now`, in the time zone of `now`.
+    /// Tasks without time are overdue from the day after their due date.
+    pub fn due_lists_at<T: TimeZone>(&self, now: &DateTime<T>) -> Vec<SpecialListsIndex> {
+        if self.is_done() {
+            return vec![];
+        }
+        let Some(due_date) = self.inner.due_date else {
+            return vec![];
+        };
+        let today = now.date_naive();
+        let (overdue, local_due_date) = match self.utc_due() {
+            Some(due_at) => (
+                due_at < now.to_utc(),
+                due_at.with_timezone(&now.timezone()).date_naive(),
+            ),
+            None => (due_date < today, due_date),
+        };
+        if overdue {
+            return vec![SpecialListsIndex::MyOverdueTasks];
+        }
+
+        let mut lists = vec![];
+        if local_due_date == today {
+            lists.push(SpecialListsIndex::MyTasksDueToday);
+        }
+        let days_left = 6 - today.weekday().num_days_from_monday();
+        if today
+            .checked_add_days(Days::new(days_left.into()))
+            .is_some_and(|end_of_week| local_due_date <= end_of_week)
+        {
+            lists.push(SpecialListsIndex::MyTasksDueThisWeek);
+        }
+        lists
+    }
+}
+
+/// When the due lists of `tasks` change next after `now` without any of the
+/// tasks changing: at the next timed due date or the next local midnight
+pub fn next_due_lists_change<'a, T: TimeZone>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    now: &DateTime<T>,
+) -> Option<UtcDateTime> {
+    let tz = now.timezone();
+    let next_midnight = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|day| {
+            tz.from_local_datetime(&day.and_time(NaiveTime::MIN))
+                .earliest()
+        })
+        .map(|d| d.to_utc());
+    let utc_now = now.to_utc();
+    tasks
+        .into_iter()
+        .filter(|t| !t.is_done())
+        .filter_map(Task::utc_due)
+        .filter(|due_at| *due_at > utc_now)
+        .chain(next_midnight)
+        .min()
+}
+
+/// Keeps the overdue and due-soon lists of the user current.
+///
+/// As their content depends on the time, too, they aren’t kept in the store
+/// but computed from the open tasks of the user. The watcher checks them
+/// whenever those change and as time passes, and notifies the subscribers of
+/// the lists that changed.
+#[derive(Clone, Debug)]
+pub struct TaskDueWatcher {
+    executor: Executor,
+    state: Arc<Mutex<BTreeMap<SpecialListsIndex, Vec<OwnedEventId>>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl TaskDueWatcher {
+    pub fn new(executor: Executor) -> Self {
+        TaskDueWatcher {
+            executor,
+            state: Default::default(),
+            running: Default::default(),
+        }
+    }
+
+    pub fn key(list: SpecialListsIndex) -> ExecuteReference {
+        ExecuteReference::Index(IndexKey::Special(list))
+    }
+
+    pub fn subscribe(&self, list: SpecialListsIndex) -> Receiver<()> {
+        self.executor.subscribe(Self::key(list))
+    }
+
+    async fn my_open_tasks(&self) -> Result<Vec<Task>> {
+        Ok(self
+            .executor
+            .store()
+            .get_list(&IndexKey::Special(SpecialListsIndex::MyOpenTasks))
+            .await?
+            .filter_map(|mdl| match mdl {
+                AnyActerModel::Task(task) => Some(task),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// My open tasks on the given due list at `now`, the ones due first, first
+    pub async fn tasks_at<T: TimeZone>(
+        &self,
+        list: SpecialListsIndex,
+        now: &DateTime<T>,
+    ) -> Result<Vec<Task>> {
+        let mut tasks = self
+            .my_open_tasks()
+            .await?
+            .into_iter()
+            .filter(|t| t.due_lists_at(now).contains(&list))
+            .collect::<Vec<_>>();
+        tasks.sort_by_key(|t| (t.due_date, t.utc_due_time_of_day));
+        Ok(tasks)
+    }
+
+    /// Recompute the due lists and notify the subscribers of the lists that
+    /// changed since the last check. Returns the number of changed lists.
+    pub async fn check<T: TimeZone>(&self, now: &DateTime<T>) -> Result<usize> {
+        let mut lists: BTreeMap<SpecialListsIndex, Vec<OwnedEventId>> = BTreeMap::new();
+        for task in self.my_open_tasks().await? {
+            for list in task.due_lists_at(now) {
+                lists
+                    .entry(list)
+                    .or_default()
+                    .push(task.event_id().to_owned());
+            }
+        }
+        let changed = {
+            let mut state = self.state.lock()?;
+            let changed = DUE_LISTS
+                .into_iter()
+                .filter(|list| state.get(list) != lists.get(list))
+                .map(Self::key)
+                .collect::<Vec<_>>();
+            *state = lists;
+            changed
+        };
+        let count = changed.len();
+        trace!(count, "checked task due lists");
+        if count > 0 {
+            self.executor.notify(changed);
+        }
+        Ok(count)
+    }
+
+    /// When the due lists change next as time passes, if ever
+    pub async fn next_change<T: TimeZone>(&self, now: &DateTime<T>) -> Result<Option<UtcDateTime>> {
+        Ok(next_due_lists_change(&self.my_open_tasks().await?, now))
+    }
+
+    /// Keep checking the due lists in the local time zone whenever my open
+    /// tasks change or the next change is due. Only one loop runs at a time,
+    /// further calls return right away.
+    pub async fn run(&self) -> Result<()> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let mut open_tasks = self
+            .executor
+            .subscribe(IndexKey::Special(SpecialListsIndex::MyOpenTasks));
+        let result = loop {
+            let now = Local::now();
+            if let Err(error) = self.check(&now).await {
+                warn!(?error, "Checking task due lists failed");
+            }
+            let wait = match self.next_change(&now).await {
+                Ok(Some(next)) => (next - Utc::now()).to_std().unwrap_or_default(),
+                Ok(None) => std::time::Duration::from_secs(3600),
+                Err(error) => break Err(error),
+            };
+            tokio::select! {
+                received = open_tasks.recv() => {
+                    if matches!(received, Err(RecvError::Closed)) {
+                        break Ok(());
+                    }
+                }
+                _ = tokio::time::sleep(wait) => {}
+            }
+        };
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Tz;
+
+    use super::*;
+    use crate::events::AnyActerEvent;
+
+    fn task(due: &str) -> anyhow::Result<Task> {
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.task",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{{"title":"Fix the replicator",{due}
+                "m.relates_to":{{"event_id":"$tasklist:ds9.acter.global"}}}},
+            "origin_server_ts":1672407531453,
+            "unsigned":{{"age":11523850}},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::Task(task) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a task");
+        };
+        Ok(task)
+    }
+
+    fn at(local: &str) -> anyhow::Result<DateTime<Tz>> {
+        let tz: Tz = "Europe/Berlin".parse()?;
+        let naive = chrono::NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M")?;
+        tz.from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| anyhow::anyhow!("invalid local time"))
+    }
+
+    #[test]
+    fn date_only_tasks_are_due_all_day() -> anyhow::Result<()> {
+        // a Wednesday
+        let task = task(r#""due_date":"2024-03-06","#)?;
+        assert_eq!(
+            task.due_lists_at(&at("2024-03-04 09:00")?),
+            [SpecialListsIndex::MyTasksDueThisWeek]
+        );
+        assert_eq!(
+            task.due_lists_at(&at("2024-03-06 23:59")?),
+            [
+                SpecialListsIndex::MyTasksDueToday,
+                SpecialListsIndex::MyTasksDueThisWeek
+            ]
+        );
+        assert_eq!(
+            task.due_lists_at(&at("2024-03-07 00:00")?),
+            [SpecialListsIndex::MyOverdueTasks]
+        );
+        // the week before
+        assert!(task.due_lists_at(&at("2024-03-03 12:00")?).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn timed_tasks_are_overdue_right_away() -> anyhow::Result<()> {
+        // 15:00 UTC is 16:00 in Berlin
+        let task = task(r#""due_date":"2024-03-06","utc_due_time_of_day":54000,"#)?;
+        assert_eq!(
+            task.due_lists_at(&at("2024-03-06 15:59")?),
+            [
+                SpecialListsIndex::MyTasksDueToday,
+                SpecialListsIndex::MyTasksDueThisWeek
+            ]
+        );
+        assert_eq!(
+            task.due_lists_at(&at("2024-03-06 16:00")?),
+            [SpecialListsIndex::MyOverdueTasks]
+        );
+
+        let now = at("2024-03-06 10:00")?;
+        assert_eq!(
+            next_due_lists_change([&task], &now),
+            Some("2024-03-06T15:00:00Z".parse()?)
+        );
+        let now = at("2024-03-06 17:00")?;
+        // the next local midnight
+        assert_eq!(
+            next_due_lists_change([&task], &now),
+            Some("2024-03-06T23:00:00Z".parse()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn done_and_undated_tasks_arent_due() -> anyhow::Result<()> {
+        let now = at("2024-03-06 10:00")?;
+        assert!(task("")?.due_lists_at(&now).is_empty());
+        let done = task(r#""due_date":"2024-03-01","progress_percent":100,"#)?;
+        assert!(done.due_lists_at(&now).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use chrono::{Duration, Timelike};
+use icalendar::{
+    Calendar as iCalendar, CalendarComponent, Component, DatePerhapsTime, EventLike, Property,
+    Todo, TodoStatus,
+};
+use matrix_sdk_base::ruma::{events::room::message::TextMessageEventContent, OwnedUserId};
+use tracing::warn;
+
+use super::{
+    super::calendar::{
+        escape_text, ical_cal_address, parse_error, start_of_day, text_property, to_utc,
+        unescape_text, user_id_from_cal_address,
+    },
+    Task, TaskList,
+};
+use crate::{
+    events::tasks::{Priority, TaskBuilder},
+    Error, Result,
+};
+
+/// A task read from an iCal `VTODO`. Assignees can’t be part of the task
+/// itself, they have to be assigned once the task was created.
+#[derive(Clone, Debug)]
+pub struct IcalTaskDraft {
+    pub draft: TaskBuilder,
+    pub assignees: Vec<OwnedUserId>,
+}
+
+/// `PRIORITY` uses the same 1 (highest) to 9 (lowest) scale, 0 is undefined
+fn ical_priority(priority: &Priority) -> Option<u32> {
+    match priority {
+        Priority::Undefined => None,
+        other => Some(other.clone() as u32),
+    }
+}
+
+fn priority_from_ical(value: u32) -> Priority {
+    match value {
+        1 => Priority::Highest,
+        2 => Priority::SecondHighest,
+        3 => Priority::Three,
+        4 => Priority::Four,
+        5 => Priority::Five,
+        6 => Priority::Six,
+        7 => Priority::Seven,
+        8 => Priority::SecondLowest,
+        9 => Priority::Lowest,
+        _ => Priority::Undefined,
+    }
+}
+
+/// split a comma separated TEXT list like `CATEGORIES`, keeping escaped commas
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ',' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items
+        .iter()
+        .map(|item| unescape_text(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn ical_assignee(user_id: &OwnedUserId) -> Property {
+    Property::new("ATTENDEE", ical_cal_address(user_id))
+        .add_parameter("CUTYPE", "INDIVIDUAL")
+        .done()
+}
+
+/// all `ATTENDEE`s with a `matrix:` address
+fn assignees_of(todo: &Todo) -> Vec<OwnedUserId> {
+    let single = todo.properties().get("ATTENDEE");
+    let multi = todo
+        .multi_properties()
+        .get("ATTENDEE")
+        .into_iter()
+        .flatten();
+    let mut assignees: Vec<OwnedUserId> = vec![];
+    for property in single.into_iter().chain(multi) {
+        match user_id_from_cal_address(property.value()) {
+            Some(user_id) if !assignees.contains(&user_id) => assignees.push(user_id),
+            Some(_) => {}
+            None => warn!(
+                attendee = property.value(),
+                "Skipping non-matrix attendee in iCal import"
+            ),
+        }
+    }
+    assignees
+}
+
+impl Task {
+    /// iCal `VTODO` of this task, with its assignees as `ATTENDEE`s
+    pub fn as_ical_todo(&self) -> Todo {
+        let mut todo = Todo::new();
+        todo.uid(self.meta.event_id.as_str())
+            .summary(&self.inner.title)
+            .class(icalendar::Class::Private)
+            .add_property("ORGANIZER", ical_cal_address(&self.meta.sender));
+
+        if let Some(due_date) = self.inner.due_date {
+            let due_at = self.inner.utc_due_time_of_day.and_then(|secs| {
+                Some(start_of_day(due_date).ok()? + Duration::seconds(secs.into()))
+            });
+            match due_at {
+                Some(due_at) => todo.due(due_at),
+                None => todo.due(due_date),
+            };
+        }
+        if let Some(start) = self.inner.utc_start {
+            todo.starts(start);
+        }
+        if let Some(priority) = ical_priority(&self.inner.priority) {
+            todo.priority(priority);
+        }
+
+        let percent = self.inner.progress_percent.unwrap_or_default().min(100);
+        if percent > 0 {
+            todo.percent_complete(percent);
+        }
+        todo.status(if self.is_done() {
+            TodoStatus::Completed
+        } else if percent > 0 {
+            TodoStatus::InProcess
+        } else {
+            TodoStatus::NeedsAction
+        });
+
+        if !self.inner.categories.is_empty() {
+            let categories: Vec<String> = self
+                .inner
+                .categories
+                .iter()
+                .map(|c| escape_text(c))
+                .collect();
+            todo.add_property("CATEGORIES", categories.join(","));
+        }
+        if let Some(parent) = &self.inner.parent_task {
+            todo.add_property("RELATED-TO", parent.as_str());
+        }
+        for assignee in &self.assignees() {
+            todo.append_multi_property(ical_assignee(assignee));
+        }
+
+        if let Some(msg) = &self.inner.description {
+            // DESCRIPTION is plain text, the formatted body is HTML
+            todo.description(&msg.body);
+        }
+        todo.done()
+    }
+}
+
+/// Render the tasks of a tasklist as one iCalendar document of `VTODO`s,
+/// named after the list
+pub fn task_list_ical_document<'a>(
+    task_list: &TaskList,
+    tasks: impl IntoIterator<Item = &'a Task>,
+) -> String {
+    let mut calendar = iCalendar::new();
+    calendar.name(&task_list.name);
+    if let Some(description) = &task_list.description {
+        calendar.description(&description.body);
+    }
+    for task in tasks {
+        calendar.push(task.as_ical_todo());
+    }
+    calendar.done().to_string()
+}
+
+/// Build a task draft from a single iCal `VTODO`. The tasklist it goes
+/// into isn’t set yet.
+pub fn task_draft_from_ical_todo(todo: &Todo) -> Result<IcalTaskDraft> {
+    let title =
+        text_property(todo, "SUMMARY").ok_or_else(|| Error::MissingField("SUMMARY".to_owned()))?;
+
+    let mut draft = TaskBuilder::default();
+    draft.title(title);
+
+    if let Some(body) = text_property(todo, "DESCRIPTION") {
+        draft.description(Some(TextMessageEventContent::plain(body)));
+    }
+
+    match todo.get_due() {
+        Some(DatePerhapsTime::Date(date)) => {
+            draft.due_date(Some(date));
+        }
+        Some(due) => {
+            let (utc_due, _, _) = to_utc(due)?;
+            draft
+                .due_date(Some(utc_due.date_naive()))
+                .utc_due_time_of_day(Some(utc_due.num_seconds_from_midnight() as i32));
+        }
+        None => {}
+    }
+    if let Some(start) = todo.get_start() {
+        draft.utc_start(Some(to_utc(start)?.0));
+    }
+
+    if let Some(priority) = todo.get_priority() {
+        draft.priority(priority_from_ical(priority));
+    }
+
+    let percent = match todo.get_status() {
+        Some(TodoStatus::Completed) => Some(100),
+        _ => todo.get_percent_complete().map(|p| p.min(100)),
+    };
+    draft.progress_percent(percent.filter(|p| *p > 0));
+
+    if let Some(categories) = todo.property_value("CATEGORIES") {
+        draft.categories(split_text_list(categories));
+    }
+
+    Ok(IcalTaskDraft {
+        draft,
+        assignees: assignees_of(todo),
+    })
+}
+
+/// Parse an iCalendar document into one task draft per `VTODO`. Todos we
+/// can’t read are skipped, so one broken entry doesn’t prevent importing the
+/// rest.
+pub fn task_drafts_from_ical(ics: &str) -> Result<Vec<IcalTaskDraft>> {
+    let calendar = ics
+        .parse::<iCalendar>()
+        .map_err(|e| parse_error(e.to_string()))?;
+    Ok(calendar
+        .components
+        .iter()
+        .filter_map(|component| match component {
+            CalendarComponent::Todo(todo) => match task_draft_from_ical_todo(todo) {
+                Ok(draft) => Some(draft),
+                Err(error) => {
+                    warn!(uid = ?todo.get_uid(), ?error, "Skipping VTODO in iCal import");
+                    None
+                }
+            },
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix_sdk_base::ruma::OwnedEventId;
+
+    use super::*;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Other Tool//EN\r
+BEGIN:VTODO\r
+UID:1@example.org\r
+DTSTAMP:20240301T120000Z\r
+SUMMARY:Order supplies\r
+DESCRIPTION:Paper\\, pens\\nand coffee\r
+DUE:20240305T170000Z\r
+PRIORITY:1\r
+PERCENT-COMPLETE:40\r
+STATUS:IN-PROCESS\r
+CATEGORIES:office,errands\r
+ATTENDEE;CUTYPE=INDIVIDUAL:matrix:u/kira:ds9.acter.global\r
+ATTENDEE:mailto:odo@example.org\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:2@example.org\r
+DTSTAMP:20240301T120000Z\r
+SUMMARY:File report\r
+DUE;VALUE=DATE:20240621\r
+STATUS:COMPLETED\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn imports_todos() -> anyhow::Result<()> {
+        let list_id = OwnedEventId::try_from("$list:ds9.acter.global")?;
+        let drafts = task_drafts_from_ical(ICS)?;
+        assert_eq!(drafts.len(), 2);
+
+        let supplies = drafts[0]
+            .draft
+            .clone()
+            .task_list_id(list_id.clone())
+            .build()?;
+        assert_eq!(supplies.title, "Order supplies");
+        assert_eq!(
+            supplies.description.map(|d| d.body).as_deref(),
+            Some("Paper, pens\nand coffee")
+        );
+        assert_eq!(
+            supplies.due_date.map(|d| d.to_string()).as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(supplies.utc_due_time_of_day, Some(17 * 3600));
+        assert_eq!(supplies.priority, Priority::Highest);
+        assert_eq!(supplies.progress_percent, Some(40));
+        assert_eq!(supplies.categories, vec!["office", "errands"]);
+        assert_eq!(
+            drafts[0].assignees,
+            vec![OwnedUserId::try_from("@kira:ds9.acter.global")?]
+        );
+
+        let report = drafts[1]
+            .draft
+            .clone()
+            .task_list_id(list_id.clone())
+            .build()?;
+        assert_eq!(
+            report.due_date.map(|d| d.to_string()).as_deref(),
+            Some("2024-06-21")
+        );
+        assert_eq!(report.utc_due_time_of_day, None);
+        assert_eq!(report.progress_percent, Some(100));
+        assert!(drafts[1].assignees.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn todo_without_summary_is_skipped() -> anyhow::Result<()> {
+        let ics = ICS.replace("SUMMARY:Order supplies\r\n", "");
+        let drafts = task_drafts_from_ical(&ics)?;
+        assert_eq!(drafts.len(), 1);
+        let list_id = OwnedEventId::try_from("$list:ds9.acter.global")?;
+        let report = drafts[0].draft.clone().task_list_id(list_id).build()?;
+        assert_eq!(report.title, "File report");
+        Ok(())
+    }
+
+    #[test]
+    fn categories_are_escaped_for_export() {
+        let escaped: Vec<String> = ["a, b", "c;d"].iter().map(|c| escape_text(c)).collect();
+        let value = escaped.join(",");
+        assert_eq!(value, "a\\, b,c\\;d");
+        assert_eq!(
+            split_text_list(&value),
+            vec!["a, b".to_owned(), "c;d".to_owned()]
+        );
+    }
+
+    #[test]
+    fn category_lists_keep_escaped_commas() {
+        assert_eq!(
+            split_text_list("a\\, b,c, ,d"),
+            vec!["a, b".to_owned(), "c".to_owned(), "d".to_owned()]
+        );
+    }
+}
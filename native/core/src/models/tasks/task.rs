@@ -1,18 +1,38 @@
-use matrix_sdk::ruma::OwnedEventId;
-use matrix_sdk_base::ruma::{events::OriginalMessageLikeEvent, OwnedUserId, RoomId, UserId};
+use chrono::NaiveTime;
+use matrix_sdk::{
+    ruma::{EventId, OwnedEventId},
+    Room,
+};
+use matrix_sdk_base::ruma::{
+    events::{
+        room::power_levels::RoomPowerLevels, OriginalMessageLikeEvent, StaticEventContent,
+        TimelineEventType,
+    },
+    Int, OwnedUserId, RoomId, UserId,
+};
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
+use std::{collections::HashSet, ops::Deref};
+use tracing::{trace, warn};
 
-use super::super::{
-    default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, Store,
+use super::{
+    super::{
+        default_model_execute, transition_tree, ActerModel, AnyActerModel, Capability, EventMeta,
+        RedactedActerModel, Store,
+    },
+    TaskStats, TimeTrackingManager,
 };
 use crate::{
-    events::tasks::{
-        TaskEventContent, TaskSelfAssignEventContent, TaskSelfUnassignEventContent,
-        TaskUpdateBuilder, TaskUpdateEventContent,
+    events::{
+        tasks::{
+            ChecklistItem, ChecklistOperation, TaskAssignEventContent, TaskBuilder,
+            TaskEventContent, TaskSelfAssignEventContent, TaskSelfUnassignEventContent,
+            TaskUnassignEventContent, TaskUpdateBuilder, TaskUpdateEventContent,
+        },
+        Date, Recurrence,
     },
     models::InvitationsManager,
     referencing::{ExecuteReference, IndexKey, ObjectListIndex, SpecialListsIndex},
+    util::{is_false, serialized_differ},
     Result,
 };
 
@@ -23,6 +43,34 @@ pub struct Task {
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     assignees: Vec<OwnedUserId>,
+
+    #[serde(default)]
+    subtask_stats: TaskStats,
+
+    /// the tasks blocked by this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blocks: Vec<OwnedEventId>,
+
+    /// the tasks we are blocked by that aren’t done yet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    open_blockers: Vec<OwnedEventId>,
+
+    /// the next instance of this recurring task, once created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    superseded_by: Option<OwnedEventId>,
+
+    /// the instance following the same previous one that came first, if this
+    /// one was created concurrently to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duplicate_of: Option<OwnedEventId>,
+
+    /// the tasklist this task was in before it was moved to the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trashed_from: Option<OwnedEventId>,
+
+    /// the parent task hasn’t arrived yet, it picks us up once it does
+    #[serde(default, skip_serializing_if = "is_false")]
+    awaiting_parent: bool,
 }
 
 impl Deref for Task {
@@ -70,6 +118,139 @@ impl Task {
             .map(|d| d.format("%Y-%m-%d").to_string())
     }
 
+    pub fn parent_task(&self) -> Option<&OwnedEventId> {
+        self.inner.parent_task.as_ref()
+    }
+
+    pub fn is_subtask(&self) -> bool {
+        self.inner.parent_task.is_some()
+    }
+
+    pub fn subtasks_key(&self) -> IndexKey {
+        IndexKey::ObjectList(self.meta.event_id.clone(), ObjectListIndex::Subtasks)
+    }
+
+    pub fn subtask_stats(&self) -> &TaskStats {
+        &self.subtask_stats
+    }
+
+    pub fn blocked_by(&self) -> &[OwnedEventId] {
+        &self.inner.blocked_by
+    }
+
+    /// The tasks this one is blocking
+    pub fn blocks(&self) -> &[OwnedEventId] {
+        &self.blocks
+    }
+
+    /// The tasks blocking this one that aren’t done yet
+    pub fn open_blockers(&self) -> &[OwnedEventId] {
+        &self.open_blockers
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        !self.open_blockers.is_empty()
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        self.inner.recurrence.is_some()
+    }
+
+    /// The first task of the recurring series this is an instance of
+    pub fn original_task(&self) -> &EventId {
+        self.inner
+            .original_task
+            .as_deref()
+            .unwrap_or(&self.meta.event_id)
+    }
+
+    /// The next instance of this recurring task, if it has been created already
+    pub fn superseded_by(&self) -> Option<&OwnedEventId> {
+        self.superseded_by.as_ref()
+    }
+
+    /// The instance that counts, if this one follows the same previous
+    /// instance and was created after it
+    pub fn duplicate_of(&self) -> Option<&OwnedEventId> {
+        self.duplicate_of.as_ref()
+    }
+
+    /// Whether this task sits in the trash tasklist of its space
+    pub fn is_trashed(&self) -> bool {
+        self.trashed_from.is_some()
+    }
+
+    /// The tasklist to restore this task to when taking it out of the trash
+    pub fn trashed_from(&self) -> Option<&EventId> {
+        self.trashed_from.as_deref()
+    }
+
+    /// The next instance of this recurring task, after this one was done on
+    /// `done_on`: due on the first occurrence after the current due date and
+    /// `done_on`. `None` if the task doesn’t recur (anymore) or we don’t
+    /// support its rule.
+    pub fn next_instance(&self, done_on: Date) -> Option<TaskBuilder> {
+        let rule = self.inner.recurrence.as_ref()?.rule()?;
+        let start = self.inner.due_date.unwrap_or(done_on);
+        let after = start.max(done_on);
+        let (idx, next_due) = rule
+            .iter_from(
+                start.and_time(NaiveTime::MIN),
+                rule.until.map(|u| u.naive_utc()),
+            )
+            .map(|d| d.date())
+            .enumerate()
+            .find(|(_, d)| *d > after)?;
+
+        let mut next_rule = rule.clone();
+        if let Some(count) = rule.count {
+            // the count includes the instances we skipped
+            next_rule.count = Some(count.checked_sub(idx as u32).filter(|c| *c > 0)?);
+        }
+
+        let mut builder = TaskBuilder::default();
+        builder
+            .title(self.inner.title.clone())
+            .task_list_id(self.inner.task_list_id.event_id.clone())
+            .parent_task(self.inner.parent_task.clone())
+            .description(self.inner.description.clone())
+            .due_date(next_due)
+            .utc_due_time_of_day(self.inner.utc_due_time_of_day)
+            .sort_order(self.inner.sort_order)
+            .priority(self.inner.priority.clone())
+            .display(self.inner.display.clone())
+            .keywords(self.inner.keywords.clone())
+            .categories(self.inner.categories.clone())
+            .checklist(
+                // the next instance starts with everything unchecked
+                self.inner
+                    .checklist
+                    .iter()
+                    .map(|item| ChecklistItem {
+                        done: false,
+                        ..item.clone()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .recurrence(Recurrence::Rule(next_rule))
+            .original_task(self.original_task().to_owned())
+            .previous_instance(self.meta.event_id.clone());
+        Some(builder)
+    }
+
+    /// The next instance of this recurring task if it is done but hasn’t been
+    /// followed up yet, e.g. as it was completed from elsewhere
+    pub fn missing_next_instance(&self, done_on: Date) -> Option<TaskBuilder> {
+        if !self.is_done()
+            || self.superseded_by.is_some()
+            || self.duplicate_of.is_some()
+            || self.is_trashed()
+        {
+            return None;
+        }
+        self.next_instance(done_on)
+    }
+
     pub fn utc_due_time_of_day(&self) -> Option<i32> {
         self.inner.utc_due_time_of_day
     }
@@ -87,6 +268,367 @@ impl Task {
             .to_owned()
     }
 
+    /// Update setting all fields that differ back to the given earlier state
+    pub fn reverter(&self, target: &TaskEventContent) -> TaskUpdateBuilder {
+        let current = &self.inner;
+        let mut builder = self.updater();
+        if serialized_differ(&current.title, &target.title) {
+            builder.title(Some(target.title.clone()));
+        }
+        let moved = current.task_list_id.event_id != target.task_list_id.event_id;
+        if moved {
+            builder.task_list_id(Some(target.task_list_id.event_id.clone()));
+        }
+        // moving clears the parent, so we always need to restore it then
+        if moved || serialized_differ(&current.parent_task, &target.parent_task) {
+            builder.parent_task(Some(target.parent_task.clone()));
+        }
+        if serialized_differ(&current.blocked_by, &target.blocked_by) {
+            builder.blocked_by(Some(target.blocked_by.clone()));
+        }
+        if serialized_differ(&current.recurrence, &target.recurrence) {
+            builder.recurrence(Some(target.recurrence.clone()));
+        }
+        if serialized_differ(&current.description, &target.description) {
+            builder.description(Some(target.description.clone()));
+        }
+        if serialized_differ(&current.due_date, &target.due_date) {
+            builder.due_date(Some(target.due_date));
+        }
+        if serialized_differ(&current.utc_due_time_of_day, &target.utc_due_time_of_day) {
+            builder.utc_due_time_of_day(Some(target.utc_due_time_of_day));
+        }
+        if serialized_differ(&current.utc_start, &target.utc_start) {
+            builder.utc_start(Some(target.utc_start));
+        }
+        if serialized_differ(&current.progress_percent, &target.progress_percent) {
+            builder.progress_percent(Some(target.progress_percent));
+        }
+        builder.checklist(ChecklistOperation::reverting(
+            &current.checklist,
+            &target.checklist,
+        ));
+        if serialized_differ(&current.estimated_minutes, &target.estimated_minutes) {
+            builder.estimated_minutes(Some(target.estimated_minutes));
+        }
+        if serialized_differ(&current.state, &target.state) {
+            builder.state(Some(target.state.clone()));
+        }
+        if serialized_differ(&current.sort_order, &target.sort_order) {
+            builder.sort_order(Some(target.sort_order));
+        }
+        if serialized_differ(&current.priority, &target.priority) {
+            builder.priority(Some(target.priority.clone()));
+        }
+        if serialized_differ(&current.display, &target.display) {
+            builder.display(Some(target.display.clone()));
+        }
+        if serialized_differ(&current.keywords, &target.keywords) {
+            builder.keywords(Some(target.keywords.clone()));
+        }
+        if serialized_differ(&current.categories, &target.categories) {
+            builder.categories(Some(target.categories.clone()));
+        }
+        builder
+    }
+
+    /// Register with the tasks we are blocked by, unregister from the `previous`
+    /// blockers we don’t depend on anymore and refresh which of them are still
+    /// open. Returns the blockers that changed.
+    async fn link_blockers(
+        &mut self,
+        store: &Store,
+        previous: &[OwnedEventId],
+    ) -> Result<Vec<AnyActerModel>> {
+        let task_id = self.meta.event_id.clone();
+        let mut changed = vec![];
+        for blocker_id in previous
+            .iter()
+            .filter(|b| !self.inner.blocked_by.contains(b))
+        {
+            let Ok(AnyActerModel::Task(mut blocker)) = store.get(blocker_id).await else {
+                continue;
+            };
+            blocker.blocks.retain(|t| t != &task_id);
+            changed.push(blocker.into());
+        }
+
+        let mut open_blockers = vec![];
+        for blocker_id in &self.inner.blocked_by {
+            let Ok(AnyActerModel::Task(mut blocker)) = store.get(blocker_id).await else {
+                // it picks us up from the blocked tasks index once it arrives
+                trace!(?task_id, ?blocker_id, "Blocking task not found yet.");
+                continue;
+            };
+            if !blocker.is_done() {
+                open_blockers.push(blocker_id.clone());
+            }
+            if !blocker.blocks.contains(&task_id) {
+                blocker.blocks.push(task_id.clone());
+                changed.push(blocker.into());
+            }
+        }
+        self.open_blockers = open_blockers;
+        Ok(changed)
+    }
+
+    /// Pick up the tasks blocked by us that arrived before we did and let them
+    /// know whether we are blocking them. Returns the blocked tasks that changed.
+    async fn link_waiting(&mut self, store: &Store) -> Result<Vec<AnyActerModel>> {
+        let task_id = self.meta.event_id.clone();
+        let key = IndexKey::ObjectList(task_id.clone(), ObjectListIndex::BlockedTasks);
+        let mut changed = vec![];
+        for model in store.get_list(&key).await? {
+            let AnyActerModel::Task(mut blocked) = model else {
+                continue;
+            };
+            if !blocked.inner.blocked_by.contains(&task_id) {
+                continue;
+            }
+            if !self.blocks.contains(&blocked.meta.event_id) {
+                self.blocks.push(blocked.meta.event_id.clone());
+            }
+            if !self.is_done() && !blocked.open_blockers.contains(&task_id) {
+                blocked.open_blockers.push(task_id.clone());
+                changed.push(blocked.into());
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Of the instances following the same `previous` one only the first counts,
+    /// later ones were created concurrently and are duplicates of it. Settles
+    /// which one that is, leaving out `removed`, for us and the other instances
+    /// and lets the previous instance know. Returns the other models that changed.
+    async fn settle_instances(
+        &mut self,
+        store: &Store,
+        previous: &EventId,
+        removed: Option<&EventId>,
+    ) -> Result<Vec<AnyActerModel>> {
+        let task_id = self.meta.event_id.clone();
+        let key = IndexKey::ObjectList(previous.to_owned(), ObjectListIndex::NextInstances);
+        let mut others = store
+            .get_list(&key)
+            .await?
+            .filter_map(|model| match model {
+                AnyActerModel::Task(task)
+                    if task.meta.event_id != task_id
+                        && Some(&*task.meta.event_id) != removed
+                        && task.inner.previous_instance.as_deref() == Some(previous) =>
+                {
+                    Some(task)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let first = others
+            .iter()
+            .map(|t| &t.meta)
+            .chain((removed != Some(&*task_id)).then_some(&self.meta))
+            .min_by(|a, b| {
+                (a.origin_server_ts, &a.event_id).cmp(&(b.origin_server_ts, &b.event_id))
+            })
+            .map(|meta| meta.event_id.clone());
+
+        let duplicate_of = |id: &EventId| first.clone().filter(|f| f != id);
+        self.duplicate_of = duplicate_of(&task_id);
+        let mut changed = vec![];
+        for mut other in others.drain(..) {
+            let dup = duplicate_of(&other.meta.event_id);
+            if other.duplicate_of != dup {
+                other.duplicate_of = dup;
+                changed.push(other.into());
+            }
+        }
+        match store.get(previous).await {
+            Ok(AnyActerModel::Task(mut previous)) if previous.superseded_by != first => {
+                previous.superseded_by = first;
+                changed.push(previous.into());
+            }
+            Ok(_) => {}
+            // it picks up its next instance once it arrives
+            Err(error) => trace!(?task_id, ?previous, ?error, "Previous instance not found."),
+        }
+        Ok(changed)
+    }
+
+    /// Pick up our next instance if it arrived before we did
+    async fn link_next_instance(&mut self, store: &Store) -> Result<()> {
+        let task_id = self.meta.event_id.clone();
+        let key = IndexKey::ObjectList(task_id.clone(), ObjectListIndex::NextInstances);
+        self.superseded_by = store
+            .get_list(&key)
+            .await?
+            .filter_map(|model| match model {
+                AnyActerModel::Task(task)
+                    if task.inner.previous_instance.as_ref() == Some(&task_id) =>
+                {
+                    Some(task.meta)
+                }
+                _ => None,
+            })
+            .min_by(|a, b| {
+                (a.origin_server_ts, &a.event_id).cmp(&(b.origin_server_ts, &b.event_id))
+            })
+            .map(|meta| meta.event_id);
+        Ok(())
+    }
+
+    /// Let the tasks we block know whether we are still blocking them.
+    /// Returns the blocked tasks that changed.
+    async fn update_blocked(&self, store: &Store, blocking: bool) -> Result<Vec<AnyActerModel>> {
+        let task_id = &self.meta.event_id;
+        let mut changed = vec![];
+        for blocked_id in &self.blocks {
+            let Ok(AnyActerModel::Task(mut blocked)) = store.get(blocked_id).await else {
+                continue;
+            };
+            let listed = blocked.open_blockers.contains(task_id);
+            if !blocking && listed {
+                blocked.open_blockers.retain(|b| b != task_id);
+                changed.push(blocked.into());
+            } else if blocking && !listed && blocked.inner.blocked_by.contains(task_id) {
+                blocked.open_blockers.push(task_id.clone());
+                changed.push(blocked.into());
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Whether this task can be put below `parent`: subtasks stay in the
+    /// tasklist of their parent and never end up below themselves. `None` if
+    /// we don’t know the parent yet.
+    async fn accepts_parent(&self, store: &Store, parent: &EventId) -> Option<bool> {
+        let mut seen = HashSet::new();
+        let mut current = parent.to_owned();
+        loop {
+            if current == self.meta.event_id || !seen.insert(current.clone()) {
+                return Some(false);
+            }
+            let ancestor = match store.get(&current).await {
+                Ok(AnyActerModel::Task(ancestor)) => ancestor,
+                // further up we can’t tell, the parent checked that once
+                Err(_) if &*current != parent => return Some(true),
+                Err(_) => return None,
+                Ok(_) => return Some(false),
+            };
+            if &*current == parent && ancestor.task_list_id.event_id != self.task_list_id.event_id {
+                return Some(false);
+            }
+            let Some(next) = ancestor.parent_task() else {
+                return Some(true);
+            };
+            current = next.clone();
+        }
+    }
+
+    /// Drops a parent we can’t be put below and remembers one we are still
+    /// waiting for.
+    async fn check_parent(&mut self, store: &Store) {
+        self.awaiting_parent = false;
+        let Some(parent) = self.inner.parent_task.clone() else {
+            return;
+        };
+        match self.accepts_parent(store, &parent).await {
+            Some(true) => {}
+            None => self.awaiting_parent = true,
+            Some(false) => {
+                warn!(
+                    task_id = ?self.meta.event_id,
+                    ?parent,
+                    "Subtasks must be in the tasklist of their parent and not above it. Ignoring the parent."
+                );
+                self.inner.parent_task = None;
+            }
+        }
+    }
+
+    /// Link the subtasks that arrived before us: count those in our tasklist,
+    /// the others can’t have us as their parent. Returns the changed subtasks.
+    async fn link_subtasks(&mut self, store: &Store) -> Result<Vec<AnyActerModel>> {
+        let task_id = self.meta.event_id.clone();
+        let mut changed = vec![];
+        for model in store.get_list(&self.subtasks_key()).await? {
+            let AnyActerModel::Task(mut subtask) = model else {
+                continue;
+            };
+            if !subtask.awaiting_parent || subtask.parent_task() != Some(&task_id) {
+                continue;
+            }
+            subtask.awaiting_parent = false;
+            if subtask.task_list_id.event_id == self.task_list_id.event_id {
+                self.subtask_stats
+                    .track(subtask.event_id(), Some(subtask.is_done()));
+            } else {
+                warn!(
+                    ?task_id,
+                    subtask_id = ?subtask.meta.event_id,
+                    "Subtask isn’t in the tasklist of its parent. Ignoring the parent."
+                );
+                subtask.inner.parent_task = None;
+            }
+            changed.push(subtask.into());
+        }
+        Ok(changed)
+    }
+
+    /// Our subtasks, as top level tasks now that we are gone.
+    async fn orphaned_subtasks(&self, store: &Store) -> Result<Vec<AnyActerModel>> {
+        let task_id = self.meta.event_id.clone();
+        Ok(store
+            .get_list(&self.subtasks_key())
+            .await?
+            .filter_map(|model| match model {
+                AnyActerModel::Task(mut subtask) if subtask.parent_task() == Some(&task_id) => {
+                    subtask.inner.parent_task = None;
+                    subtask.awaiting_parent = false;
+                    Some(subtask.into())
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Subtasks go wherever their parent goes: all tasks below this one, put
+    /// into its tasklist, each with the list it was taken from. Those ending
+    /// up in a trash remember where they were.
+    async fn subtasks_moved_along(
+        &self,
+        store: &Store,
+        to_trash: bool,
+    ) -> Result<Vec<(OwnedEventId, Task)>> {
+        let target = &self.inner.task_list_id.event_id;
+        let mut moved = vec![];
+        let mut seen = HashSet::from([self.meta.event_id.clone()]);
+        let mut parents = vec![self.meta.event_id.clone()];
+        while let Some(parent_id) = parents.pop() {
+            let index = IndexKey::ObjectList(parent_id.clone(), ObjectListIndex::Subtasks);
+            for mdl in store.get_list(&index).await? {
+                let AnyActerModel::Task(mut subtask) = mdl else {
+                    continue;
+                };
+                if subtask.parent_task() != Some(&parent_id)
+                    || !seen.insert(subtask.meta.event_id.clone())
+                {
+                    continue;
+                }
+                parents.push(subtask.meta.event_id.clone());
+                let from = subtask.inner.task_list_id.event_id.clone();
+                if &from == target {
+                    continue;
+                }
+                subtask.trashed_from = match subtask.trashed_from.take() {
+                    Some(origin) if to_trash => Some(origin),
+                    _ => Some(from.clone()).filter(|_| to_trash),
+                };
+                subtask.inner.task_list_id.event_id = target.clone();
+                moved.push((from, subtask));
+            }
+        }
+        Ok(moved)
+    }
+
     pub fn self_assign_event_content(&self) -> TaskSelfAssignEventContent {
         TaskSelfAssignEventContent {
             task: self.meta.event_id.clone().into(),
@@ -98,10 +640,25 @@ impl Task {
             task: self.meta.event_id.clone().into(),
         }
     }
+
+    pub fn assign_event_content(&self, user_id: OwnedUserId) -> TaskAssignEventContent {
+        TaskAssignEventContent::new(self.meta.event_id.clone(), user_id)
+    }
+
+    pub fn unassign_event_content(&self, user_id: OwnedUserId) -> TaskUnassignEventContent {
+        TaskUnassignEventContent::new(self.meta.event_id.clone(), user_id)
+    }
 }
 
 impl ActerModel for Task {
     fn indizes(&self, user_id: &UserId) -> Vec<IndexKey> {
+        if let (Some(previous), Some(_)) = (&self.inner.previous_instance, &self.duplicate_of) {
+            // a duplicate instance isn’t listed anywhere
+            return vec![
+                IndexKey::ObjectList(previous.clone(), ObjectListIndex::NextInstances),
+                IndexKey::ObjectHistory(self.meta.event_id.clone()),
+            ];
+        }
         let mut indizes = vec![
             IndexKey::ObjectList(
                 self.inner.task_list_id.event_id.clone(),
@@ -112,12 +669,35 @@ impl ActerModel for Task {
             IndexKey::ObjectHistory(self.inner.task_list_id.event_id.clone()),
             IndexKey::AllHistory,
         ];
-        if self.is_assigned(user_id) {
-            indizes.push(if self.is_done() {
-                IndexKey::Special(SpecialListsIndex::MyDoneTasks)
-            } else {
-                IndexKey::Special(SpecialListsIndex::MyOpenTasks)
-            });
+        if let Some(parent) = &self.inner.parent_task {
+            indizes.push(IndexKey::ObjectList(
+                parent.clone(),
+                ObjectListIndex::Subtasks,
+            ));
+        }
+        if let Some(original) = &self.inner.original_task {
+            indizes.push(IndexKey::ObjectHistory(original.clone()));
+        }
+        if let Some(previous) = &self.inner.previous_instance {
+            indizes.push(IndexKey::ObjectList(
+                previous.clone(),
+                ObjectListIndex::NextInstances,
+            ));
+        }
+        for blocker in &self.inner.blocked_by {
+            indizes.push(IndexKey::ObjectList(
+                blocker.clone(),
+                ObjectListIndex::BlockedTasks,
+            ));
+        }
+        // trashed tasks are off everyone’s plate
+        if self.is_assigned(user_id) && !self.is_trashed() {
+            if self.is_done() {
+                indizes.push(IndexKey::Special(SpecialListsIndex::MyDoneTasks));
+            } else if self.superseded_by.is_none() {
+                // of recurring tasks only the current instance is open
+                indizes.push(IndexKey::Special(SpecialListsIndex::MyOpenTasks));
+            }
         }
         indizes
     }
@@ -135,19 +715,92 @@ impl ActerModel for Task {
         ]
     }
 
-    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
-        default_model_execute(store, self.into()).await
+    async fn execute(mut self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        if let Some(previous) = &self.inner.previous_instance {
+            if let Ok(AnyActerModel::Task(previous)) = store.get(previous).await {
+                // the next instance is on the plate of the same people
+                self.assignees = previous.assignees;
+            }
+        }
+        self.check_parent(store).await;
+        let mut models = self.link_waiting(store).await?;
+        models.extend(self.link_subtasks(store).await?);
+        self.link_next_instance(store).await?;
+        if let Some(previous) = self.inner.previous_instance.clone() {
+            models.extend(self.settle_instances(store, &previous, None).await?);
+        }
+        if !self.inner.blocked_by.is_empty() {
+            models.extend(self.link_blockers(store, &[]).await?);
+        }
+        let mut updates = default_model_execute(store, self.into()).await?;
+        if !models.is_empty() {
+            updates.extend(store.save_many(models).await?);
+        }
+        Ok(updates)
+    }
+
+    async fn redact(
+        &self,
+        store: &Store,
+        redaction_model: RedactedActerModel,
+    ) -> Result<Vec<ExecuteReference>> {
+        // a redacted task isn’t blocking anyone anymore
+        let mut models = self.update_blocked(store, false).await?;
+        // nor anyone’s parent
+        models.extend(self.orphaned_subtasks(store).await?);
+        if let Some(previous) = &self.inner.previous_instance {
+            // another instance might be the current one now
+            let mut task = self.clone();
+            models.extend(
+                task.settle_instances(store, previous, Some(self.event_id()))
+                    .await?,
+            );
+        }
+        let model: AnyActerModel = redaction_model.into();
+        if let Some(belongs_to) = self.belongs_to() {
+            models.extend(transition_tree(store, belongs_to, &model).await?);
+        }
+        models.push(model);
+        store.save_many(models).await
     }
 
     fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
-        Some(vec![self.inner.task_list_id.event_id.clone()])
+        let mut parents = vec![self.inner.task_list_id.event_id.clone()];
+        if let Some(parent) = self
+            .inner
+            .parent_task
+            .as_ref()
+            .filter(|_| !self.awaiting_parent)
+        {
+            parents.push(parent.clone());
+        }
+        Some(parents)
     }
 
     fn transition(&mut self, model: &AnyActerModel) -> Result<bool> {
         match model {
-            AnyActerModel::TaskUpdate(update) => update.apply(&mut self.inner),
+            AnyActerModel::TaskUpdate(update) => {
+                let changed = update.apply(&mut self.inner)?;
+                if update.inner.parent_task.is_some() || self.inner.parent_task.is_none() {
+                    self.awaiting_parent =
+                        update.awaiting_parent && self.inner.parent_task.is_some();
+                }
+                Ok(changed)
+            }
             AnyActerModel::TaskSelfAssign(update) => update.apply(self),
             AnyActerModel::TaskSelfUnassign(update) => update.apply(self),
+            AnyActerModel::TaskAssign(update) => update.apply(self),
+            AnyActerModel::TaskUnassign(update) => update.apply(self),
+            AnyActerModel::Task(subtask) => {
+                // a subtask changed or was moved away from us
+                let done =
+                    (subtask.parent_task() == Some(&self.meta.event_id)).then(|| subtask.is_done());
+                Ok(self.subtask_stats.track(subtask.event_id(), done))
+            }
+            // a subtask has been redacted
+            AnyActerModel::RedactedActerModel(redacted) => {
+                Ok(self.subtask_stats.track(redacted.event_id(), None))
+            }
             _ => Ok(false),
         }
     }
@@ -156,16 +809,26 @@ impl ActerModel for Task {
 impl From<OriginalMessageLikeEvent<TaskEventContent>> for Task {
     fn from(outer: OriginalMessageLikeEvent<TaskEventContent>) -> Self {
         let OriginalMessageLikeEvent {
-            content,
+            mut content,
             room_id,
             event_id,
             sender,
             origin_server_ts,
             ..
         } = outer;
+        if content.progress_percent.is_none() {
+            content.progress_percent = content.checklist_progress();
+        }
         Task {
             inner: content,
             assignees: Vec::with_capacity(0),
+            subtask_stats: Default::default(),
+            blocks: Default::default(),
+            open_blockers: Default::default(),
+            superseded_by: None,
+            duplicate_of: None,
+            trashed_from: None,
+            awaiting_parent: false,
             meta: EventMeta {
                 room_id,
                 event_id,
@@ -181,23 +844,181 @@ impl From<OriginalMessageLikeEvent<TaskEventContent>> for Task {
 pub struct TaskUpdate {
     pub(crate) inner: TaskUpdateEventContent,
     meta: EventMeta,
+
+    /// the tasklist the task was moved away from by this update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    moved_from: Option<OwnedEventId>,
+
+    /// the new parent task hasn’t arrived yet
+    #[serde(default, skip_serializing_if = "is_false")]
+    awaiting_parent: bool,
+}
+
+impl TaskUpdate {
+    pub fn moved_from(&self) -> Option<&EventId> {
+        self.moved_from.as_deref()
+    }
 }
 
 impl ActerModel for TaskUpdate {
     fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
-        vec![
+        let mut indizes = vec![
             IndexKey::ObjectHistory(self.inner.task.event_id.clone()),
             IndexKey::RoomHistory(self.meta.room_id.clone()),
             IndexKey::AllHistory,
-        ]
+        ];
+        if let (Some(from), Some(to)) = (&self.moved_from, &self.inner.task_list_id) {
+            indizes.push(IndexKey::ObjectHistory(from.clone()));
+            indizes.push(IndexKey::ObjectHistory(to.clone()));
+        }
+        indizes
     }
 
     fn event_meta(&self) -> &EventMeta {
         &self.meta
     }
 
-    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
-        default_model_execute(store, self.into()).await
+    async fn execute(mut self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let task_id = self.inner.task.event_id.clone();
+        let blockers_changed = self.inner.blocked_by.is_some();
+        let prev = match store.get(&task_id).await {
+            Ok(AnyActerModel::Task(task)) => Some(task),
+            _ => None,
+        };
+        let mut to_trash = None;
+        if let Some(target) = &self.inner.task_list_id {
+            match store.get(target).await {
+                Ok(AnyActerModel::TaskList(list)) if list.room_id() == &*self.meta.room_id => {
+                    self.moved_from = prev
+                        .as_ref()
+                        .map(|t| t.task_list_id.event_id.clone())
+                        .filter(|from| from != target);
+                    to_trash = Some(list.is_trash());
+                }
+                _ => {
+                    warn!(
+                        ?task_id,
+                        ?target,
+                        "Tasks can only be moved to tasklists of the same space. Ignoring."
+                    );
+                    self.inner.task_list_id = None;
+                }
+            }
+        }
+        if let (Some(prev), Some(Some(parent))) = (&prev, &self.inner.parent_task) {
+            // check the parent against the tasklist the task ends up in
+            let mut task = prev.clone();
+            self.inner.apply(&mut task.inner)?;
+            match task.accepts_parent(store, parent).await {
+                Some(true) => {}
+                None => self.awaiting_parent = true,
+                Some(false) => {
+                    warn!(
+                        ?task_id,
+                        ?parent,
+                        "Subtasks must be in the tasklist of their parent and not above it. Ignoring."
+                    );
+                    self.inner.parent_task = None;
+                }
+            }
+        }
+        let moved_from = self.moved_from.clone();
+        // moving the list also takes the task away from its parent
+        let moved = self.inner.parent_task.is_some() || moved_from.is_some();
+
+        let mut updates = default_model_execute(store, self.into()).await?;
+
+        let Some(prev) = prev else {
+            return Ok(updates);
+        };
+        let AnyActerModel::Task(mut task) = store.get(&task_id).await? else {
+            return Ok(updates);
+        };
+        if let Some(prev_list) = &moved_from {
+            // the logged time moves along with the task
+            updates.push(TimeTrackingManager::stats_field_for(prev_list.clone()));
+            updates.push(TimeTrackingManager::stats_field_for(
+                task.task_list_id.event_id.clone(),
+            ));
+        }
+        if (prev.due_date, prev.utc_due_time_of_day) != (task.due_date, task.utc_due_time_of_day)
+            && task.is_assigned(store.user_id())
+        {
+            // the due lists are computed from my open tasks, let them recheck
+            updates.push(ExecuteReference::Index(IndexKey::Special(
+                SpecialListsIndex::MyOpenTasks,
+            )));
+        }
+
+        let mut models = vec![];
+        let mut task_changed = false;
+        if let Some(to_trash) = to_trash.filter(|_| moved_from.is_some()) {
+            // remember where to restore it to, unless it was in a trash already
+            let trashed_from = match &prev.trashed_from {
+                Some(origin) if to_trash => Some(origin.clone()),
+                _ => moved_from.clone().filter(|_| to_trash),
+            };
+            if task.trashed_from != trashed_from {
+                task.trashed_from = trashed_from;
+                task_changed = true;
+            }
+        }
+        if let Some(prev_list) = moved_from {
+            // its subtasks move along, so they stay in the same list
+            let subtasks = task
+                .subtasks_moved_along(store, to_trash.unwrap_or_default())
+                .await?;
+            // the previous tasklists have to drop them from their stats, the new one add them
+            let mut lists = vec![prev_list, task.task_list_id.event_id.clone()];
+            for (from, _) in &subtasks {
+                if !lists.contains(from) {
+                    lists.push(from.clone());
+                }
+            }
+            for list_id in lists {
+                let Ok(mut list) = store.get(&list_id).await else {
+                    continue;
+                };
+                trace!(?task_id, ?list_id, "moving task between tasklists");
+                let mut list_changed = false;
+                for moved in std::iter::once(&task).chain(subtasks.iter().map(|(_, t)| t)) {
+                    list_changed |= list.transition(&moved.clone().into())?;
+                }
+                if list_changed {
+                    models.push(list);
+                }
+            }
+            models.extend(subtasks.into_iter().map(|(_, t)| AnyActerModel::from(t)));
+        }
+        // if the task is moved, the previous parent has to drop it from its stats
+        if let Some(prev_parent) = prev.parent_task().filter(|_| moved) {
+            if task.parent_task() != Some(prev_parent) {
+                if let Ok(mut parent) = store.get(prev_parent).await {
+                    trace!(
+                        ?task_id,
+                        ?prev_parent,
+                        "removing subtask from previous parent"
+                    );
+                    if parent.transition(&task.clone().into())? {
+                        models.push(parent);
+                    }
+                }
+            }
+        }
+        if blockers_changed {
+            models.extend(task.link_blockers(store, &prev.inner.blocked_by).await?);
+            task_changed = true;
+        }
+        if task_changed {
+            models.push(task.clone().into());
+        }
+        if prev.is_done() != task.is_done() {
+            models.extend(task.update_blocked(store, !task.is_done()).await?);
+        }
+        if !models.is_empty() {
+            updates.extend(store.save_many(models).await?);
+        }
+        Ok(updates)
     }
 
     fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
@@ -231,6 +1052,8 @@ impl From<OriginalMessageLikeEvent<TaskUpdateEventContent>> for TaskUpdate {
                 origin_server_ts,
                 redacted: None,
             },
+            moved_from: None,
+            awaiting_parent: false,
         }
     }
 }
@@ -386,3 +1209,629 @@ impl From<OriginalMessageLikeEvent<TaskSelfUnassignEventContent>> for TaskSelfUn
         }
     }
 }
+
+/// The power level assigning others takes where the assignment events have
+/// none of their own, as in spaces created before they got one
+pub const ASSIGN_OTHERS_POWER_LEVEL: i32 = 50;
+
+/// The power levels set for the assignment events, `None` where there is none
+fn assign_others_levels(power_levels: &RoomPowerLevels) -> [Option<Int>; 2] {
+    [
+        <TaskAssignEventContent as StaticEventContent>::TYPE,
+        <TaskUnassignEventContent as StaticEventContent>::TYPE,
+    ]
+    .map(|event_type| {
+        power_levels
+            .events
+            .get(&TimelineEventType::from(event_type))
+            .copied()
+    })
+}
+
+fn may_assign_others(power_levels: &RoomPowerLevels, sender_id: &UserId) -> bool {
+    let user_level = power_levels.for_user(sender_id);
+    assign_others_levels(power_levels)
+        .into_iter()
+        .all(|level| user_level >= level.unwrap_or(Int::from(ASSIGN_OTHERS_POWER_LEVEL)))
+}
+
+/// Whether `sender_id` may (un)assign other members to tasks in this room.
+///
+/// Anyone may assign themselves, assigning others takes the power level set
+/// for the assignment events or, without one, [`ASSIGN_OTHERS_POWER_LEVEL`].
+pub async fn can_assign(room: &Room, sender_id: &UserId) -> Result<bool> {
+    Ok(may_assign_others(&room.power_levels().await?, sender_id))
+}
+
+/// Drop assignment changes of others sent by members without the power to do
+/// so. Where the assignment events have a power level of their own the
+/// homeserver enforced it when they were sent, others we check when applied.
+async fn is_permitted(store: &Store, meta: &EventMeta, users: &[OwnedUserId]) -> Result<bool> {
+    if users.iter().all(|u| u == &meta.sender) {
+        return Ok(true);
+    }
+    let Some(room) = store.client.get_room(&meta.room_id) else {
+        // we can't check, we assume the server did its job
+        return Ok(true);
+    };
+    let Ok(power_levels) = room.power_levels().await else {
+        return Ok(true);
+    };
+    if assign_others_levels(&power_levels)
+        .iter()
+        .all(Option::is_some)
+    {
+        return Ok(true);
+    }
+    Ok(may_assign_others(&power_levels, &meta.sender))
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskAssign {
+    pub(crate) inner: TaskAssignEventContent,
+    meta: EventMeta,
+}
+
+impl TaskAssign {
+    pub fn assignees(&self) -> Vec<OwnedUserId> {
+        self.inner.assignee.user_ids.iter().cloned().collect()
+    }
+
+    fn apply(&self, task: &mut Task) -> Result<bool> {
+        let mut changed = false;
+        for user_id in &self.inner.assignee.user_ids {
+            if !task.assignees.contains(user_id) {
+                task.assignees.push(user_id.clone());
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl ActerModel for TaskAssign {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            IndexKey::ObjectHistory(self.inner.task.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let assignees = self.assignees();
+        if !is_permitted(store, &self.meta, &assignees).await? {
+            warn!(event_id=?self.meta.event_id, sender=?self.meta.sender, "Not allowed to assign others. Ignoring.");
+            return Ok(vec![]);
+        }
+        let belongs_to = self.inner.task.event_id.clone();
+        let manager = {
+            let mut manager = InvitationsManager::from_store_and_event_id(store, &belongs_to).await;
+            let mut changed = false;
+            for user_id in assignees {
+                changed |= manager.mark_as_assigned(user_id);
+            }
+            changed.then_some(manager)
+        };
+
+        let mut updates = default_model_execute(store, self.into()).await?;
+        if let Some(manager) = manager {
+            updates.extend_from_slice(&manager.save().await?);
+        }
+        Ok(updates)
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        Some(vec![self.inner.task.event_id.clone()])
+    }
+}
+
+impl From<OriginalMessageLikeEvent<TaskAssignEventContent>> for TaskAssign {
+    fn from(outer: OriginalMessageLikeEvent<TaskAssignEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        TaskAssign {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskUnassign {
+    pub(crate) inner: TaskUnassignEventContent,
+    meta: EventMeta,
+}
+
+impl TaskUnassign {
+    pub fn assignees(&self) -> Vec<OwnedUserId> {
+        self.inner.assignee.user_ids.iter().cloned().collect()
+    }
+
+    fn apply(&self, task: &mut Task) -> Result<bool> {
+        let before = task.assignees.len();
+        task.assignees
+            .retain(|u| !self.inner.assignee.user_ids.contains(u));
+        Ok(before != task.assignees.len())
+    }
+}
+
+impl ActerModel for TaskUnassign {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            IndexKey::ObjectHistory(self.inner.task.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        let assignees = self.assignees();
+        if !is_permitted(store, &self.meta, &assignees).await? {
+            warn!(event_id=?self.meta.event_id, sender=?self.meta.sender, "Not allowed to unassign others. Ignoring.");
+            return Ok(vec![]);
+        }
+        let belongs_to = self.inner.task.event_id.clone();
+        let manager = {
+            let mut manager = InvitationsManager::from_store_and_event_id(store, &belongs_to).await;
+            let mut changed = false;
+            for user_id in assignees {
+                changed |= manager.mark_as_unassigned(&user_id);
+            }
+            changed.then_some(manager)
+        };
+
+        let mut updates = default_model_execute(store, self.into()).await?;
+        if let Some(manager) = manager {
+            updates.extend_from_slice(&manager.save().await?);
+        }
+        Ok(updates)
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        Some(vec![self.inner.task.event_id.clone()])
+    }
+}
+
+impl From<OriginalMessageLikeEvent<TaskUnassignEventContent>> for TaskUnassign {
+    fn from(outer: OriginalMessageLikeEvent<TaskUnassignEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        TaskUnassign {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events::AnyActerEvent, executor::Executor, models::fixtures};
+
+    fn weekly_task(rule: &str) -> anyhow::Result<Task> {
+        let json_raw = format!(
+            r#"{{"type":"global.acter.dev.task",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{{"title":"Clean the shared kitchen",
+                "m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                    "event_id":"$9tXSFEtqOQcpRS8KFmFXzv9KCrbNVDI3vgANc2ddnnc"}},
+                "due_date":"2024-03-04",
+                "recurrence":"{rule}"}},
+            "origin_server_ts":1672407531453,
+            "unsigned":{{"age":11523850}},
+            "event_id":"$KwumA4L3M-duXu0I3UA886LvN-BDCKAyxR1skNfnh3c",
+            "user_id":"@odo:ds9.acter.global","age":11523850}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        let AnyActerModel::Task(task) = AnyActerModel::try_from(event)? else {
+            anyhow::bail!("Not a task");
+        };
+        Ok(task)
+    }
+
+    #[test]
+    fn assigning_others_in_spaces_from_before_its_power_level() -> anyhow::Result<()> {
+        use matrix_sdk_base::ruma::events::room::power_levels::RoomPowerLevelsEventContent;
+
+        let odo = UserId::parse("@odo:ds9.acter.global")?;
+        let kira = UserId::parse("@kira:ds9.acter.global")?;
+        // the assignment events have no level of their own, anyone may send them
+        let mut content = RoomPowerLevelsEventContent::new();
+        content.users.insert(kira.clone(), Int::from(50));
+        content.events.insert(
+            <TaskEventContent as StaticEventContent>::TYPE.into(),
+            Int::from(0),
+        );
+        let old_space = RoomPowerLevels::from(content.clone());
+        assert_eq!(assign_others_levels(&old_space), [None, None]);
+        assert!(!may_assign_others(&old_space, &odo));
+        assert!(may_assign_others(&old_space, &kira));
+
+        // once the space has levels for them, those count
+        for event_type in [
+            <TaskAssignEventContent as StaticEventContent>::TYPE,
+            <TaskUnassignEventContent as StaticEventContent>::TYPE,
+        ] {
+            content.events.insert(event_type.into(), Int::from(0));
+        }
+        let open_space = RoomPowerLevels::from(content);
+        assert!(may_assign_others(&open_space, &odo));
+        Ok(())
+    }
+
+    #[test]
+    fn next_instance_follows_the_rule() -> anyhow::Result<()> {
+        let task = weekly_task("FREQ=WEEKLY;COUNT=5")?;
+        let next = task
+            .next_instance("2024-03-04".parse()?)
+            .expect("there is a next instance")
+            .build()?;
+        assert_eq!(next.due_date, Some("2024-03-11".parse()?));
+        assert_eq!(
+            next.recurrence
+                .as_ref()
+                .and_then(Recurrence::rule)
+                .and_then(|r| r.count),
+            Some(4)
+        );
+        assert_eq!(next.original_task.as_ref(), Some(&task.meta.event_id));
+        assert_eq!(next.previous_instance.as_ref(), Some(&task.meta.event_id));
+
+        // done late, we skip the missed ones
+        let next = task
+            .next_instance("2024-03-20".parse()?)
+            .expect("there is a next instance")
+            .build()?;
+        assert_eq!(next.due_date, Some("2024-03-25".parse()?));
+        assert_eq!(
+            next.recurrence
+                .as_ref()
+                .and_then(Recurrence::rule)
+                .and_then(|r| r.count),
+            Some(2)
+        );
+
+        let task = weekly_task("FREQ=WEEKLY;COUNT=3")?;
+        assert!(task.next_instance("2024-03-20".parse()?).is_none());
+
+        // kept, but we can’t tell when it is due next
+        let task = weekly_task("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1")?;
+        assert!(task.is_recurring());
+        assert_eq!(
+            task.recurrence.as_ref().map(ToString::to_string),
+            Some("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1".to_owned())
+        );
+        assert!(task.next_instance("2024-03-04".parse()?).is_none());
+        Ok(())
+    }
+
+    const LIST_ID: &str = "$renovation";
+
+    fn model(
+        event_id: &str,
+        ts: u64,
+        event_type: &str,
+        content: &str,
+    ) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":{ts},
+            "event_id":"{event_id}",
+            "user_id":"@odo:ds9.acter.global"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    /// an executor with an empty tasklist [`LIST_ID`]
+    async fn executor_with_list() -> anyhow::Result<Executor> {
+        let executor = fixtures::executor().await?;
+        executor
+            .handle(model(
+                LIST_ID,
+                1672407531453,
+                "global.acter.dev.tasklist",
+                r#"{"name":"Renovation"}"#,
+            )?)
+            .await?;
+        Ok(executor)
+    }
+
+    fn task(event_id: &str, list_id: &str, fields: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            event_id,
+            1672407531453,
+            "global.acter.dev.task",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"{list_id}"}},{fields}}}"#
+            ),
+        )
+    }
+
+    fn task_in_list(event_id: &str, fields: &str) -> anyhow::Result<AnyActerModel> {
+        task(event_id, LIST_ID, fields)
+    }
+
+    async fn stored_task(executor: &Executor, event_id: &str) -> anyhow::Result<Task> {
+        let AnyActerModel::Task(task) = executor.store().get(&EventId::parse(event_id)?).await?
+        else {
+            anyhow::bail!("{event_id} is not a task");
+        };
+        Ok(task)
+    }
+
+    #[tokio::test]
+    async fn blockers_link_in_any_order() -> anyhow::Result<()> {
+        let executor = executor_with_list().await?;
+        // the blocked task arrives before its blocker
+        executor
+            .handle(task_in_list(
+                "$paint",
+                r#""title":"Paint the walls","blocked_by":["$plaster"]"#,
+            )?)
+            .await?;
+        assert!(!stored_task(&executor, "$paint").await?.is_blocked());
+
+        executor
+            .handle(task_in_list("$plaster", r#""title":"Plaster the walls""#)?)
+            .await?;
+        let plaster = stored_task(&executor, "$plaster").await?;
+        assert_eq!(plaster.blocks(), [EventId::parse("$paint")?]);
+        let paint = stored_task(&executor, "$paint").await?;
+        assert_eq!(paint.open_blockers(), [EventId::parse("$plaster")?]);
+
+        // and the other way around, with the blocker done already
+        executor
+            .handle(task_in_list(
+                "$sand",
+                r#""title":"Sand the floor","progress_percent":100"#,
+            )?)
+            .await?;
+        executor
+            .handle(task_in_list(
+                "$varnish",
+                r#""title":"Varnish the floor","blocked_by":["$sand"]"#,
+            )?)
+            .await?;
+        assert!(!stored_task(&executor, "$varnish").await?.is_blocked());
+        assert_eq!(
+            stored_task(&executor, "$sand").await?.blocks(),
+            [EventId::parse("$varnish")?]
+        );
+        Ok(())
+    }
+
+    fn instance(event_id: &str, ts: u64) -> anyhow::Result<AnyActerModel> {
+        model(
+            event_id,
+            1672407531453 + ts,
+            "global.acter.dev.task",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"{LIST_ID}"}},"title":"Water the plants","recurrence":"FREQ=WEEKLY",
+                "original_task":"$water","previous_instance":"$water"}}"#
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn concurrent_next_instances_are_deduplicated() -> anyhow::Result<()> {
+        let executor = executor_with_list().await?;
+        // the next instances arrive before the task they follow
+        executor.handle(instance("$later", 20)?).await?;
+        executor.handle(instance("$first", 10)?).await?;
+        executor
+            .handle(task_in_list(
+                "$water",
+                r#""title":"Water the plants","recurrence":"FREQ=WEEKLY","progress_percent":100"#,
+            )?)
+            .await?;
+
+        let water = stored_task(&executor, "$water").await?;
+        assert_eq!(water.superseded_by(), Some(&EventId::parse("$first")?));
+        assert!(water.missing_next_instance("2024-03-04".parse()?).is_none());
+        assert_eq!(stored_task(&executor, "$first").await?.duplicate_of(), None);
+        assert_eq!(
+            stored_task(&executor, "$later").await?.duplicate_of(),
+            Some(&EventId::parse("$first")?)
+        );
+
+        // only the first one is listed
+        let listed = executor
+            .store()
+            .get_list(&IndexKey::ObjectList(
+                EventId::parse(LIST_ID)?,
+                ObjectListIndex::Tasks,
+            ))
+            .await?
+            .map(|m| m.event_id().to_string())
+            .collect::<Vec<_>>();
+        assert!(listed.contains(&"$first".to_owned()));
+        assert!(!listed.contains(&"$later".to_owned()));
+
+        // one sent once everyone agreed doesn’t change that either
+        executor.handle(instance("$late", 30)?).await?;
+        assert_eq!(
+            stored_task(&executor, "$late").await?.duplicate_of(),
+            Some(&EventId::parse("$first")?)
+        );
+        assert_eq!(
+            stored_task(&executor, "$water").await?.superseded_by(),
+            Some(&EventId::parse("$first")?)
+        );
+        Ok(())
+    }
+
+    fn task_update(event_id: &str, task_id: &str, fields: &str) -> anyhow::Result<AnyActerModel> {
+        model(
+            event_id,
+            1672407531453,
+            "global.acter.dev.task.update",
+            &format!(
+                r#"{{"m.relates_to":{{"rel_type":"global.acter.dev.update",
+                "event_id":"{task_id}"}},{fields}}}"#
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn subtasks_move_along() -> anyhow::Result<()> {
+        let executor = executor_with_list().await?;
+        executor
+            .handle(model(
+                "$trash",
+                1672407531453,
+                "global.acter.dev.tasklist",
+                r#"{"name":"Trash","role":"Trash"}"#,
+            )?)
+            .await?;
+        executor
+            .handle(task_in_list("$walls", r#""title":"Redo the walls""#)?)
+            .await?;
+        executor
+            .handle(task_in_list(
+                "$plaster",
+                r#""title":"Plaster the walls","parent_task":"$walls""#,
+            )?)
+            .await?;
+        executor
+            .handle(task_in_list(
+                "$corners",
+                r#""title":"Fix the corners","parent_task":"$plaster""#,
+            )?)
+            .await?;
+
+        executor
+            .handle(task_update(
+                "$trashing",
+                "$walls",
+                r#""task_list_id":"$trash""#,
+            )?)
+            .await?;
+        for task_id in ["$walls", "$plaster", "$corners"] {
+            let task = stored_task(&executor, task_id).await?;
+            assert_eq!(task.task_list_id.event_id, EventId::parse("$trash")?);
+            assert_eq!(task.trashed_from(), Some(&*EventId::parse(LIST_ID)?));
+        }
+        // and are counted in the trash only
+        for (list_id, count) in [(LIST_ID, 0), ("$trash", 3)] {
+            let AnyActerModel::TaskList(list) =
+                executor.store().get(&EventId::parse(list_id)?).await?
+            else {
+                anyhow::bail!("{list_id} is not a tasklist");
+            };
+            assert_eq!(*list.stats().tasks_count(), count);
+        }
+
+        // restoring brings them all back
+        executor
+            .handle(task_update(
+                "$restoring",
+                "$walls",
+                &format!(r#""task_list_id":"{LIST_ID}""#),
+            )?)
+            .await?;
+        for task_id in ["$walls", "$plaster", "$corners"] {
+            let task = stored_task(&executor, task_id).await?;
+            assert_eq!(task.task_list_id.event_id, EventId::parse(LIST_ID)?);
+            assert!(!task.is_trashed());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn subtask_parents_are_checked() -> anyhow::Result<()> {
+        let executor = executor_with_list().await?;
+        executor
+            .handle(model(
+                "$garden",
+                1672407531453,
+                "global.acter.dev.tasklist",
+                r#"{"name":"Garden"}"#,
+            )?)
+            .await?;
+        executor
+            .handle(task_in_list("$walls", r#""title":"Redo the walls""#)?)
+            .await?;
+
+        // the parent has to be in the same list
+        executor
+            .handle(task(
+                "$hedge",
+                "$garden",
+                r#""title":"Cut the hedge","parent_task":"$walls""#,
+            )?)
+            .await?;
+        assert!(!stored_task(&executor, "$hedge").await?.is_subtask());
+
+        // and must not be below the task itself
+        executor
+            .handle(task_in_list(
+                "$plaster",
+                r#""title":"Plaster the walls","parent_task":"$walls""#,
+            )?)
+            .await?;
+        executor
+            .handle(task_update(
+                "$looping",
+                "$walls",
+                r#""parent_task":"$plaster""#,
+            )?)
+            .await?;
+        assert!(!stored_task(&executor, "$walls").await?.is_subtask());
+
+        // a subtask may arrive before its parent
+        executor
+            .handle(task_in_list(
+                "$lamp",
+                r#""title":"Hang the lamp","parent_task":"$ceiling""#,
+            )?)
+            .await?;
+        executor
+            .handle(task_in_list("$ceiling", r#""title":"Paint the ceiling""#)?)
+            .await?;
+        let ceiling = stored_task(&executor, "$ceiling").await?;
+        assert_eq!(*ceiling.subtask_stats().tasks_count(), 1);
+
+        // and stays around as a top level task once the parent is gone
+        fixtures::redact(&executor, "$ceiling").await?;
+        assert!(!stored_task(&executor, "$lamp").await?.is_subtask());
+        Ok(())
+    }
+}
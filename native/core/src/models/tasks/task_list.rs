@@ -1,16 +1,21 @@
 use derive_getters::Getters;
-use matrix_sdk::ruma::OwnedEventId;
+use matrix_sdk::ruma::{EventId, OwnedEventId};
 use matrix_sdk_base::ruma::{events::OriginalMessageLikeEvent, RoomId, UserId};
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 use tracing::{trace, warn};
 
-use super::super::{
-    default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, Store,
+use super::{
+    super::{default_model_execute, ActerModel, AnyActerModel, Capability, EventMeta, Store},
+    Task,
 };
 use crate::{
-    events::tasks::{TaskListEventContent, TaskListUpdateBuilder, TaskListUpdateEventContent},
+    events::tasks::{
+        SpecialTaskListRole, TaskListEventContent, TaskListUpdateBuilder,
+        TaskListUpdateEventContent, TaskState,
+    },
     referencing::{ExecuteReference, IndexKey, ObjectListIndex, SectionIndex},
+    util::serialized_differ,
     Result,
 };
 
@@ -18,6 +23,33 @@ use crate::{
 pub struct TaskStats {
     has_tasks: bool,
     tasks_count: u32,
+    #[serde(default)]
+    done_count: u32,
+    #[serde(default)]
+    open_count: u32,
+    /// the done state per task we are counting
+    #[getter(skip)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tasks: BTreeMap<OwnedEventId, bool>,
+}
+
+impl TaskStats {
+    /// Track the done state of the given task or stop counting it if `None`.
+    /// Returns whether the stats changed.
+    pub(crate) fn track(&mut self, task_id: &EventId, done: Option<bool>) -> bool {
+        let changed = match done {
+            Some(done) => self.tasks.insert(task_id.to_owned(), done) != Some(done),
+            None => self.tasks.remove(task_id).is_some(),
+        };
+        if changed {
+            let done_count = self.tasks.values().filter(|done| **done).count() as u32;
+            self.tasks_count = self.tasks.len() as u32;
+            self.has_tasks = self.tasks_count > 0;
+            self.done_count = done_count;
+            self.open_count = self.tasks_count - done_count;
+        }
+        changed
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -55,11 +87,83 @@ impl TaskList {
         false
     }
 
+    pub fn is_inbox(&self) -> bool {
+        self.inner.role == Some(SpecialTaskListRole::Inbox)
+    }
+
+    pub fn is_trash(&self) -> bool {
+        self.inner.role == Some(SpecialTaskListRole::Trash)
+    }
+
+    /// The workflow states tasks of this list go through, in order
+    pub fn states(&self) -> Vec<TaskState> {
+        if self.inner.states.is_empty() {
+            return TaskState::defaults();
+        }
+        if let Err(error) = TaskState::validate_workflow(&self.inner.states) {
+            warn!(task_list = ?self.meta.event_id, ?error, "Invalid workflow, using default");
+            return TaskState::defaults();
+        }
+        self.inner.states.clone()
+    }
+
+    /// The state the given task is in. Whether a task is done is decided by
+    /// its progress, so a done task not in the done state is shown as done
+    /// and an open one in the done state as being in the first open state.
+    pub fn state_of(&self, task: &Task) -> TaskState {
+        let states = self.states();
+        let done = task.is_done();
+        let current = task
+            .state
+            .as_ref()
+            .and_then(|id| states.iter().find(|s| &s.id == id))
+            .filter(|s| s.done == done);
+        // valid workflows always have open and done states
+        current
+            .or_else(|| states.iter().find(|s| s.done == done))
+            .unwrap_or(&states[0])
+            .clone()
+    }
+
     pub fn updater(&self) -> TaskListUpdateBuilder {
         TaskListUpdateBuilder::default()
             .task_list(self.meta.event_id.clone())
             .to_owned()
     }
+
+    /// Update setting all fields that differ back to the given earlier state
+    pub fn reverter(&self, target: &TaskListEventContent) -> TaskListUpdateBuilder {
+        let current = &self.inner;
+        let mut builder = self.updater();
+        if serialized_differ(&current.name, &target.name) {
+            builder.name(Some(target.name.clone()));
+        }
+        if serialized_differ(&current.role, &target.role) {
+            builder.role(Some(target.role.clone()));
+        }
+        if serialized_differ(&current.description, &target.description) {
+            builder.description(Some(target.description.clone()));
+        }
+        if serialized_differ(&current.display, &target.display) {
+            builder.display(Some(target.display.clone()));
+        }
+        if serialized_differ(&current.sort_order, &target.sort_order) {
+            builder.sort_order(Some(target.sort_order));
+        }
+        if serialized_differ(&current.time_zone, &target.time_zone) {
+            builder.time_zone(Some(target.time_zone));
+        }
+        if serialized_differ(&current.keywords, &target.keywords) {
+            builder.keywords(Some(target.keywords.clone()));
+        }
+        if serialized_differ(&current.categories, &target.categories) {
+            builder.categories(Some(target.categories.clone()));
+        }
+        if serialized_differ(&current.states, &target.states) {
+            builder.states(Some(target.states.clone()));
+        }
+        builder
+    }
 }
 
 impl From<OriginalMessageLikeEvent<TaskListEventContent>> for TaskList {
@@ -118,10 +222,12 @@ impl ActerModel for TaskList {
             AnyActerModel::TaskListUpdate(update) => update.apply(&mut self.inner),
             AnyActerModel::Task(task) => {
                 let key = self.event_id().to_owned();
-                trace!(?key, ?task, "adding task to list");
-                self.task_stats.tasks_count += 1;
-                self.task_stats.has_tasks = true;
-                Ok(true)
+                trace!(?key, ?task, "updating task in list");
+                let done = (task.task_list_id.event_id == key).then(|| task.is_done());
+                Ok(self.task_stats.track(task.event_id(), done))
+            }
+            AnyActerModel::RedactedActerModel(task) => {
+                Ok(self.task_stats.track(task.event_id(), None))
             }
             _ => {
                 warn!(?model, "Trying to transition with an unknown model");
@@ -0,0 +1,370 @@
+use derive_getters::Getters;
+use matrix_sdk_base::ruma::{
+    events::OriginalMessageLikeEvent, EventId, OwnedEventId, OwnedUserId, UserId,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ops::Deref};
+use tracing::{error, trace};
+
+use super::super::{ActerModel, AnyActerModel, EventMeta, RedactedActerModel, Store};
+use crate::{
+    events::tasks::{TaskTimeLogBuilder, TaskTimeLogEventContent},
+    referencing::{ExecuteReference, IndexKey, ModelParam, ObjectListIndex},
+    util::is_zero,
+    Error, Result,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Getters)]
+pub struct TimeTrackingStats {
+    #[serde(default, skip_serializing_if = "is_zero")]
+    total_entries_count: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    total_minutes: u64,
+    /// the minutes logged by each user
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    minutes_per_user: BTreeMap<OwnedUserId, u64>,
+}
+
+impl TimeTrackingStats {
+    fn add(&mut self, user_id: &UserId, minutes: u32) {
+        self.total_entries_count += 1;
+        self.total_minutes += u64::from(minutes);
+        *self.minutes_per_user.entry(user_id.to_owned()).or_default() += u64::from(minutes);
+    }
+
+    fn remove(&mut self, user_id: &UserId, minutes: u32) {
+        self.total_entries_count = self.total_entries_count.saturating_sub(1);
+        self.total_minutes = self.total_minutes.saturating_sub(u64::from(minutes));
+        if let Some(logged) = self.minutes_per_user.get_mut(user_id) {
+            *logged = logged.saturating_sub(u64::from(minutes));
+            if *logged == 0 {
+                self.minutes_per_user.remove(user_id);
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &TimeTrackingStats) {
+        self.total_entries_count += other.total_entries_count;
+        self.total_minutes += other.total_minutes;
+        for (user_id, minutes) in &other.minutes_per_user {
+            *self.minutes_per_user.entry(user_id.clone()).or_default() += minutes;
+        }
+    }
+
+    /// the minutes logged by the given user
+    pub fn minutes_by(&self, user_id: &UserId) -> u64 {
+        self.minutes_per_user
+            .get(user_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// The time logged on a task or, summed up, on all tasks of a tasklist
+#[derive(Clone, Debug)]
+pub struct TimeTrackingManager {
+    stats: TimeTrackingStats,
+    event_id: OwnedEventId,
+    /// the tasks whose logs are counted here
+    task_ids: Vec<OwnedEventId>,
+    is_task_list: bool,
+    store: Store,
+}
+
+impl TimeTrackingManager {
+    pub(super) fn stats_field_for(parent: OwnedEventId) -> ExecuteReference {
+        ExecuteReference::ModelParam(parent, ModelParam::TimeTrackingStats)
+    }
+
+    async fn stats_for(store: &Store, task_id: &EventId) -> TimeTrackingStats {
+        store
+            .get_raw(&Self::stats_field_for(task_id.to_owned()).as_storage_key())
+            .await
+            .unwrap_or_default()
+    }
+
+    pub async fn from_store_and_event_id(
+        store: &Store,
+        event_id: &EventId,
+    ) -> Result<TimeTrackingManager> {
+        let store = store.clone();
+        // tasks can move between lists, so the list always sums up the tasks it has now
+        let (task_ids, is_task_list) = match store.get(event_id).await {
+            Ok(AnyActerModel::TaskList(list)) => (
+                store
+                    .get_list(&list.tasks_key())
+                    .await?
+                    .filter_map(|m| match m {
+                        AnyActerModel::Task(t) => Some(t.event_id().to_owned()),
+                        _ => None,
+                    })
+                    .collect(),
+                true,
+            ),
+            // time logs may arrive before their task
+            Ok(_) | Err(Error::ModelNotFound(_)) => (vec![event_id.to_owned()], false),
+            Err(error) => return Err(error),
+        };
+        let mut stats = TimeTrackingStats::default();
+        for task_id in &task_ids {
+            stats.merge(&Self::stats_for(&store, task_id).await);
+        }
+        Ok(TimeTrackingManager {
+            stats,
+            event_id: event_id.to_owned(),
+            task_ids,
+            is_task_list,
+            store,
+        })
+    }
+
+    /// All time logs, latest first per task
+    pub async fn time_logs(&self) -> Result<Vec<TaskTimeLog>> {
+        let mut logs = vec![];
+        for task_id in &self.task_ids {
+            logs.extend(
+                self.store
+                    .get_list(&TaskTimeLog::index_for(task_id.clone()))
+                    .await?
+                    .filter_map(|e| match e {
+                        AnyActerModel::TaskTimeLog(l) => Some(l),
+                        _ => None,
+                    }),
+            );
+        }
+        Ok(logs)
+    }
+
+    /// whether this sums up the tasks of a tasklist, time can only be logged on tasks
+    pub fn is_task_list(&self) -> bool {
+        self.is_task_list
+    }
+
+    pub fn stats(&self) -> &TimeTrackingStats {
+        &self.stats
+    }
+
+    pub fn draft_builder(&self) -> TaskTimeLogBuilder {
+        TaskTimeLogBuilder::default()
+            .task(self.event_id.clone())
+            .to_owned()
+    }
+
+    pub fn update_key(&self) -> ExecuteReference {
+        Self::stats_field_for(self.event_id.clone())
+    }
+
+    pub async fn save(&self) -> Result<ExecuteReference> {
+        let update_key = self.update_key();
+        self.store
+            .set_raw(&update_key.as_storage_key(), &self.stats)
+            .await?;
+        Ok(update_key)
+    }
+
+    pub fn event_id(&self) -> &EventId {
+        &self.event_id
+    }
+}
+
+impl Deref for TimeTrackingManager {
+    type Target = TimeTrackingStats;
+    fn deref(&self) -> &Self::Target {
+        &self.stats
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskTimeLog {
+    pub(crate) inner: TaskTimeLogEventContent,
+    pub meta: EventMeta,
+}
+
+impl Deref for TaskTimeLog {
+    type Target = TaskTimeLogEventContent;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl TaskTimeLog {
+    pub fn index_for(task_id: OwnedEventId) -> IndexKey {
+        IndexKey::ObjectList(task_id, ObjectListIndex::TimeLogs)
+    }
+
+    /// Update the stats of the task and return the keys to inform
+    async fn track(&self, store: &Store, added: bool) -> Result<Vec<ExecuteReference>> {
+        let task_id = self.inner.task.event_id.clone();
+        let task_list_id = match store.get(&task_id).await {
+            Ok(AnyActerModel::Task(task)) => Some(task.task_list_id.event_id.clone()),
+            Ok(_) => {
+                error!(?task_id, time_log = ?self, "time can only be logged on tasks. can’t apply");
+                return Ok(vec![]);
+            }
+            Err(Error::ModelNotFound(_)) => {
+                // counted for the task already, its list sums it up once it arrives
+                trace!(?task_id, time_log = ?self.meta.event_id, "task not known yet");
+                None
+            }
+            Err(error) => return Err(error),
+        };
+        let mut manager = TimeTrackingManager::from_store_and_event_id(store, &task_id).await?;
+        if added {
+            manager.stats.add(&self.meta.sender, self.inner.minutes);
+        } else {
+            manager.stats.remove(&self.meta.sender, self.inner.minutes);
+        }
+        let mut updates = vec![manager.save().await?];
+        if let Some(task_list_id) = task_list_id {
+            // the sum of the tasklist changed, too
+            updates.push(TimeTrackingManager::stats_field_for(task_list_id));
+        }
+        Ok(updates)
+    }
+}
+
+impl ActerModel for TaskTimeLog {
+    fn indizes(&self, _user_id: &UserId) -> Vec<IndexKey> {
+        vec![
+            TaskTimeLog::index_for(self.inner.task.event_id.clone()),
+            IndexKey::ObjectHistory(self.inner.task.event_id.clone()),
+            IndexKey::RoomHistory(self.meta.room_id.clone()),
+            IndexKey::AllHistory,
+        ]
+    }
+
+    fn event_meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    async fn execute(self, store: &Store) -> Result<Vec<ExecuteReference>> {
+        trace!(event_id=?self.event_id(), task = ?self.inner.task.event_id, "applying time log");
+        let stats_updates = self.track(store, true).await?;
+        let mut updates = store.save(self.into()).await?;
+        updates.extend(stats_updates);
+        Ok(updates)
+    }
+
+    async fn redact(
+        &self,
+        store: &Store,
+        redaction_model: RedactedActerModel,
+    ) -> Result<Vec<ExecuteReference>> {
+        // the logged time doesn’t count anymore
+        let stats_updates = self.track(store, false).await?;
+        let mut updates = store.save(redaction_model.into()).await?;
+        updates.extend(stats_updates);
+        Ok(updates)
+    }
+
+    fn belongs_to(&self) -> Option<Vec<OwnedEventId>> {
+        // we have a manager keeping the sums
+        None
+    }
+}
+
+impl From<OriginalMessageLikeEvent<TaskTimeLogEventContent>> for TaskTimeLog {
+    fn from(outer: OriginalMessageLikeEvent<TaskTimeLogEventContent>) -> Self {
+        let OriginalMessageLikeEvent {
+            content,
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            ..
+        } = outer;
+        TaskTimeLog {
+            inner: content,
+            meta: EventMeta {
+                room_id,
+                event_id,
+                sender,
+                origin_server_ts,
+                redacted: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events::AnyActerEvent, models::fixtures};
+
+    fn model(event_id: &str, event_type: &str, content: &str) -> anyhow::Result<AnyActerModel> {
+        let json_raw = format!(
+            r#"{{"type":"{event_type}",
+            "room_id":"!euhIDqDVvVXulrhWgN:ds9.acter.global","sender":"@odo:ds9.acter.global",
+            "content":{content},
+            "origin_server_ts":1672407531453,
+            "event_id":"{event_id}",
+            "user_id":"@odo:ds9.acter.global"}}"#
+        );
+        let event = serde_json::from_str::<AnyActerEvent>(&json_raw)?;
+        Ok(AnyActerModel::try_from(event)?)
+    }
+
+    #[test]
+    fn stats_sum_up_per_user() -> anyhow::Result<()> {
+        let odo: OwnedUserId = "@odo:ds9.acter.global".try_into()?;
+        let kira: OwnedUserId = "@kira:ds9.acter.global".try_into()?;
+
+        let mut task_a = TimeTrackingStats::default();
+        task_a.add(&odo, 90);
+        task_a.add(&kira, 30);
+        task_a.add(&odo, 15);
+        let mut task_b = TimeTrackingStats::default();
+        task_b.add(&kira, 60);
+
+        let mut list = TimeTrackingStats::default();
+        list.merge(&task_a);
+        list.merge(&task_b);
+        assert_eq!(list.total_entries_count, 4);
+        assert_eq!(list.total_minutes, 195);
+        assert_eq!(list.minutes_by(&odo), 105);
+        assert_eq!(list.minutes_by(&kira), 90);
+
+        task_b.remove(&kira, 60);
+        assert_eq!(task_b.total_minutes, 0);
+        assert!(task_b.minutes_per_user.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn logs_before_their_task_are_counted() -> anyhow::Result<()> {
+        let executor = fixtures::executor().await?;
+        let time_log = model(
+            "$log",
+            "global.acter.dev.task.time_log",
+            r#"{"m.relates_to":{"rel_type":"global.acter.dev.belongs_to",
+            "event_id":"$plaster"},"minutes":45}"#,
+        )?;
+        executor.handle(time_log).await?;
+
+        for model in [
+            model(
+                "$renovation",
+                "global.acter.dev.tasklist",
+                r#"{"name":"Renovation"}"#,
+            )?,
+            model(
+                "$plaster",
+                "global.acter.dev.task",
+                r#"{"m.relates_to":{"rel_type":"global.acter.dev.belongs_to",
+                "event_id":"$renovation"},"title":"Plaster the walls"}"#,
+            )?,
+        ] {
+            executor.handle(model).await?;
+        }
+        for object_id in ["$plaster", "$renovation"] {
+            let manager = TimeTrackingManager::from_store_and_event_id(
+                executor.store(),
+                &EventId::parse(object_id)?,
+            )
+            .await?;
+            assert_eq!(*manager.total_minutes(), 45, "{object_id}");
+            assert_eq!(*manager.total_entries_count(), 1, "{object_id}");
+        }
+        Ok(())
+    }
+}
@@ -108,3 +108,49 @@ impl ActerModel for TestModel {
         default_model_execute(store, self.into()).await
     }
 }
+
+/// A store to run models through, for the unit tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use matrix_sdk::Client;
+    use matrix_sdk_base::{
+        ruma::{api::MatrixVersion, user_id, EventId},
+        store::{MemoryStore, StoreConfig},
+    };
+
+    use crate::executor::Executor;
+
+    use super::{ActerModel, Store};
+
+    /// an executor on a fresh in-memory store, to run models through
+    pub async fn executor() -> anyhow::Result<Executor> {
+        let config = StoreConfig::new("tests".to_owned()).state_store(MemoryStore::new());
+        let client = Client::builder()
+            .homeserver_url("http://localhost")
+            .server_versions([MatrixVersion::V1_5])
+            .store_config(config)
+            .build()
+            .await?;
+        let store = Store::new_with_auth(client, user_id!("@test:example.org").to_owned()).await?;
+        Ok(Executor::new(store).await?)
+    }
+
+    /// redact the stored model `event_id`, as if the server told us so
+    pub async fn redact(executor: &Executor, event_id: &str) -> anyhow::Result<()> {
+        let model = executor.store().get(&EventId::parse(event_id)?).await?;
+        let reason = serde_json::from_value(serde_json::json!({
+            "event_id": format!("{event_id}:redacted"),
+            "sender": "@odo:ds9.acter.global",
+            "origin_server_ts": 1672407531453u64,
+            "content": { "redacts": event_id },
+        }))?;
+        executor
+            .redact(
+                model.model_type().to_owned(),
+                model.event_meta().clone(),
+                reason,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -16,6 +16,8 @@ pub enum SectionIndex {
     Pins,
     Stories,
     Tasks,
+    Polls,
+    Documents,
 }
 
 #[derive(
@@ -31,6 +33,13 @@ pub enum ObjectListIndex {
     Rsvp,
     Tasks,
     Invites,
+    Subtasks,
+    TimeLogs,
+    PollVotes,
+    Revisions,
+    /// tasks blocked by the object, even if it hasn’t arrived yet
+    BlockedTasks,
+    NextInstances,
 }
 
 #[derive(
@@ -42,6 +51,15 @@ pub enum SpecialListsIndex {
     MyOpenTasks,
     MyDoneTasks,
     InvitedTo,
+    DueReminders,
+    /// my open tasks past their due date, computed as time passes
+    MyOverdueTasks,
+    /// my open tasks due today, computed as time passes
+    MyTasksDueToday,
+    /// my open tasks due until the end of this week, including today
+    MyTasksDueThisWeek,
+    /// the responses I sent to calendar events
+    MyRsvps,
     #[cfg(any(test, feature = "testing"))]
     Test1,
     #[cfg(any(test, feature = "testing"))]
@@ -73,10 +91,17 @@ pub enum ModelParam {
     AttachmentsStats,
     ReactionStats,
     RsvpStats,
+    PreviousCapacity,
     #[strum(to_string = "read_receipts")]
     ReadReceiptsStats,
     #[strum(to_string = "invites")]
     InviteStats,
+    TimeTrackingStats,
+    PollStats,
+    /// the object as it was created, before any update
+    OriginalContent,
+    /// the updates of the object in the order they were applied
+    AppliedUpdates,
 }
 
 #[derive(
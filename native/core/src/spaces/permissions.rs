@@ -6,18 +6,24 @@ use matrix_sdk::ruma::{
 };
 use serde::Deserialize;
 
-use crate::events::{
-    attachments::AttachmentEventContent,
-    calendar::CalendarEventEventContent,
-    comments::CommentEventContent,
-    news::NewsEntryEventContent,
-    pins::PinEventContent,
-    rsvp::RsvpEventContent,
-    settings::{
-        ActerAppSettingsContent, SimpleOnOffSettingBuilder, SimpleSettingWithTurnOffBuilder,
+use crate::{
+    events::{
+        attachments::AttachmentEventContent,
+        calendar::CalendarEventEventContent,
+        comments::CommentEventContent,
+        news::NewsEntryEventContent,
+        pins::PinEventContent,
+        rsvp::RsvpEventContent,
+        settings::{
+            ActerAppSettingsContent, SimpleOnOffSettingBuilder, SimpleSettingWithTurnOffBuilder,
+        },
+        stories::StoryEventContent,
+        tasks::{
+            TaskAssignEventContent, TaskEventContent, TaskListEventContent,
+            TaskUnassignEventContent,
+        },
     },
-    stories::StoryEventContent,
-    tasks::{TaskEventContent, TaskListEventContent},
+    models::ASSIGN_OTHERS_POWER_LEVEL,
 };
 
 pub fn new_app_permissions_builder() -> AppPermissionsBuilder {
@@ -89,6 +95,16 @@ impl AppPermissionsBuilder {
                 .events
                 .entry(<TaskEventContent as StaticEventContent>::TYPE.into())
                 .or_insert_with(|| Int::from(0));
+
+            // assigning others is up to the moderators
+            permissions
+                .events
+                .entry(<TaskAssignEventContent as StaticEventContent>::TYPE.into())
+                .or_insert_with(|| Int::from(ASSIGN_OTHERS_POWER_LEVEL));
+            permissions
+                .events
+                .entry(<TaskUnassignEventContent as StaticEventContent>::TYPE.into())
+                .or_insert_with(|| Int::from(ASSIGN_OTHERS_POWER_LEVEL));
         }
         (settings, permissions)
     }
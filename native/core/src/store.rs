@@ -26,7 +26,7 @@ pub struct Store {
 
 static ALL_MODELS_KEY: &str = "ACTER::ALL";
 static DB_VERSION_KEY: &str = "ACTER::DB_VERSION";
-static CURRENT_DB_VERSION: u32 = 1;
+static CURRENT_DB_VERSION: u32 = 2;
 
 async fn get_from_store<T: serde::de::DeserializeOwned>(client: Client, key: &str) -> Result<T> {
     let v = client
@@ -278,8 +278,10 @@ impl StoreIndex {
                     meta.event_id.clone(),
                 ))
             }
-            //RSVPs are latest first for collection
-            IndexKey::ObjectList(_, ObjectListIndex::Rsvp) => StoreIndex::Ranked(
+            //RSVPs, poll votes and revisions are latest first for collection
+            IndexKey::ObjectList(_, ObjectListIndex::Rsvp)
+            | IndexKey::ObjectList(_, ObjectListIndex::PollVotes)
+            | IndexKey::ObjectList(_, ObjectListIndex::Revisions) => StoreIndex::Ranked(
                 RankedIndex::new_with(meta.origin_server_ts, meta.event_id.clone()),
             ),
             IndexKey::Section(SectionIndex::Boosts)
@@ -27,8 +27,10 @@ use crate::{
     client::CoreClient,
     events::{
         calendar::CalendarEventEventContent,
+        documents::DocumentEventContent,
         news::NewsEntryEventContent,
         pins::PinEventContent,
+        polls::PollEventContent,
         stories::StoryEventContent,
         tasks::{TaskEventContent, TaskListEventContent},
     },
@@ -148,6 +150,14 @@ pub enum ObjectInner {
         #[serde(flatten)]
         fields: PinEventContent,
     },
+    Poll {
+        #[serde(flatten)]
+        fields: PollEventContent,
+    },
+    Document {
+        #[serde(flatten)]
+        fields: DocumentEventContent,
+    },
     CalendarEvent {
         #[serde(flatten)]
         fields: CalendarEventEventContent,
@@ -512,6 +522,34 @@ impl Engine {
                         );
                         yield
                     }
+                    ObjectInner::Poll { fields } => {
+                        trace!(?fields, "submitting poll");
+                        let id = room
+                            .send(fields)
+                            .await
+                            .map_err(|e| Error::Remap(format!("{key} submission failed"), e.to_string()))?
+                            .event_id;
+                        trace!(?id, "poll created");
+                        context.insert(
+                            key.clone(),
+                            Value::from_object(ObjRef::new(id.to_string(), "poll".to_owned())),
+                        );
+                        yield
+                    }
+                    ObjectInner::Document { fields } => {
+                        trace!(?fields, "submitting document");
+                        let id = room
+                            .send(fields)
+                            .await
+                            .map_err(|e| Error::Remap(format!("{key} submission failed"), e.to_string()))?
+                            .event_id;
+                        trace!(?id, "document created");
+                        context.insert(
+                            key.clone(),
+                            Value::from_object(ObjRef::new(id.to_string(), "document".to_owned())),
+                        );
+                        yield
+                    }
                     ObjectInner::NewsEntry { fields } => {
                         trace!(?fields, "submitting news entry");
                         let id = room
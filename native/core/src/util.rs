@@ -1,5 +1,8 @@
+use chrono::{Duration, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Deserializer};
 
+use crate::events::UtcDateTime;
+
 /// Any value that is present is considered Some value, including null.
 /// from [serde](https://github.com/serde-rs/serde/issues/984#issuecomment-314143738)
 pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -12,8 +15,8 @@ where
 
 /// This is only used for serialize
 #[allow(clippy::trivially_copy_pass_by_ref)]
-pub(crate) fn is_zero(num: &u32) -> bool {
-    *num == 0
+pub(crate) fn is_zero<T: Default + PartialEq>(num: &T) -> bool {
+    *num == T::default()
 }
 
 /// This is only used for serialize
@@ -26,3 +29,20 @@ pub fn do_vecs_match<T: PartialEq>(a: &[T], b: &[T]) -> bool {
     let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
     matching == a.len() && matching == b.len()
 }
+
+/// Whether the two values differ once serialized, for types without `PartialEq`
+pub(crate) fn serialized_differ<T: serde::Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+/// The UTC time for the local one in `tz`, moving times that fall into a DST
+/// gap forward
+pub(crate) fn local_to_utc<T: TimeZone>(tz: &T, local: NaiveDateTime) -> Option<UtcDateTime> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|d| d.with_timezone(&Utc))
+}
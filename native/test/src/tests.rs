@@ -1,11 +1,14 @@
 mod activities;
+mod agenda;
 mod attachment;
 mod auth;
 mod backup;
 mod bookmarks;
 mod calendar;
 mod categories;
+mod documents;
 mod formatted_body;
+mod history;
 mod invitation;
 mod media_msg;
 mod msg_draft;
@@ -14,6 +17,7 @@ mod news;
 mod notifications;
 mod onboarding;
 mod pins;
+mod polls;
 mod push;
 mod reaction;
 mod redact;
@@ -109,3 +109,86 @@ async fn task_comment_activity() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn task_comment_reply_activity() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) =
+        random_user_with_template("tasks_reply_activities", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    // wait for sync to catch up
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.into_iter().next() else {
+                bail!("task list not found");
+            };
+            let Some(task) = task_list.tasks().await?.into_iter().next() else {
+                bail!("task not found");
+            };
+            Ok(task)
+        }
+    })
+    .await?;
+
+    let comments_manager = task.comments().await?;
+    let comment_id = comments_manager
+        .comment_draft()?
+        .content_text("Sunny all week".to_owned())
+        .send()
+        .await?;
+    let comment = user.wait_for_comment(comment_id.to_string(), None).await?;
+
+    let reply_id = comment
+        .reply_draft()?
+        .content_text("Not according to my app".to_owned())
+        .send()
+        .await?;
+    let reply = user.wait_for_comment(reply_id.to_string(), None).await?;
+    assert!(reply.is_reply());
+    assert_eq!(reply.reply_to_id_str(), Some(comment_id.to_string()));
+
+    // replies show up under their comment, not as top level comments
+    let fetcher_client = comments_manager.clone();
+    let threads = Retry::spawn(retry_strategy, move || {
+        let manager = fetcher_client.clone();
+        async move {
+            let threads = manager.reload().await?.threads(5).await?;
+            if threads.first().map(|t| t.reply_count()) != Some(1) {
+                bail!("reply not found");
+            }
+            Ok(threads)
+        }
+    })
+    .await?;
+    assert_eq!(threads.len(), 1);
+    let thread = &threads[0];
+    assert_eq!(thread.comment().event_id(), comment_id);
+    assert_eq!(thread.more_replies_count(), 0);
+    let replies = thread.replies();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].event_id(), reply_id);
+
+    let replies = comment.replies().await?.replies(0, 10).await?;
+    assert_eq!(replies.len(), 1);
+
+    // the author of the comment is notified about the reply
+    let activity = user.activity(reply_id.to_string()).await?;
+    assert_eq!(activity.type_str(), "commentReply");
+    assert_eq!(
+        activity.parent_comment_id_str(),
+        Some(comment_id.to_string())
+    );
+    assert_eq!(
+        activity.msg_content().map(|c| c.body()).as_deref(),
+        Some("Not according to my app")
+    );
+    assert!(activity.mentions_you());
+    let object = activity.object().expect("we have an object");
+    assert_eq!(object.type_str(), "task");
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, Days, Utc};
+use futures::StreamExt;
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Agenda Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+
+[objects]
+main_space = { type = "space", is-default = true, name = "{{ main.display_name }}’s agenda test space" }
+
+[objects.briefing]
+type = "calendar-event"
+title = "Morning briefing"
+utc_start = "{{ future(add_mins=60).as_rfc3339 }}"
+utc_end = "{{ future(add_mins=90).as_rfc3339 }}"
+
+[objects.party]
+type = "calendar-event"
+title = "Farewell party"
+utc_start = "{{ future(add_days=4).as_rfc3339 }}"
+utc_end = "{{ future(add_days=5).as_rfc3339 }}"
+
+[objects.reports-pin]
+type = "pin"
+title = "Submit security reports"
+deadline = "{{ future(add_days=3).as_rfc3339 }}"
+
+[objects.website-pin]
+type = "pin"
+title = "Acter Website"
+url = "https://acter.global"
+
+[objects.tasklist]
+type = "task-list"
+name = "Ops"
+"#;
+
+#[tokio::test]
+async fn agenda_merges_my_events_tasks_and_deadlines() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("agenda", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            if client.calendar_events().await?.len() != 2 || client.pins().await?.len() != 2 {
+                bail!("not all objects found");
+            }
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            Ok(task_list)
+        }
+    })
+    .await?;
+
+    let events = user.calendar_events().await?;
+    for event in &events {
+        let status = if event.title() == "Morning briefing" {
+            "yes"
+        } else {
+            "no"
+        };
+        event
+            .rsvps()
+            .await?
+            .rsvp_draft()?
+            .status(status.to_owned())
+            .send()
+            .await?;
+    }
+
+    let due = Utc::now().date_naive() + Days::new(2);
+    let mut draft = task_list.task_builder()?;
+    draft
+        .title("Restock shelves".to_owned())
+        .due_date(due.year(), due.month(), due.day());
+    let task_id = draft.send().await?.to_string();
+    let task = Retry::spawn(retry_strategy.clone(), || async {
+        task_list.refresh().await?.task(task_id.clone()).await
+    })
+    .await?;
+    task.assign_self().await?;
+
+    let from = Utc::now().to_rfc3339();
+    let to = (Utc::now() + Days::new(7)).to_rfc3339();
+    let fetcher_client = user.clone();
+    let (agenda_from, agenda_to) = (from.clone(), to.clone());
+    let agenda = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        let (from, to) = (agenda_from.clone(), agenda_to.clone());
+        async move {
+            let agenda = client.agenda(from, to).await?;
+            if agenda.len() != 3 {
+                bail!("agenda not complete yet: {}", agenda.len());
+            }
+            Ok(agenda)
+        }
+    })
+    .await?;
+
+    // sorted chronologically, the declined party and the pin without
+    // deadline aren't on it
+    let titles = agenda.iter().map(|i| i.title()).collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        [
+            "Morning briefing",
+            "Restock shelves",
+            "Submit security reports"
+        ]
+    );
+    assert_eq!(agenda[0].item_type(), "calendar-event");
+    assert_eq!(agenda[0].rsvp_status().as_deref(), Some("yes"));
+    assert!(agenda[1].task().is_some());
+    assert!(agenda[1].is_all_day());
+    assert!(agenda[2]
+        .pin()
+        .is_some_and(|p| p.deadline_rfc3339().is_some()));
+
+    let mut stream = user.agenda_stream(from, to)?.fuse();
+    let Some(reset) = stream.next().await else {
+        bail!("agenda stream ended");
+    };
+    assert_eq!(reset.action(), "Reset");
+    assert_eq!(reset.values().map(|v| v.len()), Some(3));
+
+    // changing my mind about the party puts it on the agenda
+    let Some(party) = events.iter().find(|e| e.title() == "Farewell party") else {
+        bail!("party not found");
+    };
+    party
+        .rsvps()
+        .await?
+        .rsvp_draft()?
+        .status("maybe".to_owned())
+        .send()
+        .await?;
+
+    // only actual changes to the agenda come through
+    let Some(diff) = stream.next().await else {
+        bail!("agenda stream ended");
+    };
+    assert_eq!(diff.action(), "Insert");
+    assert_eq!(diff.index(), Some(3));
+    assert_eq!(
+        diff.value().and_then(|i| i.rsvp_status()).as_deref(),
+        Some("maybe")
+    );
+
+    Ok(())
+}
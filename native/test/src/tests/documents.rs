@@ -0,0 +1,102 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Documents Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+
+[objects]
+main_space = { type = "space", is-default = true, name = "{{ main.display_name }}’s documents test space" }
+
+[objects.station-rules]
+type = "document"
+title = "Station rules"
+body = { body = "No weapons on the promenade" }
+"#;
+
+#[tokio::test]
+async fn document_revisions() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("doc_revisions", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let document = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(document) = client.documents().await?.pop() else {
+                bail!("document not found");
+            };
+            Ok(document)
+        }
+    })
+    .await?;
+
+    let main_space = user
+        .spaces()
+        .await?
+        .pop()
+        .expect("main space should be available");
+    assert_eq!(main_space.documents().await?.len(), 1);
+    assert_eq!(document.title(), "Station rules");
+    assert_eq!(document.revision_count(), 0);
+    assert_eq!(document.current_revision_id_str(), document.event_id_str());
+
+    // two drafts from the same revision
+    let mut first = document.edit_draft()?;
+    first.title("Station rules v2".to_owned());
+    let mut second = document.edit_draft()?;
+    second.body_text("No weapons anywhere".to_owned());
+
+    let revision_id = first.send().await?;
+    let document = Retry::spawn(retry_strategy.clone(), || async {
+        let document = document.refresh().await?;
+        if document.revision_count() != 1 {
+            bail!("revision not applied yet");
+        }
+        Ok(document)
+    })
+    .await?;
+    assert_eq!(document.title(), "Station rules v2");
+    assert_eq!(document.current_revision_id_str(), revision_id.to_string());
+    assert!(!document.has_conflict());
+
+    // the second one is based on an outdated revision
+    assert!(second.send().await.is_err());
+
+    let revisions = document.revisions().await?;
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(
+        revisions[0].parent_revision_id_str(),
+        document.event_id_str()
+    );
+    assert_eq!(revisions[0].title(), "Station rules v2");
+
+    // bring back the original
+    document.restore(document.event_id_str()).await?;
+    let document = Retry::spawn(retry_strategy.clone(), || async {
+        let document = document.refresh().await?;
+        if document.revision_count() != 2 {
+            bail!("restore not applied yet");
+        }
+        Ok(document)
+    })
+    .await?;
+    assert_eq!(document.title(), "Station rules");
+    let revisions = document.revisions().await?;
+    assert_eq!(
+        revisions[0].restored_from_id_str(),
+        Some(document.event_id_str())
+    );
+
+    Ok(())
+}
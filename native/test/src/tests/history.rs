@@ -0,0 +1,124 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "History Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+
+[objects]
+main_space = { type = "space", is-default = true, name = "{{ main.display_name }}’s history test space" }
+
+[objects.acter-website-pin]
+type = "pin"
+title = "Acter Website"
+url = "https://acter.global"
+"#;
+
+#[tokio::test]
+async fn pin_change_history() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("pin_history", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let pin = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(pin) = client.pins().await?.pop() else {
+                bail!("pin not found");
+            };
+            Ok(pin)
+        }
+    })
+    .await?;
+
+    let history = user.object_history(pin.event_id_str()).await?;
+    assert!(history.entries().is_empty());
+
+    pin.update_builder()?
+        .title("Acter Homepage".to_owned())
+        .send()
+        .await?;
+    let update_id = pin.update_builder()?.unset_url().send().await?;
+    user.wait_for(update_id.to_string(), None).await?;
+
+    let history = Retry::spawn(retry_strategy, || async {
+        let history = user.object_history(pin.event_id_str()).await?;
+        if history.entries().len() != 2 {
+            bail!("updates not in the history yet");
+        }
+        Ok(history)
+    })
+    .await?;
+    assert_eq!(history.creator_id_str(), user.user_id()?.to_string());
+
+    let entries = history.entries();
+    assert_eq!(entries[0].changed_fields(), ["title"]);
+    let title = entries[0].changes().pop().expect("title change exists");
+    assert_eq!(title.before_json().as_deref(), Some("\"Acter Website\""));
+    assert_eq!(title.after_json().as_deref(), Some("\"Acter Homepage\""));
+
+    assert_eq!(entries[1].event_id_str(), update_id.to_string());
+    let url = entries[1].changes().pop().expect("url change exists");
+    assert_eq!(url.field(), "url");
+    assert_eq!(url.after_json(), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pin_revert_to_version() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("pin_revert", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let pin = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(pin) = client.pins().await?.pop() else {
+                bail!("pin not found");
+            };
+            Ok(pin)
+        }
+    })
+    .await?;
+
+    // someone messes it up
+    let update_id = pin
+        .update_builder()?
+        .title("Spam".to_owned())
+        .unset_url()
+        .send()
+        .await?;
+    user.wait_for(update_id.to_string(), None).await?;
+    let pin = pin.refresh().await?;
+    assert_eq!(pin.title(), "Spam");
+
+    pin.revert_builder(0).await?.send().await?;
+    let pin = Retry::spawn(retry_strategy, || async {
+        let pin = pin.refresh().await?;
+        if pin.title() != "Acter Website" {
+            bail!("revert not applied yet");
+        }
+        Ok(pin)
+    })
+    .await?;
+    assert_eq!(pin.url().as_deref(), Some("https://acter.global"));
+
+    // the revert is part of the history, too
+    let history = user.object_history(pin.event_id_str()).await?;
+    assert_eq!(history.entries().len(), 2);
+
+    Ok(())
+}
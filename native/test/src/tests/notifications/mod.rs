@@ -4,5 +4,6 @@ mod event;
 mod likes;
 mod news;
 mod pins;
+mod polls;
 mod references;
 mod tasks;
@@ -0,0 +1,91 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use acter::api::SubscriptionStatus;
+
+use crate::utils::random_users_with_random_space_under_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Poll Notifications Setup Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.secret-ballot]
+type = "poll"
+question = "Who should lead the station?"
+anonymous = true
+answers = [
+    { id = "kira", text = "Kira" },
+    { id = "odo", text = "Odo" },
+]
+"#;
+
+#[tokio::test]
+async fn anonymous_poll_vote_hides_voter() -> Result<()> {
+    let (users, _sync_states, space_id, _engine) =
+        random_users_with_random_space_under_template("anonVote", 2, TMPL).await?;
+
+    let first = users.first().expect("exists");
+    let second_user = &users[1];
+
+    // wait for sync to catch up
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(30);
+    let fetcher_client = second_user.clone();
+    let poll = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(poll) = client.polls().await?.pop() else {
+                bail!("poll not found");
+            };
+            Ok(poll)
+        }
+    })
+    .await?;
+    assert!(poll.is_anonymous());
+
+    // ensure we are expected to see these notifications
+    let notif_settings = first.notification_settings().await?;
+    let obj_id = poll.event_id_str();
+    notif_settings
+        .subscribe_object_push(obj_id.clone(), None)
+        .await
+        .expect("setting notifications subscription works");
+    // ensure this has been locally synced
+    let fetcher_client = notif_settings.clone();
+    Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        let obj_id = obj_id.clone();
+        async move {
+            if client.object_push_subscription_status(obj_id, None).await?
+                != SubscriptionStatus::Subscribed
+            {
+                bail!("not yet subscribed");
+            }
+            Ok(())
+        }
+    })
+    .await?;
+
+    let mut draft = poll.votes().await?.vote_draft()?;
+    draft.add_answer("odo".to_owned());
+    let notification_ev = draft.send().await?;
+
+    let notification_item = first
+        .get_notification_item(space_id.to_string(), notification_ev.to_string())
+        .await?;
+    assert_eq!(notification_item.push_style(), "pollVote");
+    assert_eq!(notification_item.sender().user_id(), "");
+    assert_eq!(notification_item.sender().display_name(), None);
+
+    first.wait_for(notification_ev.to_string(), None).await?;
+    let activity = first.activity(notification_ev.to_string()).await?;
+    assert_eq!(activity.sender_id_str(), "");
+
+    Ok(())
+}
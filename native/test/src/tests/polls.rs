@@ -0,0 +1,115 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Polls Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+
+[objects]
+main_space = { type = "space", is-default = true, name = "{{ main.display_name }}’s polls test space" }
+
+[objects.lunch-poll]
+type = "poll"
+question = "Where to have lunch?"
+answers = [
+    { id = "quarks", text = "Quark’s" },
+    { id = "replimat", text = "Replimat" },
+]
+"#;
+
+#[tokio::test]
+async fn poll_votes_are_counted() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("poll_votes", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let poll = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(poll) = client.polls().await?.pop() else {
+                bail!("poll not found");
+            };
+            Ok(poll)
+        }
+    })
+    .await?;
+
+    let main_space = user
+        .spaces()
+        .await?
+        .pop()
+        .expect("main space should be available");
+    assert_eq!(main_space.polls().await?.len(), 1);
+    assert_eq!(poll.question(), "Where to have lunch?");
+    assert_eq!(poll.answer_ids(), ["quarks", "replimat"]);
+    assert!(!poll.is_multiple_choice());
+    assert!(!poll.is_closed());
+
+    let votes = poll.votes().await?;
+    assert!(!votes.has_votes());
+    let mut draft = votes.vote_draft()?;
+    draft.add_answer("quarks".to_owned());
+    draft.send().await?;
+
+    let votes = Retry::spawn(retry_strategy.clone(), || async {
+        let votes = poll.votes().await?;
+        if votes.total_voter_count() != 1 {
+            bail!("vote not counted yet");
+        }
+        Ok(votes)
+    })
+    .await?;
+    assert_eq!(votes.count_for("quarks".to_owned()), 1);
+    assert_eq!(votes.my_vote().await?, ["quarks"]);
+    assert_eq!(
+        votes.voters_for("quarks".to_owned()).await?,
+        [user.user_id()?]
+    );
+
+    // changing my mind replaces my vote
+    let mut draft = votes.vote_draft()?;
+    draft.add_answer("replimat".to_owned());
+    draft.send().await?;
+
+    let votes = Retry::spawn(retry_strategy.clone(), || async {
+        let votes = poll.votes().await?;
+        if votes.count_for("replimat".to_owned()) != 1 {
+            bail!("changed vote not counted yet");
+        }
+        Ok(votes)
+    })
+    .await?;
+    assert_eq!(votes.total_voter_count(), 1);
+    assert_eq!(votes.count_for("quarks".to_owned()), 0);
+
+    // votes after closing don’t count
+    poll.update_builder()?.close_now().send().await?;
+    let poll = Retry::spawn(retry_strategy.clone(), || async {
+        let poll = poll.refresh().await?;
+        if !poll.is_closed() {
+            bail!("poll not closed yet");
+        }
+        Ok(poll)
+    })
+    .await?;
+    let mut draft = poll.votes().await?.vote_draft()?;
+    draft.add_answer("quarks".to_owned());
+    let late_vote = draft.send().await?;
+    user.wait_for(late_vote.to_string(), None).await?;
+
+    let votes = poll.votes().await?;
+    assert_eq!(votes.total_voter_count(), 1);
+    assert_eq!(votes.my_vote().await?, ["replimat"]);
+
+    Ok(())
+}
@@ -1,4 +1,14 @@
+mod checklists;
+mod dependencies;
+mod due;
+mod ical;
 mod invitations;
+mod moving;
+mod recurring;
+mod subtasks;
+mod time_tracking;
+mod trash;
+mod workflow;
 
 use acter::testing::wait_for;
 use acter_core::models::ActerModel;
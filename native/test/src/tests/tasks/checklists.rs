@@ -0,0 +1,99 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Checklist Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Camp"
+
+[objects.pack]
+type = "task"
+title = "Pack for camp"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+checklist = [
+    { id = "tent", text = "Tent" },
+    { id = "mat", text = "Sleeping mat", done = true },
+    { id = "stove", text = "Stove" },
+    { id = "lamp", text = "Lamp" },
+]
+"#;
+
+#[tokio::test]
+async fn checklist_drives_progress() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_checklist", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let task_lists = client.task_lists().await?;
+            let Some(task_list) = task_lists.first() else {
+                bail!("task list not found");
+            };
+            let Some(task) = task_list.tasks().await?.pop() else {
+                bail!("task not found");
+            };
+            Ok(task)
+        }
+    })
+    .await?;
+
+    let checklist = task.checklist();
+    assert_eq!(checklist.len(), 4);
+    assert_eq!(checklist[1].text(), "Sleeping mat");
+    assert!(checklist[1].is_done());
+    assert_eq!(task.progress_percent(), Some(25));
+
+    // two people working on the same list at once
+    let mut first = task.update_builder()?;
+    first.toggle_checklist_item("tent".to_owned(), true);
+    let mut second = task.update_builder()?;
+    second.remove_checklist_item("lamp".to_owned());
+    let torch = second.add_checklist_item_after("Torch".to_owned(), "mat".to_owned());
+    first.send().await?;
+    second.send().await?;
+
+    let task = Retry::spawn(retry_strategy.clone(), || async {
+        let task = task.refresh().await?;
+        if task.checklist().iter().any(|i| i.id() == "lamp") {
+            bail!("not yet updated");
+        }
+        Ok(task)
+    })
+    .await?;
+
+    let ids = task.checklist().iter().map(|i| i.id()).collect::<Vec<_>>();
+    assert_eq!(ids, ["tent", "mat", torch.as_str(), "stove"]);
+    assert_eq!(task.progress_percent(), Some(50));
+
+    // checking off the rest completes the task
+    let mut updater = task.update_builder()?;
+    updater.toggle_checklist_item(torch, true);
+    updater.toggle_checklist_item("stove".to_owned(), true);
+    updater.send().await?;
+
+    Retry::spawn(retry_strategy, || async {
+        if !task.refresh().await?.is_done() {
+            bail!("not yet done");
+        }
+        Ok(())
+    })
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Dependencies Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Renovation"
+
+[objects.plaster]
+type = "task"
+title = "Plaster the walls"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+
+[objects.paint]
+type = "task"
+title = "Paint the walls"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+blocked_by = ["{{ plaster.id }}"]
+"#;
+
+#[tokio::test]
+async fn blocked_tasks_unblock() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_deps", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let task_lists = client.task_lists().await?;
+            let Some(task_list) = task_lists.first() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 2 {
+                bail!("tasks not found");
+            }
+            Ok(task_list.clone())
+        }
+    })
+    .await?;
+
+    let tasks = task_list.tasks().await?;
+    let plaster = tasks
+        .iter()
+        .find(|t| t.title() == "Plaster the walls")
+        .expect("plaster task exists");
+    let paint = tasks
+        .iter()
+        .find(|t| t.title() == "Paint the walls")
+        .expect("paint task exists");
+
+    assert!(paint.is_blocked());
+    assert_eq!(paint.blocked_by_ids(), [plaster.event_id_str()]);
+    assert_eq!(plaster.blocks_ids(), [paint.event_id_str()]);
+    assert!(!plaster.is_blocked());
+
+    let event_id = plaster.update_builder()?.mark_done().send().await?;
+
+    Retry::spawn(retry_strategy, || async {
+        if paint.refresh().await?.is_blocked() {
+            bail!("still blocked");
+        }
+        Ok(())
+    })
+    .await?;
+
+    // completing it is still an activity of its own
+    let activity = user.activity(event_id.to_string()).await?;
+    assert_eq!(activity.type_str(), "taskComplete");
+    let follow_ups = activity.follow_ups();
+    assert_eq!(follow_ups.len(), 1);
+    assert_eq!(follow_ups[0].type_str(), "taskBlockerComplete");
+    let unblocked = follow_ups[0].unblocked();
+    assert_eq!(unblocked.len(), 1);
+    assert_eq!(unblocked[0].object_id_str(), paint.event_id_str());
+
+    Ok(())
+}
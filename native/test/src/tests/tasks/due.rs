@@ -0,0 +1,111 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use futures::StreamExt;
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Due Lists Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Ops"
+"#;
+
+#[tokio::test]
+async fn due_lists_follow_my_tasks() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_due_lists", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            Ok(task_list)
+        }
+    })
+    .await?;
+
+    let today = Local::now().date_naive();
+    let yesterday = today - Days::new(1);
+    let far_away = today + Days::new(30);
+    let ymd = |d: NaiveDate| (d.year(), d.month(), d.day());
+
+    let mut overdue_stream = user.subscribe_my_overdue_tasks_stream().fuse();
+    let mut task_ids = vec![];
+    for (title, due) in [
+        ("Restock shelves", yesterday),
+        ("Call supplier", today),
+        ("Plan inventory", far_away),
+    ] {
+        let (year, month, day) = ymd(due);
+        let mut draft = task_list.task_builder()?;
+        draft.title(title.to_owned()).due_date(year, month, day);
+        task_ids.push(draft.send().await?.to_string());
+    }
+    for task_id in &task_ids {
+        let task = Retry::spawn(retry_strategy.clone(), || async {
+            task_list.refresh().await?.task(task_id.clone()).await
+        })
+        .await?;
+        task.assign_self().await?;
+    }
+
+    let fetcher_client = user.clone();
+    Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            if client.my_open_tasks().await?.len() != 3 {
+                bail!("not all tasks assigned yet");
+            }
+            Ok(())
+        }
+    })
+    .await?;
+
+    let overdue = user.my_overdue_tasks().await?;
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].title(), "Restock shelves");
+
+    let due_today = user.my_tasks_due_today().await?;
+    assert_eq!(due_today.len(), 1);
+    assert_eq!(due_today[0].title(), "Call supplier");
+
+    let this_week = user.my_tasks_due_this_week().await?;
+    assert!(this_week.iter().any(|t| t.title() == "Call supplier"));
+    assert!(!this_week.iter().any(|t| t.title() == "Plan inventory"));
+
+    // the watcher let us know about the new overdue task
+    assert_eq!(overdue_stream.next().await, Some(true));
+
+    // once done, it is off the list
+    let restock = overdue[0].clone();
+    restock.update_builder()?.mark_done().send().await?;
+    let fetcher_client = user.clone();
+    Retry::spawn(retry_strategy, move || {
+        let client = fetcher_client.clone();
+        async move {
+            if !client.my_overdue_tasks().await?.is_empty() {
+                bail!("task still overdue");
+            }
+            Ok(())
+        }
+    })
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task iCal Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Station maintenance"
+
+[objects.airlock]
+type = "task"
+title = "Repair airlock"
+due_date = "2024-05-01"
+priority = 2
+progress_percent = 50
+categories = ["engineering"]
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+"#;
+
+const IMPORT_ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Other Tool//EN\r
+BEGIN:VTODO\r
+UID:replicator@example.org\r
+DTSTAMP:20240301T120000Z\r
+SUMMARY:Calibrate replicators\r
+DESCRIPTION:All of them on the promenade\r
+DUE;VALUE=DATE:20240610\r
+PRIORITY:9\r
+CATEGORIES:food,engineering\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+#[tokio::test]
+async fn task_list_ical_export_and_import() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_ical", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 1 {
+                bail!("task not found");
+            }
+            Ok(task_list)
+        }
+    })
+    .await?;
+
+    let ics = task_list.ical().await?;
+    assert!(ics.contains("BEGIN:VTODO"), "{ics}");
+    assert!(ics.contains("SUMMARY:Repair airlock"), "{ics}");
+    assert!(ics.contains("DUE;VALUE=DATE:20240501"), "{ics}");
+    assert!(ics.contains("PRIORITY:2"), "{ics}");
+    assert!(ics.contains("PERCENT-COMPLETE:50"), "{ics}");
+    assert!(ics.contains("STATUS:IN-PROCESS"), "{ics}");
+    assert!(ics.contains("CATEGORIES:engineering"), "{ics}");
+
+    let imported = task_list.import_ical(IMPORT_ICS.to_owned()).await?;
+    assert_eq!(imported.len(), 1);
+
+    let fetcher_list = task_list.clone();
+    let task = Retry::spawn(retry_strategy, move || {
+        let task_list = fetcher_list.clone();
+        let task_id = imported[0].clone();
+        async move {
+            let task_list = task_list.refresh().await?;
+            task_list.task(task_id).await
+        }
+    })
+    .await?;
+    assert_eq!(task.title(), "Calibrate replicators");
+    assert_eq!(
+        task.description().map(|d| d.body()).as_deref(),
+        Some("All of them on the promenade")
+    );
+    assert_eq!(task.due_date().as_deref(), Some("2024-06-10"));
+    assert_eq!(task.priority(), Some(9));
+    assert_eq!(task.categories(), vec!["food", "engineering"]);
+    assert!(!task.is_done());
+
+    Ok(())
+}
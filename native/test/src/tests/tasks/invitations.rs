@@ -378,3 +378,99 @@ async fn can_invite_after_unassign_task() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn assign_task_to_invited_member() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (users, _sync_states, space_id, _engine) =
+        random_users_with_random_space_under_template("i0t", 2, TMPL).await?;
+
+    let first = users.first().expect("exists");
+    let second_user = &users[1];
+
+    // wait for sync to catch up
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = first.clone();
+    let obj_entry = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let entries = client.task_lists().await?;
+            if entries.is_empty() {
+                bail!("no task lists not found");
+            }
+            let tasks = entries[0].tasks().await?;
+            let Some(task) = tasks.first() else {
+                bail!("no tasks found")
+            };
+            Ok(task.clone())
+        }
+    })
+    .await?;
+
+    assert!(obj_entry.can_assign_others().await?);
+
+    let manager = obj_entry.invitations().await?;
+    let stream = manager.subscribe_stream();
+    let mut stream = stream.fuse();
+    let second_user_str = second_user.user_id()?.to_string();
+    manager.invite(second_user_str.clone()).await?;
+    let _ = stream.next().await; // await the invite being sent
+
+    // the coordinator assigns them directly
+    let event_id = manager.assign(second_user_str.clone()).await?;
+
+    let fetcher_client = second_user.clone();
+    let task = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let entries = client.task_lists().await?;
+            if entries.is_empty() {
+                bail!("no task lists not found");
+            }
+            let tasks = entries[0].tasks().await?;
+            let Some(task) = tasks.first() else {
+                bail!("no tasks found")
+            };
+            if !task.is_assigned_to_me() {
+                bail!("not yet assigned");
+            }
+            Ok(task.clone())
+        }
+    })
+    .await?;
+
+    assert!(task.assignees_str().contains(&second_user_str));
+    let invites = task.invitations().await?;
+    assert!(!invites.is_invited());
+    assert!(invites.has_accepted());
+
+    // it shows up in my open tasks
+    let my_tasks = second_user.my_open_tasks().await?;
+    assert_eq!(my_tasks.len(), 1);
+
+    // and the assignee is notified
+    let notification = second_user
+        .get_notification_item(space_id.to_string(), event_id)
+        .await?;
+    assert_eq!(notification.push_style(), "taskAssign");
+    let parent = notification.parent().expect("parent should be available");
+    assert_eq!(parent.title().as_deref(), Some("Scroll news"));
+    assert_eq!(notification.whom(), vec![second_user_str.clone()]);
+    assert!(notification.mentions_you());
+
+    // a regular member can’t assign others
+    assert!(!task.can_assign_others().await?);
+    assert!(task.assign(first.user_id()?.to_string()).await.is_err());
+
+    // but the coordinator can unassign them again
+    obj_entry.unassign(second_user_str).await?;
+    Retry::spawn(retry_strategy, || async {
+        if task.refresh().await?.is_assigned_to_me() {
+            bail!("still assigned");
+        }
+        Ok(())
+    })
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Moving Tasks Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.groceries]
+type = "task-list"
+name = "Groceries"
+
+[objects.chores]
+type = "task-list"
+name = "Chores"
+
+[objects.milk]
+type = "task"
+title = "Buy milk"
+"m.relates_to" = { event_id = "{{ groceries.id }}" }
+
+[objects.laundry]
+type = "task"
+title = "Do the laundry"
+"m.relates_to" = { event_id = "{{ groceries.id }}" }
+"#;
+
+#[tokio::test]
+async fn task_moves_between_lists() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_move", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let (groceries, chores) = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let task_lists = client.task_lists().await?;
+            let Some(groceries) = task_lists.iter().find(|l| l.name() == "Groceries") else {
+                bail!("groceries not found");
+            };
+            let Some(chores) = task_lists.iter().find(|l| l.name() == "Chores") else {
+                bail!("chores not found");
+            };
+            if groceries.tasks().await?.len() != 2 {
+                bail!("tasks not found");
+            }
+            Ok((groceries.clone(), chores.clone()))
+        }
+    })
+    .await?;
+
+    let laundry = groceries
+        .tasks()
+        .await?
+        .into_iter()
+        .find(|t| t.title() == "Do the laundry")
+        .expect("laundry task exists");
+
+    let mut updater = laundry.update_builder()?;
+    updater.task_list(chores.event_id_str())?;
+    let event_id = updater.send().await?;
+
+    Retry::spawn(retry_strategy, || async {
+        if chores.refresh().await?.tasks().await?.len() != 1 {
+            bail!("task not yet moved");
+        }
+        Ok(())
+    })
+    .await?;
+
+    let laundry = laundry.refresh().await?;
+    assert_eq!(laundry.task_list_id_str(), chores.event_id_str());
+
+    let groceries = groceries.refresh().await?;
+    let tasks = groceries.tasks().await?;
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].title(), "Buy milk");
+    assert_eq!(*groceries.tasks_stats()?.tasks_count(), 1);
+    assert_eq!(*chores.refresh().await?.tasks_stats()?.tasks_count(), 1);
+
+    let activity = user.activity(event_id.to_string()).await?;
+    assert_eq!(activity.type_str(), "taskMove");
+    let from = activity.moved_from().expect("origin is known");
+    assert_eq!(from.object_id_str(), groceries.event_id_str());
+    let to = activity.moved_to().expect("target is known");
+    assert_eq!(to.object_id_str(), chores.event_id_str());
+
+    Ok(())
+}
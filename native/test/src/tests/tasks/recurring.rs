@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_users_with_random_space_under_template;
+use acter::{Client, Task};
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Recurring Task Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Chores"
+
+[objects.plants]
+type = "task"
+title = "Water the plants"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+recurrence = "FREQ=WEEKLY"
+"#;
+
+async fn fetch_task(client: &Client) -> Result<Task> {
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    Retry::spawn(retry_strategy, || async {
+        let task_lists = client.task_lists().await?;
+        let Some(task_list) = task_lists.first() else {
+            bail!("task list not found");
+        };
+        let Some(task) = task_list.tasks().await?.first().cloned() else {
+            bail!("task not found");
+        };
+        Ok(task)
+    })
+    .await
+}
+
+#[tokio::test]
+async fn completing_concurrently_creates_one_next_instance() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (users, sync_states, _space_id, _engine) =
+        random_users_with_random_space_under_template("rec_task", 2, TMPL).await?;
+    for sync_state in &sync_states {
+        sync_state.await_has_synced_history().await?;
+    }
+
+    let first = fetch_task(&users[0]).await?;
+    let second = fetch_task(&users[1]).await?;
+    assert!(first.is_recurring());
+
+    // both complete it before seeing the other one did
+    let mut first_update = first.update_builder()?;
+    let mut second_update = second.update_builder()?;
+    let (a, b) = futures::join!(
+        first_update.mark_done().send(),
+        second_update.mark_done().send()
+    );
+    a?;
+    b?;
+
+    // everyone agrees on the single instance that follows
+    let retry_strategy = FibonacciBackoff::from_millis(250).map(jitter).take(10);
+    for user in &users {
+        Retry::spawn(retry_strategy.clone(), || async {
+            let task_lists = user.task_lists().await?;
+            let Some(task_list) = task_lists.first() else {
+                bail!("task list not found");
+            };
+            let tasks = task_list.tasks().await?;
+            let Some(next_id) = tasks
+                .iter()
+                .find(|t| t.event_id_str() == first.event_id_str())
+                .and_then(|t| t.next_instance_id_str())
+            else {
+                bail!("no next instance yet");
+            };
+            if tasks.len() != 2 {
+                bail!(
+                    "expected the task and its next instance, got {}",
+                    tasks.len()
+                );
+            }
+            let Some(next) = tasks.iter().find(|t| t.event_id_str() == next_id) else {
+                bail!("next instance not listed");
+            };
+            if next.is_done() || next.title() != "Water the plants" {
+                bail!("next instance isn’t fresh");
+            }
+            Ok(())
+        })
+        .await?;
+    }
+    Ok(())
+}
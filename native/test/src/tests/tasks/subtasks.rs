@@ -0,0 +1,111 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Subtasks Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Moving house"
+
+[objects.parent]
+type = "task"
+title = "Pack everything"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+
+[objects.kitchen]
+type = "task"
+title = "Pack the kitchen"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+parent_task = "{{ parent.id }}"
+
+[objects.books]
+type = "task"
+title = "Pack the books"
+progress_percent = 100
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+parent_task = "{{ parent.id }}"
+"#;
+
+#[tokio::test]
+async fn subtasks_roll_up() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("subtasks", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let parent = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let task_lists = client.task_lists().await?;
+            let Some(task_list) = task_lists.first() else {
+                bail!("task list not found");
+            };
+            let Some(parent) = task_list
+                .tasks()
+                .await?
+                .into_iter()
+                .find(|t| !t.is_subtask())
+            else {
+                bail!("parent task not found");
+            };
+            if parent.subtasks_count() != 2 {
+                bail!("subtasks not yet counted");
+            }
+            Ok(parent)
+        }
+    })
+    .await?;
+
+    assert_eq!(parent.done_subtasks_count(), 1);
+    assert_eq!(parent.open_subtasks_count(), 1);
+
+    let subtasks = parent.subtasks().await?;
+    assert_eq!(subtasks.len(), 2);
+    let kitchen = subtasks
+        .iter()
+        .find(|t| t.title() == "Pack the kitchen")
+        .expect("kitchen subtask exists");
+    assert_eq!(kitchen.parent_task_id_str(), Some(parent.event_id_str()));
+
+    // finishing the kitchen rolls up to the parent
+    kitchen.update_builder()?.mark_done().send().await?;
+    Retry::spawn(retry_strategy.clone(), || async {
+        let parent = parent.refresh().await?;
+        if parent.done_subtasks_count() != 2 {
+            bail!("subtask not yet done");
+        }
+        Ok(())
+    })
+    .await?;
+
+    // moving it up to the top level removes it from the parent
+    let mut updater = kitchen.update_builder()?;
+    updater.unset_parent_task();
+    updater.send().await?;
+    Retry::spawn(retry_strategy, || async {
+        let parent = parent.refresh().await?;
+        if parent.subtasks_count() != 1 {
+            bail!("subtask not yet moved");
+        }
+        Ok(())
+    })
+    .await?;
+
+    let parent = parent.refresh().await?;
+    assert_eq!(parent.subtasks().await?.len(), 1);
+    assert_eq!(parent.open_subtasks_count(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Time Tracking Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Food bank"
+
+[objects.sorting]
+type = "task"
+title = "Sort donations"
+estimated_minutes = 120
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+
+[objects.delivery]
+type = "task"
+title = "Deliver boxes"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+"#;
+
+#[tokio::test]
+async fn time_logged_sums_up_per_list() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_time_tracking", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 2 {
+                bail!("not all tasks found");
+            }
+            Ok(task_list)
+        }
+    })
+    .await?;
+
+    let tasks = task_list.tasks().await?;
+    let sorting = tasks
+        .iter()
+        .find(|t| t.title() == "Sort donations")
+        .expect("sorting task exists");
+    let delivery = tasks
+        .iter()
+        .find(|t| t.title() == "Deliver boxes")
+        .expect("delivery task exists");
+    assert_eq!(sorting.estimated_minutes(), Some(120));
+    assert_eq!(delivery.estimated_minutes(), None);
+
+    let sorting_time = sorting.time_tracking().await?;
+    assert_eq!(sorting_time.total_minutes(), 0);
+    let subscriber = sorting_time.subscribe();
+
+    sorting_time.log_draft()?.minutes(90).send().await?;
+    sorting_time
+        .log_draft()?
+        .minutes(45)
+        .date(2024, 3, 1)
+        .note("inventory".to_owned())
+        .send()
+        .await?;
+    delivery
+        .time_tracking()
+        .await?
+        .log_draft()?
+        .minutes(30)
+        .send()
+        .await?;
+
+    Retry::spawn(retry_strategy.clone(), || async {
+        if subscriber.is_empty() {
+            bail!("not been alerted to reload");
+        }
+        Ok(())
+    })
+    .await?;
+
+    let list_time = Retry::spawn(retry_strategy.clone(), || async {
+        let list_time = task_list.time_tracking().await?;
+        if list_time.entries_count() != 3 {
+            bail!("not all time logs arrived");
+        }
+        Ok(list_time)
+    })
+    .await?;
+
+    let me = user.user_id()?.to_string();
+    assert_eq!(list_time.total_minutes(), 165);
+    assert_eq!(list_time.users_str(), [me.clone()]);
+    assert_eq!(list_time.minutes_by(me.clone())?, 165);
+    assert_eq!(list_time.time_logs().await?.len(), 3);
+    assert!(list_time.log_draft().is_err());
+
+    let sorting_time = sorting_time.reload().await?;
+    assert_eq!(sorting_time.total_minutes(), 135);
+    let logs = sorting_time.time_logs().await?;
+    let dated = logs
+        .iter()
+        .find(|l| l.minutes() == 45)
+        .expect("dated log exists");
+    assert_eq!(dated.date().as_deref(), Some("2024-03-01"));
+    assert_eq!(dated.note().as_deref(), Some("inventory"));
+
+    Ok(())
+}
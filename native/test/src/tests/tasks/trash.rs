@@ -0,0 +1,121 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Trash Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Errands"
+
+[objects.post]
+type = "task"
+title = "Bring letters to the post office"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+"#;
+
+#[tokio::test]
+async fn trash_and_restore_task() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_trash", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let errands = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let task_lists = client.task_lists().await?;
+            let Some(task_list) = task_lists.first() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 1 {
+                bail!("task not found");
+            }
+            Ok(task_list.clone())
+        }
+    })
+    .await?;
+
+    let space = user.spaces().await?.first().cloned().expect("space exists");
+
+    // the inbox is created on first access and found afterwards
+    let inbox = space.inbox_task_list().await?;
+    assert!(inbox.is_inbox());
+    assert_eq!(
+        space.inbox_task_list().await?.event_id_str(),
+        inbox.event_id_str()
+    );
+
+    let task = errands.tasks().await?.pop().expect("task exists");
+    assert!(!task.is_trashed());
+    task.trash().await?;
+
+    let trash = space.trash_task_list().await?;
+    assert!(trash.is_trash());
+
+    Retry::spawn(retry_strategy.clone(), || async {
+        if !task.refresh().await?.is_trashed() {
+            bail!("not yet trashed");
+        }
+        Ok(())
+    })
+    .await?;
+    let task = task.refresh().await?;
+    assert_eq!(task.task_list_id_str(), trash.event_id_str());
+    assert!(errands.refresh().await?.tasks().await?.is_empty());
+
+    // and back to where it came from
+    task.restore().await?;
+    Retry::spawn(retry_strategy, || async {
+        if task.refresh().await?.is_trashed() {
+            bail!("still trashed");
+        }
+        Ok(())
+    })
+    .await?;
+    let task = task.refresh().await?;
+    assert_eq!(task.task_list_id_str(), errands.event_id_str());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quick_capture_lands_in_the_inbox() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_inbox", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let space = user.spaces().await?.first().cloned().expect("space exists");
+    let task_id = space
+        .quick_capture_task("Order more paint".to_owned())
+        .await?;
+    let inbox = space.inbox_task_list().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let task = Retry::spawn(retry_strategy, || async {
+        let tasks = inbox.refresh().await?.tasks().await?;
+        let Some(task) = tasks
+            .into_iter()
+            .find(|t| t.event_id_str() == task_id.to_string())
+        else {
+            bail!("captured task not found");
+        };
+        Ok(task)
+    })
+    .await?;
+    assert_eq!(task.title(), "Order more paint");
+    assert_eq!(task.task_list_id_str(), inbox.event_id_str());
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use anyhow::{bail, Result};
+use tokio_retry::{
+    strategy::{jitter, FibonacciBackoff},
+    Retry,
+};
+
+use crate::utils::random_user_with_template;
+
+const TMPL: &str = r#"
+version = "0.1"
+name = "Task Workflow Template"
+
+[inputs]
+main = { type = "user", is-default = true, required = true, description = "The starting user" }
+space = { type = "space", is-default = true, required = true, description = "The main user" }
+
+[objects.tasklist]
+type = "task-list"
+name = "Website relaunch"
+states = [
+    { id = "backlog", name = "Backlog" },
+    { id = "doing", name = "In progress" },
+    { id = "review", name = "Review" },
+    { id = "done", name = "Done", done = true },
+]
+
+[objects.texts]
+type = "task"
+title = "Write texts"
+state = "doing"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+
+[objects.photos]
+type = "task"
+title = "Take photos"
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+
+[objects.domain]
+type = "task"
+title = "Register domain"
+progress_percent = 100
+"m.relates_to" = { event_id = "{{ tasklist.id }}" }
+"#;
+
+#[tokio::test]
+async fn tasks_grouped_by_workflow_state() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) = random_user_with_template("task_workflow", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 3 {
+                bail!("not all tasks found");
+            }
+            Ok(task_list)
+        }
+    })
+    .await?;
+
+    let states = task_list.states();
+    let names = states.iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["Backlog", "In progress", "Review", "Done"]);
+
+    let titles = |groups: &[acter::TaskStateGroup]| {
+        groups
+            .iter()
+            .map(|g| g.tasks().iter().map(|t| t.title()).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    let groups = task_list.tasks_by_state().await?;
+    assert_eq!(
+        titles(&groups),
+        [
+            vec!["Take photos"],
+            vec!["Write texts"],
+            vec![],
+            vec!["Register domain"]
+        ]
+    );
+
+    // moving into the done column completes the task
+    let texts = task_list
+        .tasks()
+        .await?
+        .into_iter()
+        .find(|t| t.title() == "Write texts")
+        .expect("texts task exists");
+    let mut updater = texts.update_builder()?;
+    updater.state(Box::new(states[3].clone()));
+    updater.send().await?;
+
+    let texts = Retry::spawn(retry_strategy.clone(), || async {
+        let texts = texts.refresh().await?;
+        if !texts.is_done() {
+            bail!("not yet done");
+        }
+        Ok(texts)
+    })
+    .await?;
+    assert_eq!(texts.state_id().as_deref(), Some("done"));
+
+    // and moving it back reopens it
+    let mut updater = texts.update_builder()?;
+    updater.state(Box::new(states[2].clone()));
+    updater.send().await?;
+
+    Retry::spawn(retry_strategy.clone(), || async {
+        if texts.refresh().await?.is_done() {
+            bail!("not yet reopened");
+        }
+        Ok(())
+    })
+    .await?;
+
+    let task_list = task_list.refresh().await?;
+    let groups = task_list.tasks_by_state().await?;
+    assert_eq!(
+        titles(&groups),
+        [
+            vec!["Take photos"],
+            vec![],
+            vec!["Write texts"],
+            vec!["Register domain"]
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dropping_a_state_change_keeps_the_progress() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) =
+        random_user_with_template("task_workflow_unset", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let fetcher_client = user.clone();
+    let task_list = Retry::spawn(retry_strategy.clone(), move || {
+        let client = fetcher_client.clone();
+        async move {
+            let Some(task_list) = client.task_lists().await?.pop() else {
+                bail!("task list not found");
+            };
+            if task_list.tasks().await?.len() != 3 {
+                bail!("not all tasks found");
+            }
+            Ok(task_list)
+        }
+    })
+    .await?;
+    let states = task_list.states();
+    let photos = task_list
+        .tasks()
+        .await?
+        .into_iter()
+        .find(|t| t.title() == "Take photos")
+        .expect("photos task exists");
+
+    // picked the done column first, then changed our mind
+    let mut updater = photos.update_builder()?;
+    updater.state(Box::new(states[3].clone()));
+    updater.unset_state_update();
+    updater.title("Take more photos".to_owned());
+    updater.send().await?;
+
+    let photos = Retry::spawn(retry_strategy, || async {
+        let photos = photos.refresh().await?;
+        if photos.title() != "Take more photos" {
+            bail!("not yet updated");
+        }
+        Ok(photos)
+    })
+    .await?;
+    assert!(!photos.is_done());
+    assert_eq!(photos.progress_percent(), None);
+    assert_eq!(photos.state_id(), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn task_lists_have_default_states() -> Result<()> {
+    let _ = env_logger::try_init();
+    let (user, sync_state, _engine) =
+        random_user_with_template("task_workflow_default", TMPL).await?;
+    sync_state.await_has_synced_history().await?;
+
+    let retry_strategy = FibonacciBackoff::from_millis(100).map(jitter).take(10);
+    let space = user.spaces().await?.pop().expect("space exists");
+    let mut draft = space.task_list_draft()?;
+    draft.name("Plain list".to_owned());
+    let list_id = draft.send().await?;
+    let task_list = user.task_list(list_id.to_string(), Some(30)).await?;
+
+    let names = task_list
+        .states()
+        .iter()
+        .map(|s| s.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Open", "Done"]);
+
+    // a workflow needs a done state
+    let mut updater = task_list.update_builder()?;
+    updater.add_state("Ideas".to_owned(), false);
+    assert!(updater.send().await.is_err());
+
+    let mut updater = task_list.update_builder()?;
+    updater.add_state("Ideas".to_owned(), false);
+    updater.add_state("Finished".to_owned(), true);
+    updater.send().await?;
+
+    Retry::spawn(retry_strategy, || async {
+        let names = task_list
+            .refresh()
+            .await?
+            .states()
+            .iter()
+            .map(|s| s.name())
+            .collect::<Vec<_>>();
+        if names != ["Ideas", "Finished"] {
+            bail!("states not yet updated");
+        }
+        Ok(())
+    })
+    .await?;
+
+    Ok(())
+}